@@ -0,0 +1,326 @@
+//! Keyframe animations: `@keyframes` rules and the `animation-*` properties that run them
+
+use std::fmt;
+
+use crate::cascade::ComputedStyle;
+use crate::media::{MediaEnvironment, MediaQueryList};
+use crate::property::*;
+use crate::sheet::{Declaration, Stylesheet};
+
+/// A `@keyframes name { from { ... } 50% { ... } to { ... } }` rule
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct KeyframesRule {
+    /// The name that `animation-name` refers to
+    pub name: String,
+    /// The keyframes in the order they appear in the rule, a keyframe with multiple
+    /// selectors (`0%, 100% { }`) results in one `Keyframe` per selector
+    pub keyframes: Vec<Keyframe>,
+    /// Full name of the cascade layer (`@layer`) the rule is in, `None` for unlayered rules
+    pub layer: Option<String>,
+    /// Query lists of the (nested) `@media` blocks the rule is in, see `RuleBlock::media`
+    pub media: Vec<MediaQueryList>,
+}
+
+impl KeyframesRule {
+    /// Returns whether all the `@media` query lists of the rule match the environment
+    pub fn matches_media(&self, env: &MediaEnvironment) -> bool {
+        self.media.iter().all(|queries| queries.matches(env))
+    }
+}
+
+/// Writes the rule as CSS text, `{:#}` writes one declaration per line
+impl fmt::Display for KeyframesRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "@keyframes {}", StyleAnimationName(self.name.clone()))?;
+        if f.alternate() {
+            writeln!(f, " {{")?;
+            for keyframe in self.keyframes.iter() {
+                for line in format!("{:#}", keyframe).lines() {
+                    writeln!(f, "    {}", line)?;
+                }
+            }
+        } else {
+            write!(f, "{{")?;
+            for keyframe in self.keyframes.iter() {
+                write!(f, "{}", keyframe)?;
+            }
+        }
+        write!(f, "}}")
+    }
+}
+
+/// One keyframe of a `@keyframes` rule, i.e. `50% { width: 10px; }`
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct Keyframe {
+    /// Position of the keyframe in the animation, `0%` for `from` and `100%` for `to`
+    pub offset: PercentageValue,
+    /// The declarations of the keyframe (`!important` declarations are ignored).
+    /// `animation-timing-function` sets the easing from this keyframe to the next one.
+    pub declarations: Vec<Declaration>,
+}
+
+impl Keyframe {
+    /// Returns the `animation-timing-function` of the keyframe, if it has one
+    pub fn timing_function(&self) -> Option<TimingFunction> {
+        self.declarations.iter().rev().find_map(|d| match d.property() {
+            Property::AnimationTimingFunction(Value::Exact(functions)) => functions.first().cloned(),
+            _ => None,
+        })
+    }
+}
+
+impl fmt::Display for Keyframe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            writeln!(f, "{} {{", self.offset)?;
+            for declaration in self.declarations.iter() {
+                writeln!(f, "    {};", declaration)?;
+            }
+            write!(f, "}}")
+        } else {
+            write!(f, "{}{{", self.offset)?;
+            for (i, declaration) in self.declarations.iter().enumerate() {
+                if i != 0 {
+                    write!(f, ";")?;
+                }
+                write!(f, "{}", declaration)?;
+            }
+            write!(f, "}}")
+        }
+    }
+}
+
+/// A running keyframe animation: the keyframes of a `@keyframes` rule together
+/// with the values of the `animation-*` properties
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    /// Name of the `@keyframes` rule
+    pub name: String,
+    /// The keyframes, sorted by their offset
+    pub keyframes: Vec<Keyframe>,
+    /// Duration of one iteration in milliseconds
+    pub duration: f32,
+    /// Delay in milliseconds before the animation starts, can be negative
+    pub delay: f32,
+    /// Easing between two keyframes, unless the keyframe sets its own `animation-timing-function`
+    pub timing_function: TimingFunction,
+    pub iteration_count: StyleIterationCount,
+    pub direction: StyleAnimationDirection,
+    pub fill_mode: StyleAnimationFillMode,
+    /// The animation doesn't advance while it is paused: since `sample()` takes the time
+    /// since the start of the animation, the caller has to stop advancing the time.
+    pub play_state: StyleAnimationPlayState,
+}
+
+impl Animation {
+    /// Creates an animation running the keyframes once, with the initial values
+    /// of the `animation-*` properties (so the duration is 0)
+    pub fn new(rule: &KeyframesRule) -> Self {
+        let mut keyframes = rule.keyframes.clone();
+        // stable sort: for keyframes with the same offset, the later one still wins
+        keyframes.sort_by_key(|k| k.offset);
+        Animation {
+            name: rule.name.clone(),
+            keyframes,
+            duration: 0.0,
+            delay: 0.0,
+            timing_function: TimingFunction::default(),
+            iteration_count: StyleIterationCount::default(),
+            direction: StyleAnimationDirection::default(),
+            fill_mode: StyleAnimationFillMode::default(),
+            play_state: StyleAnimationPlayState::default(),
+        }
+    }
+
+    /// Returns the animations of a computed style, one for every name in `animation-name`
+    /// that a `@keyframes` rule exists for (later stylesheets win, see `Stylesheet::find_keyframes`).
+    /// The lists of the other `animation-*` properties are repeated to the number of names.
+    pub fn from_style(style: &ComputedStyle, stylesheets: &[Stylesheet]) -> Vec<Animation> {
+        let names = match style.get(PType::AnimationName) {
+            Property::AnimationName(Value::Exact(names)) => names,
+            _ => return Vec::new(),
+        };
+
+        names
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| {
+                let rule = stylesheets.iter().rev().find_map(|s| s.find_keyframes(&name.0))?;
+                let mut animation = Animation::new(rule);
+                if let Property::AnimationDuration(v) = style.get(PType::AnimationDuration) {
+                    animation.duration = nth_item(v, i).get_ms();
+                }
+                if let Property::AnimationDelay(v) = style.get(PType::AnimationDelay) {
+                    animation.delay = nth_item(v, i).get_ms();
+                }
+                if let Property::AnimationTimingFunction(v) = style.get(PType::AnimationTimingFunction) {
+                    animation.timing_function = nth_item(v, i);
+                }
+                if let Property::AnimationIterationCount(v) = style.get(PType::AnimationIterationCount) {
+                    animation.iteration_count = nth_item(v, i);
+                }
+                if let Property::AnimationDirection(v) = style.get(PType::AnimationDirection) {
+                    animation.direction = nth_item(v, i);
+                }
+                if let Property::AnimationFillMode(v) = style.get(PType::AnimationFillMode) {
+                    animation.fill_mode = nth_item(v, i);
+                }
+                if let Property::AnimationPlayState(v) = style.get(PType::AnimationPlayState) {
+                    animation.play_state = nth_item(v, i);
+                }
+                Some(animation)
+            })
+            .collect()
+    }
+
+    /// Returns the progress (0 to 1) through the keyframes at `time` milliseconds after the
+    /// start of the animation (including the delay), after applying the direction.
+    /// Returns `None` if the animation has no effect at that time, depending on the fill mode.
+    pub fn progress(&self, time: f32) -> Option<f32> {
+        let iterations = match self.iteration_count {
+            StyleIterationCount::Infinite => f32::INFINITY,
+            StyleIterationCount::Count(count) => count.get(),
+        };
+        let active_time = time - self.delay;
+
+        let (iteration, progress) = if active_time < 0.0 {
+            if !matches!(self.fill_mode, StyleAnimationFillMode::Backwards | StyleAnimationFillMode::Both) {
+                return None;
+            }
+            (0.0, 0.0)
+        } else if self.duration <= 0.0 || active_time >= self.duration * iterations {
+            if !matches!(self.fill_mode, StyleAnimationFillMode::Forwards | StyleAnimationFillMode::Both) {
+                return None;
+            }
+            // the end of the last iteration
+            if iterations.is_infinite() {
+                (0.0, 1.0)
+            } else if iterations > 0.0 && libm::fmodf(iterations, 1.0) == 0.0 {
+                (iterations - 1.0, 1.0)
+            } else {
+                (libm::floorf(iterations), libm::fmodf(iterations, 1.0))
+            }
+        } else {
+            let iteration = libm::floorf(active_time / self.duration);
+            (iteration, active_time / self.duration - iteration)
+        };
+
+        let odd = libm::fmodf(iteration, 2.0) == 1.0;
+        let reversed = match self.direction {
+            StyleAnimationDirection::Normal => false,
+            StyleAnimationDirection::Reverse => true,
+            StyleAnimationDirection::Alternate => odd,
+            StyleAnimationDirection::AlternateReverse => !odd,
+        };
+        Some(if reversed { 1.0 - progress } else { progress })
+    }
+
+    /// Returns the values of the animated properties at `time` milliseconds after the start
+    /// of the animation (including the delay), an empty list if the animation has no effect.
+    ///
+    /// Each property is interpolated between the two keyframes around the current progress that
+    /// set it. Before the first (or after the last) keyframe setting a property, the value of that
+    /// keyframe is used. Properties that can't be interpolated switch halfway between the keyframes.
+    pub fn sample(&self, time: f32) -> Vec<Property> {
+        let progress = match self.progress(time) {
+            Some(p) => p,
+            None => return Vec::new(),
+        };
+
+        let mut property_types = Vec::new();
+        for declaration in self.keyframes.iter().flat_map(|k| k.declarations.iter()) {
+            let property_type = declaration.get_type();
+            if !is_animation_property(property_type) && !property_types.contains(&property_type) {
+                property_types.push(property_type);
+            }
+        }
+
+        property_types
+            .into_iter()
+            .filter_map(|property_type| {
+                // offset, value and easing of the keyframes that set the property
+                let frames = self
+                    .keyframes
+                    .iter()
+                    .filter_map(|k| {
+                        let declaration = k.declarations.iter().rev().find(|d| d.get_type() == property_type)?;
+                        Some((k.offset.get() / 100.0, declaration.property(), k.timing_function()))
+                    })
+                    .collect::<Vec<_>>();
+
+                let next = frames.iter().position(|f| f.0 > progress).unwrap_or(frames.len());
+                let (from, to) = match (next.checked_sub(1).map(|i| &frames[i]), frames.get(next)) {
+                    (Some(from), Some(to)) => (from, to),
+                    (Some(only), None) | (None, Some(only)) => return Some(only.1.clone()),
+                    (None, None) => return None,
+                };
+                let local_progress = (progress - from.0) / (to.0 - from.0);
+                let eased = from.2.as_ref().unwrap_or(&self.timing_function).apply(local_progress);
+                from.1.interpolate(to.1, eased)
+            })
+            .collect()
+    }
+}
+
+/// Returns the `i`-th item of a list of the `animation-*` or `transition-*` properties,
+/// repeating the list if it is too short
+pub(crate) fn nth_item<T: Clone + Default>(list: &Value<Vec<T>>, i: usize) -> T {
+    match list {
+        Value::Exact(items) if !items.is_empty() => items[i % items.len()].clone(),
+        _ => T::default(),
+    }
+}
+
+/// Returns whether the property configures animations (and can't be animated itself)
+fn is_animation_property(property_type: PType) -> bool {
+    matches!(
+        property_type,
+        PType::AnimationName
+            | PType::AnimationDuration
+            | PType::AnimationTimingFunction
+            | PType::AnimationDelay
+            | PType::AnimationIterationCount
+            | PType::AnimationDirection
+            | PType::AnimationFillMode
+            | PType::AnimationPlayState
+    )
+}
+
+#[test]
+fn test_sample_animation() {
+    let stylesheet = Stylesheet::parse(
+        "@keyframes slide { from { width: 0px; color: #000000; display: none; } \
+        50% { height: 20px; animation-timing-function: step-end; } \
+        25%, 75% { height: 10px; } \
+        to { width: 100px; color: #ffffff; display: block; } }",
+    )
+    .unwrap();
+    let mut animation = Animation::new(stylesheet.find_keyframes("slide").unwrap());
+    animation.duration = 1000.0;
+    animation.timing_function = TimingFunction::Linear;
+
+    let width = |properties: &[Property]| properties.iter().find(|p| p.get_type() == PType::Width).map(|p| p.to_string());
+    let sample = animation.sample(250.0);
+    assert_eq!(
+        sample.iter().map(|p| p.to_string()).collect::<Vec<_>>(),
+        vec!["width: 25px", "color: rgba(64, 64, 64, 1)", "display: none", "height: 10px"]
+    );
+    assert_eq!(width(&animation.sample(600.0)).as_deref(), Some("width: 60px"));
+    // the timing function of a keyframe applies until the next keyframe
+    assert_eq!(animation.sample(400.0)[3].to_string(), "height: 16px");
+    assert_eq!(animation.sample(600.0)[3].to_string(), "height: 20px");
+    assert_eq!(animation.sample(900.0)[2].to_string(), "display: block");
+    // not running before the delay and after the end without a fill mode
+    assert_eq!(animation.sample(-1.0), Vec::new());
+    assert_eq!(animation.sample(1000.0), Vec::new());
+
+    animation.iteration_count = StyleIterationCount::Count(FloatValue::new(2.5));
+    animation.direction = StyleAnimationDirection::Alternate;
+    animation.fill_mode = StyleAnimationFillMode::Both;
+    animation.delay = 500.0;
+    assert_eq!(width(&animation.sample(0.0)).as_deref(), Some("width: 0px"));
+    assert_eq!(width(&animation.sample(1700.0)).as_deref(), Some("width: 80px"));
+    assert_eq!(width(&animation.sample(2200.0)).as_deref(), Some("width: 30px"));
+    // stays at the end of the third (half) iteration
+    assert_eq!(width(&animation.sample(5000.0)).as_deref(), Some("width: 50px"));
+}
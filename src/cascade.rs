@@ -0,0 +1,303 @@
+//! Computes the style of the nodes of a UI tree from a `Stylesheet` (the "cascade")
+
+use std::collections::BTreeMap;
+
+use crate::matching::Element;
+use crate::property::{PType, Property};
+use crate::sheet::{get_specificity, sort_layers, Origin, PseudoElement, Specificity, Stylesheet};
+
+/// The final style of one node: the value of every property (`PType::ALL`), after
+/// the matching rules have been applied and `initial` / `inherit` have been resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComputedStyle {
+    properties: BTreeMap<PType, Property>,
+    /// Styles of the pseudo-elements (`::before`, etc.) that at least one rule applies to
+    pseudo_elements: BTreeMap<PseudoElement, ComputedStyle>,
+}
+
+/// Order in which declarations are applied, see `ComputedStyle::compute`
+type Precedence = (u8, usize, Specificity, (usize, usize));
+
+impl Default for ComputedStyle {
+    fn default() -> Self {
+        Self::initial()
+    }
+}
+
+impl ComputedStyle {
+    /// Returns the style with every property set to its initial value
+    pub fn initial() -> Self {
+        ComputedStyle {
+            properties: PType::ALL.iter().map(|t| (*t, t.initial_value())).collect(),
+            pseudo_elements: BTreeMap::new(),
+        }
+    }
+
+    /// Computes the style of a single element. `parent_style` is the computed style of the
+    /// parent of the element (`None` for the root), which inherited properties are taken from.
+    ///
+    /// Declarations of the matching rules are applied in the order of (lowest precedence first):
+    ///
+    /// 1. the origin of the stylesheet and whether the declaration is `!important` (see `Origin`)
+    /// 2. the cascade layer of the rule: unlayered rules win over layered rules, later layers
+    ///    over earlier layers - the other way around for `!important` declarations
+    /// 3. the specificity of the rule
+    /// 4. the order in which they appear in the stylesheets (`RuleBlock::source_index`)
+    ///
+    /// Layers with the same name in stylesheets of the same origin are the same layer.
+    /// Dynamic properties use their default value.
+    ///
+    /// Rules with a pseudo-element (`p::before`) are applied to the style of the pseudo-element
+    /// instead, which inherits from the style of the element, see `ComputedStyle::pseudo_element`.
+    ///
+    /// The `@media` queries of the rules are not evaluated here, all rules are applied:
+    /// use `Stylesheet::evaluate_media()` to select the rules for a `MediaEnvironment` first.
+    pub fn compute<E: Element>(stylesheets: &[Stylesheet], element: &E, parent_style: Option<&ComputedStyle>) -> Self {
+        let mut declarations = Vec::new();
+        let mut pseudo_declarations = BTreeMap::<PseudoElement, Vec<_>>::new();
+        for (sheet_index, stylesheet) in stylesheets.iter().enumerate() {
+            let layers = sort_layers(
+                stylesheets
+                    .iter()
+                    .filter(|s| s.origin == stylesheet.origin)
+                    .flat_map(|s| s.layers.iter().map(|l| l.as_str())),
+            );
+            for rule in stylesheet.rules.iter() {
+                if !rule.path.matches(element) {
+                    continue;
+                }
+                let layer = match &rule.layer {
+                    Some(name) => layers.iter().position(|l| l == name).unwrap_or(layers.len()),
+                    None => layers.len(),
+                };
+                let specificity = get_specificity(&rule.path);
+                let declarations = match rule.path.pseudo_element {
+                    Some(pseudo_element) => pseudo_declarations.entry(pseudo_element).or_default(),
+                    None => &mut declarations,
+                };
+                let normal = rule.declarations.iter().map(|d| (d, false));
+                let important = rule.important_declarations.iter().map(|d| (d, true));
+                for (declaration, important) in normal.chain(important) {
+                    let precedence = (
+                        origin_precedence(stylesheet.origin, important),
+                        if important { layers.len() - layer } else { layer },
+                        specificity,
+                        (sheet_index, rule.source_index),
+                    );
+                    declarations.push((precedence, declaration.property()));
+                }
+            }
+        }
+
+        let mut style = ComputedStyle { properties: cascade(declarations, parent_style), pseudo_elements: BTreeMap::new() };
+        for (pseudo_element, declarations) in pseudo_declarations {
+            let properties = cascade(declarations, Some(&style));
+            style.pseudo_elements.insert(pseudo_element, ComputedStyle { properties, pseudo_elements: BTreeMap::new() });
+        }
+        style
+    }
+
+    /// Returns the style of the pseudo-element, `None` if no rule applies to it.
+    /// Note that `::before` and `::after` only generate content if `content` is not `none`.
+    pub fn pseudo_element(&self, pseudo_element: PseudoElement) -> Option<&ComputedStyle> {
+        self.pseudo_elements.get(&pseudo_element)
+    }
+
+    /// Returns the computed value of the given property
+    pub fn get(&self, property_type: PType) -> &Property {
+        &self.properties[&property_type]
+    }
+
+    /// Iterates over the computed values of all properties, in the order of `PType::ALL`
+    pub fn iter(&self) -> impl Iterator<Item = &Property> {
+        self.properties.values()
+    }
+}
+
+/// Applies the declarations in the order of their precedence and resolves `inherit` / `initial`
+/// and the inheritance from the parent style
+fn cascade(mut declarations: Vec<(Precedence, &Property)>, parent_style: Option<&ComputedStyle>) -> BTreeMap<PType, Property> {
+    // stable sort: keeps the order of the declarations inside of a rule
+    declarations.sort_by_key(|d| d.0);
+
+    let mut cascaded = BTreeMap::new();
+    for (_, property) in declarations {
+        cascaded.insert(property.get_type(), property.clone());
+    }
+
+    let inherited = |property_type: PType| match parent_style {
+        Some(parent) => parent.get(property_type).clone(),
+        None => property_type.initial_value(),
+    };
+
+    PType::ALL
+        .iter()
+        .map(|&property_type| {
+            let initial = property_type.initial_value();
+            let property = match cascaded.remove(&property_type) {
+                Some(p) if p.is_inherit() => inherited(property_type),
+                Some(p) if p.is_initial() => initial,
+                Some(p) => p,
+                None if initial.is_inheritable() => inherited(property_type),
+                None => initial,
+            };
+            (property_type, property)
+        })
+        .collect()
+}
+
+/// Returns the precedence of a declaration depending on its origin and importance
+/// (higher wins): normal declarations follow the order of `Origin`, important declarations the reverse
+fn origin_precedence(origin: Origin, important: bool) -> u8 {
+    let rank = match origin {
+        Origin::UserAgent => 0,
+        Origin::User => 1,
+        Origin::Author => 2,
+    };
+    if important { 5 - rank } else { rank }
+}
+
+/// Computes the style of every node in the tree below (and including) `root`, see `ComputedStyle::compute`.
+/// Returns the nodes and their styles in document order (depth-first, parents before children).
+pub fn compute_styles<E: Element>(stylesheets: &[Stylesheet], root: E) -> Vec<(E, ComputedStyle)> {
+    let mut styles = Vec::new();
+    compute_subtree(stylesheets, root, None, &mut styles);
+    styles
+}
+
+fn compute_subtree<E: Element>(stylesheets: &[Stylesheet], element: E, parent_style: Option<&ComputedStyle>, styles: &mut Vec<(E, ComputedStyle)>) {
+    let style = ComputedStyle::compute(stylesheets, &element, parent_style);
+    let mut child = element.first_child();
+    styles.push((element, style.clone()));
+    while let Some(current) = child {
+        child = current.next_sibling();
+        compute_subtree(stylesheets, current, Some(&style), styles);
+    }
+}
+
+#[test]
+fn test_compute_styles() {
+    use crate::matching::{TestElement, TestNode};
+    use crate::property::*;
+    use crate::sheet::NodeTypeTag::*;
+
+    // <body> <div id="main" class="a"> <p class="x"/> <p/> </div> </body>
+    let nodes: &[TestNode] = &[
+        (Body, None, &[], &[], None),
+        (Div, Some("main"), &["a"], &[], Some(0)),
+        (P, None, &["x"], &[], Some(1)),
+        (P, None, &[], &[], Some(1)),
+    ];
+    let stylesheet = Stylesheet::parse("
+        #main { color: green; }
+        div.a { color: blue; width: 10px; }
+        body { font-size: 20px; width: 50px; }
+        p { padding-top: 1px; width: inherit; }
+        p { padding-top: 2px; }
+        .x { color: initial; font-size: 12px; }
+        div p { padding-top: 3px; }
+    ").unwrap();
+
+    let styles = compute_styles(std::slice::from_ref(&stylesheet), TestElement { nodes, index: 0, states: &[] });
+    assert_eq!(styles.iter().map(|(e, _)| e.index).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+    let get = |index: usize, property_type: PType| styles[index].1.get(property_type).clone();
+
+    let color = |c: ColorU| Property::TextColor(Value::Exact(StyleTextColor(c)));
+    let font_size = |px: f32| Property::FontSize(Value::Exact(StyleFontSize(PixelValue::px(px))));
+    let width = |px: f32| Property::Width(Value::Exact(LayoutWidth(PixelValue::px(px))));
+
+    // the id selector wins, although the class rule comes later
+    assert_eq!(get(1, PType::TextColor), color(ColorU { r: 0, g: 128, b: 0, a: 255 }));
+    // inherited from the parent, unless set to `initial`
+    assert_eq!(get(3, PType::TextColor), get(1, PType::TextColor));
+    assert_eq!(get(2, PType::TextColor), color(ColorU::BLACK));
+    assert_eq!(get(0, PType::TextColor), color(ColorU::BLACK));
+    assert_eq!(get(1, PType::FontSize), font_size(20.0));
+    assert_eq!(get(2, PType::FontSize), font_size(12.0));
+    // not inherited unless explicitly set to `inherit`
+    assert_eq!(get(1, PType::Width), width(10.0));
+    assert_eq!(get(2, PType::Width), width(10.0));
+    assert_eq!(get(0, PType::Height), Property::Height(Value::Auto));
+    assert_eq!(get(1, PType::PaddingTop), PType::PaddingTop.initial_value());
+    // more specific rule wins, the later rule wins on equal specificity
+    assert_eq!(get(3, PType::PaddingTop), Property::PaddingTop(Value::Exact(LayoutPaddingTop(PixelValue::px(3.0)))));
+
+    assert_eq!(styles[0].1.iter().count(), PType::ALL.len());
+    assert_eq!(ComputedStyle::compute(&[], &styles[0].0, None), ComputedStyle::initial());
+}
+
+#[test]
+fn test_cascade_origins_and_layers() {
+    use crate::matching::{TestElement, TestNode};
+    use crate::property::*;
+    use crate::sheet::NodeTypeTag::*;
+
+    let nodes: &[TestNode] = &[(Div, Some("main"), &["a"], &[], None)];
+    let element = TestElement { nodes, index: 0, states: &[] };
+
+    let mut widget_theme = Stylesheet::parse("
+        #main { width: 1px; height: 1px; min-width: 1px !important; }
+    ").unwrap();
+    widget_theme.origin = Origin::UserAgent;
+    let app = Stylesheet::parse("
+        @layer reset, components;
+        div { width: 2px; min-width: 2px !important; }
+        @layer components { #main.a { height: 3px; max-width: 3px !important; } }
+        @layer reset { #main.a { height: 4px; max-width: 4px !important; } }
+        div { max-height: 5px !important; }
+        div { max-height: 6px; }
+    ").unwrap();
+
+    let style = ComputedStyle::compute(&[widget_theme, app], &element, None);
+    let px = |px: f32| PixelValue::px(px);
+    // author styles override user agent styles, regardless of the specificity
+    assert_eq!(style.get(PType::Width), &Property::Width(Value::Exact(LayoutWidth(px(2.0)))));
+    // ... except for important user agent declarations
+    assert_eq!(style.get(PType::MinWidth), &Property::MinWidth(Value::Exact(LayoutMaxWidth(px(1.0)))));
+    // later layers win, for important declarations earlier layers win
+    assert_eq!(style.get(PType::Height), &Property::Height(Value::Exact(LayoutHeight(px(3.0)))));
+    assert_eq!(style.get(PType::MaxWidth), &Property::MaxWidth(Value::Exact(LayoutMaxWidth(px(4.0)))));
+    // important declarations win over later normal declarations
+    assert_eq!(style.get(PType::MaxHeight), &Property::MaxHeight(Value::Exact(LayoutMaxHeight(px(5.0)))));
+
+    // equal specificity: the later rule wins, even after the rules have been sorted
+    let mut equal_weight = Stylesheet::parse("*.a { width: 1px; } div { width: 3px; } .a { width: 2px; }").unwrap();
+    equal_weight.sort_by_specificity();
+    let style = ComputedStyle::compute(&[equal_weight], &element, None);
+    assert_eq!(style.get(PType::Width), &Property::Width(Value::Exact(LayoutWidth(px(2.0)))));
+}
+
+#[test]
+fn test_compute_pseudo_elements() {
+    use crate::matching::{TestElement, TestNode};
+    use crate::property::*;
+    use crate::sheet::NodeTypeTag::*;
+
+    let nodes: &[TestNode] = &[(P, None, &["x"], &[], None)];
+    let element = TestElement { nodes, index: 0, states: &[] };
+    let stylesheet = Stylesheet::parse("
+        p { color: red; width: 10px; }
+        p::before { content: \"> \" counter(item) attr(title); }
+        .x::before { width: 5px; }
+        p:after { content: normal; }
+    ").unwrap();
+
+    let style = ComputedStyle::compute(&[stylesheet], &element, None);
+    assert_eq!(style.get(PType::Content), &Property::Content(Value::None));
+    assert_eq!(style.get(PType::Width), &Property::Width(Value::Exact(LayoutWidth(PixelValue::px(10.0)))));
+
+    let before = style.pseudo_element(PseudoElement::Before).unwrap();
+    assert_eq!(before.get(PType::Content), &Property::Content(Value::Exact(vec![
+        StyleContent::String("> ".to_string()),
+        StyleContent::Counter { name: "item".to_string(), style: None },
+        StyleContent::Attr("title".to_string()),
+    ])));
+    // inherited from the element
+    assert_eq!(before.get(PType::TextColor), style.get(PType::TextColor));
+    assert_eq!(before.get(PType::Width), &Property::Width(Value::Exact(LayoutWidth(PixelValue::px(5.0)))));
+
+    let after = style.pseudo_element(PseudoElement::After).unwrap();
+    assert_eq!(after.get(PType::Content), &Property::Content(Value::None));
+    assert_eq!(after.get(PType::Width), &PType::Width.initial_value());
+    assert!(style.pseudo_element(PseudoElement::Selection).is_none());
+}
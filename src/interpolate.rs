@@ -0,0 +1,890 @@
+//! Interpolation of property values for animations and transitions
+
+use crate::property::*;
+
+/// A value that can be animated smoothly between two values of its type
+pub trait Interpolate: Sized {
+    /// Returns the value at `t` between `self` (`t = 0`) and `other` (`t = 1`). `t` can be
+    /// outside of `[0, 1]` for timing functions that overshoot. Returns `None` if the two
+    /// values can't be interpolated, i.e. gradients with a different number of color stops.
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self>;
+}
+
+/// Calls `Interpolate::interpolate`: some types also have an infallible inherent `interpolate`
+/// method, which would take precedence over the trait method
+fn interpolate<T: Interpolate>(from: &T, to: &T, t: f32) -> Option<T> {
+    from.interpolate(to, t)
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+/// Implements `Interpolate` with the infallible inherent `interpolate` method of the type
+macro_rules! impl_interpolate_inherent {
+    ($($ty:ident),+ $(,)?) => {
+        $(
+            impl Interpolate for $ty {
+                fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+                    Some($ty::interpolate(self, other, t))
+                }
+            }
+        )+
+    };
+}
+
+impl_interpolate_inherent!(FloatValue, PercentageValue, ColorU, StyleTextColor);
+
+/// Lengths with different metrics are interpolated in pixels. A percentage can't be
+/// resolved without the size it refers to, so it can only be interpolated with another percentage.
+impl Interpolate for PixelValue {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        let is_percent = |value: &PixelValue| value.metric == SizeMetric::Percent;
+        if self.metric != other.metric && (is_percent(self) || is_percent(other)) {
+            return None;
+        }
+        Some(PixelValue::interpolate(self, other, t))
+    }
+}
+
+impl Interpolate for AngleValue {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        if self.metric == other.metric {
+            Some(AngleValue { metric: self.metric, number: interpolate(&self.number, &other.number, t)? })
+        } else {
+            Some(AngleValue::deg(lerp(self.to_degrees_unclamped(), other.to_degrees_unclamped(), t)))
+        }
+    }
+}
+
+/// Implements `Interpolate` for types wrapping a single interpolatable value (i.e. `LayoutWidth(PixelValue)`)
+macro_rules! impl_interpolate_newtype {
+    ($($ty:ident),+ $(,)?) => {
+        $(
+            impl Interpolate for $ty {
+                fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+                    interpolate(&self.0, &other.0, t).map($ty)
+                }
+            }
+        )+
+    };
+}
+
+impl_interpolate_newtype!(
+    StyleFontSize, StyleLetterSpacing, StyleLineHeight, StyleWordSpacing, StyleTabWidth,
+    LayoutWidth, LayoutHeight, LayoutMinWidth, LayoutMinHeight, LayoutMaxWidth, LayoutMaxHeight,
+    LayoutTop, LayoutRight, LayoutLeft, LayoutBottom, LayoutFlexGrow, LayoutFlexShrink,
+    LayoutPaddingTop, LayoutPaddingLeft, LayoutPaddingRight, LayoutPaddingBottom,
+    LayoutMarginTop, LayoutMarginLeft, LayoutMarginRight, LayoutMarginBottom,
+    StyleBorderTopLeftRadius, StyleBorderTopRightRadius, StyleBorderBottomLeftRadius, StyleBorderBottomRightRadius,
+    StyleBorderTopColor, StyleBorderRightColor, StyleBorderLeftColor, StyleBorderBottomColor,
+    LayoutBorderTopWidth, LayoutBorderRightWidth, LayoutBorderLeftWidth, LayoutBorderBottomWidth,
+    StyleOpacity,
+);
+
+impl Interpolate for StyleTransformOrigin {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        Some(StyleTransformOrigin { x: interpolate(&self.x, &other.x, t)?, y: interpolate(&self.y, &other.y, t)? })
+    }
+}
+
+impl Interpolate for StylePerspectiveOrigin {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        Some(StylePerspectiveOrigin { x: interpolate(&self.x, &other.x, t)?, y: interpolate(&self.y, &other.y, t)? })
+    }
+}
+
+/// Only shadows with the same clip mode (`inset` or not) can be interpolated
+impl Interpolate for StyleBoxShadow {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        if self.clip_mode != other.clip_mode {
+            return None;
+        }
+        Some(StyleBoxShadow {
+            offset: (interpolate(&self.offset.0, &other.offset.0, t)?, interpolate(&self.offset.1, &other.offset.1, t)?),
+            color: interpolate(&self.color, &other.color, t)?,
+            blur_radius: interpolate(&self.blur_radius, &other.blur_radius, t)?,
+            spread_radius: interpolate(&self.spread_radius, &other.spread_radius, t)?,
+            clip_mode: self.clip_mode,
+        })
+    }
+}
+
+/// Only filters of the same kind can be interpolated, filters without parameters
+/// (or with keywords as parameters) only if they are equal
+impl Interpolate for StyleFilter {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        use self::StyleFilter::*;
+        match (self, other) {
+            (Flood(a), Flood(b)) => Some(Flood(interpolate(a, b, t)?)),
+            (Blur(a), Blur(b)) => Some(Blur(StyleBlur { width: interpolate(&a.width, &b.width, t)?, height: interpolate(&a.height, &b.height, t)? })),
+            (Opacity(a), Opacity(b)) => Some(Opacity(interpolate(a, b, t)?)),
+            (ColorMatrix(a), ColorMatrix(b)) => {
+                let mut matrix = a.matrix;
+                for (value, target) in matrix.iter_mut().zip(b.matrix.iter()) {
+                    *value = interpolate(value, target, t)?;
+                }
+                Some(ColorMatrix(StyleColorMatrix { matrix }))
+            }
+            (DropShadow(a), DropShadow(b)) => Some(DropShadow(a.interpolate(b, t)?)),
+            (Offset(a), Offset(b)) => Some(Offset(StyleFilterOffset { x: interpolate(&a.x, &b.x, t)?, y: interpolate(&a.y, &b.y, t)? })),
+            (Composite(StyleCompositeFilter::Arithmetic(a)), Composite(StyleCompositeFilter::Arithmetic(b))) => {
+                let mut values = *a;
+                for (value, target) in values.iter_mut().zip(b.iter()) {
+                    *value = interpolate(value, target, t)?;
+                }
+                Some(Composite(StyleCompositeFilter::Arithmetic(values)))
+            }
+            (a, b) if a == b => Some(a.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Keywords are interpolated as the percentages they stand for (`left` is `0%`, `center` is `50%`)
+impl Interpolate for StyleBackgroundPosition {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        use self::BackgroundPositionHorizontal as H;
+        use self::BackgroundPositionVertical as V;
+
+        let horizontal = |h: BackgroundPositionHorizontal| match h {
+            H::Left => PixelValue::const_percent(0),
+            H::Center => PixelValue::const_percent(50),
+            H::Right => PixelValue::const_percent(100),
+            H::Exact(v) => v,
+        };
+        let vertical = |v: BackgroundPositionVertical| match v {
+            V::Top => PixelValue::const_percent(0),
+            V::Center => PixelValue::const_percent(50),
+            V::Bottom => PixelValue::const_percent(100),
+            V::Exact(v) => v,
+        };
+        Some(StyleBackgroundPosition {
+            horizontal: if self.horizontal == other.horizontal {
+                self.horizontal
+            } else {
+                H::Exact(interpolate(&horizontal(self.horizontal), &horizontal(other.horizontal), t)?)
+            },
+            vertical: if self.vertical == other.vertical {
+                self.vertical
+            } else {
+                V::Exact(interpolate(&vertical(self.vertical), &vertical(other.vertical), t)?)
+            },
+        })
+    }
+}
+
+impl Interpolate for StyleBackgroundSize {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        match (self, other) {
+            (StyleBackgroundSize::ExactSize(w1, h1), StyleBackgroundSize::ExactSize(w2, h2)) => {
+                Some(StyleBackgroundSize::ExactSize(interpolate(w1, w2, t)?, interpolate(h1, h2, t)?))
+            }
+            (a, b) if a == b => Some(*a),
+            _ => None,
+        }
+    }
+}
+
+impl Interpolate for NormalizedLinearColorStop {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        Some(NormalizedLinearColorStop { offset: interpolate(&self.offset, &other.offset, t)?, color: interpolate(&self.color, &other.color, t)? })
+    }
+}
+
+impl Interpolate for NormalizedRadialColorStop {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        Some(NormalizedRadialColorStop { angle: interpolate(&self.angle, &other.angle, t)?, color: interpolate(&self.color, &other.color, t)? })
+    }
+}
+
+/// Angles are interpolated, `to right` / `to bottom` style directions only if they are equal
+impl Interpolate for Direction {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        match (self, other) {
+            (Direction::Angle(a), Direction::Angle(b)) => Some(Direction::Angle(a.interpolate(b, t)?)),
+            (a, b) if a == b => Some(*a),
+            _ => None,
+        }
+    }
+}
+
+/// Colors are interpolated, gradients of the same kind with the same number of color stops
+/// interpolate their stops (and directions / positions), images only stay the same
+impl Interpolate for StyleBackgroundContent {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        use self::StyleBackgroundContent::*;
+        match (self, other) {
+            (Color(a), Color(b)) => Some(Color(interpolate(a, b, t)?)),
+            (LinearGradient(a), LinearGradient(b)) if a.extend_mode == b.extend_mode => {
+                Some(LinearGradient(crate::property::LinearGradient {
+                    direction: a.direction.interpolate(&b.direction, t)?,
+                    extend_mode: a.extend_mode,
+                    stops: a.stops.interpolate(&b.stops, t)?,
+                }))
+            }
+            (RadialGradient(a), RadialGradient(b)) if a.extend_mode == b.extend_mode && a.shape == b.shape && a.size == b.size => {
+                Some(RadialGradient(crate::property::RadialGradient {
+                    shape: a.shape,
+                    size: a.size.clone(),
+                    position: a.position.interpolate(&b.position, t)?,
+                    extend_mode: a.extend_mode,
+                    stops: a.stops.interpolate(&b.stops, t)?,
+                }))
+            }
+            (ConicGradient(a), ConicGradient(b)) if a.extend_mode == b.extend_mode => {
+                Some(ConicGradient(crate::property::ConicGradient {
+                    extend_mode: a.extend_mode,
+                    center: a.center.interpolate(&b.center, t)?,
+                    angle: a.angle.interpolate(&b.angle, t)?,
+                    stops: a.stops.interpolate(&b.stops, t)?,
+                }))
+            }
+            (a, b) if a == b => Some(a.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Lists are interpolated item by item, they must have the same length
+impl<T: Interpolate> Interpolate for Vec<T> {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        if self.len() != other.len() {
+            return None;
+        }
+        self.iter().zip(other.iter()).map(|(a, b)| a.interpolate(b, t)).collect()
+    }
+}
+
+/// Only exact values are interpolated, keywords (`auto`, `none`) only if they are equal
+impl<T: Interpolate + Clone> Interpolate for Value<T> {
+    fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        match (self, other) {
+            (Value::Exact(a), Value::Exact(b)) => a.interpolate(b, t).map(Value::Exact),
+            (Value::Auto, Value::Auto) => Some(Value::Auto),
+            (Value::None, Value::None) => Some(Value::None),
+            _ => None,
+        }
+    }
+}
+
+/// Interpolates two lists of transform functions (CSS Transforms Level 2):
+///
+/// - the shorter list is padded with the identity functions of the longer list
+/// - pairs of functions with a common primitive (i.e. `translateX()` and `translate()`) are
+///   interpolated pairwise, rotations around different axes and `matrix()` pairs are interpolated
+///   via their decomposed matrices
+/// - from the first pair without a common primitive, the remaining functions of each list are
+///   combined into a 4x4 matrix, the two matrices are decomposed into translation, scale, skew,
+///   perspective and rotation (as a quaternion), interpolated and recomposed
+///
+/// Percentages (which need the size of the element) are resolved as `0px` in matrices.
+/// Skew angles are stored in degrees (see `StyleTransform::Skew`).
+/// Returns `None` if a matrix can't be decomposed (i.e. `scale(0)`).
+pub fn interpolate_transforms(from: &[StyleTransform], to: &[StyleTransform], t: f32) -> Option<Vec<StyleTransform>> {
+    let len = from.len().max(to.len());
+    let from = (0..len).map(|i| from.get(i).copied().unwrap_or_else(|| identity_transform(&to[i]))).collect::<Vec<_>>();
+    let to = (0..len).map(|i| to.get(i).copied().unwrap_or_else(|| identity_transform(&from[i]))).collect::<Vec<_>>();
+
+    let mut result = Vec::with_capacity(len);
+    for i in 0..len {
+        match interpolate_transform_pair(&from[i], &to[i], t) {
+            Some(transform) => result.push(transform),
+            None => {
+                let both_2d = from[i..].iter().chain(to[i..].iter()).all(is_2d_transform);
+                result.push(interpolate_matrices(&transforms_to_matrix(&from[i..]), &transforms_to_matrix(&to[i..]), t, both_2d)?);
+                break;
+            }
+        }
+    }
+    Some(result)
+}
+
+/// Returns the function of the same kind that doesn't change anything, used to pad the shorter list
+fn identity_transform(transform: &StyleTransform) -> StyleTransform {
+    use self::StyleTransform::*;
+    let zero = PixelValue::zero();
+    let one = PercentageValue::const_new(100);
+    let no_skew = PercentageValue::const_new(0);
+    match transform {
+        Matrix(_) => Matrix(StyleTransformMatrix2D {
+            a: PixelValue::const_px(1), b: zero, c: zero, d: PixelValue::const_px(1), tx: zero, ty: zero,
+        }),
+        Matrix3D(_) => matrix_to_transform(&IDENTITY_MATRIX, false),
+        Perspective(_) => StyleTransform::PERSPECTIVE_NONE,
+        Translate(_) => Translate(StyleTransformTranslate2D { x: zero, y: zero }),
+        Translate3D(_) => Translate3D(StyleTransformTranslate3D { x: zero, y: zero, z: zero }),
+        TranslateX(_) => TranslateX(zero),
+        TranslateY(_) => TranslateY(zero),
+        TranslateZ(_) => TranslateZ(zero),
+        Rotate(_) => Rotate(AngleValue::zero()),
+        Rotate3D(r) => Rotate3D(StyleTransformRotate3D { angle: AngleValue::zero(), ..*r }),
+        RotateX(_) => RotateX(AngleValue::zero()),
+        RotateY(_) => RotateY(AngleValue::zero()),
+        RotateZ(_) => RotateZ(AngleValue::zero()),
+        Scale(_) => Scale(StyleTransformScale2D { x: one, y: one }),
+        Scale3D(_) => Scale3D(StyleTransformScale3D { x: one, y: one, z: one }),
+        ScaleX(_) => ScaleX(one),
+        ScaleY(_) => ScaleY(one),
+        ScaleZ(_) => ScaleZ(one),
+        Skew(_) => Skew(StyleTransformSkew2D { x: no_skew, y: no_skew }),
+        SkewX(_) => SkewX(no_skew),
+        SkewY(_) => SkewY(no_skew),
+    }
+}
+
+/// Interpolates two functions with a common primitive, `None` if they don't have one
+fn interpolate_transform_pair(from: &StyleTransform, to: &StyleTransform, t: f32) -> Option<StyleTransform> {
+    use self::StyleTransform::*;
+
+    let is_matrix = |transform: &StyleTransform| matches!(transform, Matrix(_) | Matrix3D(_) | Perspective(_));
+    match (from, to) {
+        (Perspective(_), Perspective(_)) => return Some(interpolate_perspective(from, to, t)),
+        (a, b) if is_matrix(a) && is_matrix(b) => {
+            let both_2d = is_2d_transform(a) && is_2d_transform(b);
+            return interpolate_matrices(&transform_to_matrix(a), &transform_to_matrix(b), t, both_2d);
+        }
+        _ => {}
+    }
+
+    if let (Some(a), Some(b)) = (translate_components(from), translate_components(to)) {
+        let (x, y, z) = (interpolate(&a.0, &b.0, t)?, interpolate(&a.1, &b.1, t)?, interpolate(&a.2, &b.2, t)?);
+        return Some(match (from, to) {
+            (TranslateX(_), TranslateX(_)) => TranslateX(x),
+            (TranslateY(_), TranslateY(_)) => TranslateY(y),
+            (TranslateZ(_), TranslateZ(_)) => TranslateZ(z),
+            _ if is_2d_transform(from) && is_2d_transform(to) => Translate(StyleTransformTranslate2D { x, y }),
+            _ => Translate3D(StyleTransformTranslate3D { x, y, z }),
+        });
+    }
+
+    if let (Some(a), Some(b)) = (scale_components(from), scale_components(to)) {
+        let (x, y, z) = (interpolate(&a.0, &b.0, t)?, interpolate(&a.1, &b.1, t)?, interpolate(&a.2, &b.2, t)?);
+        return Some(match (from, to) {
+            (ScaleX(_), ScaleX(_)) => ScaleX(x),
+            (ScaleY(_), ScaleY(_)) => ScaleY(y),
+            (ScaleZ(_), ScaleZ(_)) => ScaleZ(z),
+            _ if is_2d_transform(from) && is_2d_transform(to) => Scale(StyleTransformScale2D { x, y }),
+            _ => Scale3D(StyleTransformScale3D { x, y, z }),
+        });
+    }
+
+    let skew_components = |transform: &StyleTransform| match transform {
+        Skew(s) => Some((s.x, s.y)),
+        SkewX(x) => Some((*x, PercentageValue::const_new(0))),
+        SkewY(y) => Some((PercentageValue::const_new(0), *y)),
+        _ => None,
+    };
+    if let (Some(a), Some(b)) = (skew_components(from), skew_components(to)) {
+        let (x, y) = (interpolate(&a.0, &b.0, t)?, interpolate(&a.1, &b.1, t)?);
+        return Some(match (from, to) {
+            (SkewX(_), SkewX(_)) => SkewX(x),
+            (SkewY(_), SkewY(_)) => SkewY(y),
+            _ => Skew(StyleTransformSkew2D { x, y }),
+        });
+    }
+
+    match (from, to) {
+        (Rotate(a), Rotate(b)) | (Rotate(a), RotateZ(b)) | (RotateZ(a), Rotate(b)) => Some(Rotate(a.interpolate(b, t)?)),
+        (RotateZ(a), RotateZ(b)) => Some(RotateZ(a.interpolate(b, t)?)),
+        (RotateX(a), RotateX(b)) => Some(RotateX(a.interpolate(b, t)?)),
+        (RotateY(a), RotateY(b)) => Some(RotateY(a.interpolate(b, t)?)),
+        _ => {
+            let ((from_axis, from_angle), (to_axis, to_angle)) = (rotate_components(from)?, rotate_components(to)?);
+            let same_axis = from_axis.iter().zip(to_axis.iter()).all(|(a, b)| (a - b).abs() < 1e-6);
+            if same_axis {
+                let axis = |i: usize| PercentageValue::new(from_axis[i] as f32 * 100.0);
+                let angle = from_angle.interpolate(&to_angle, t)?;
+                Some(Rotate3D(StyleTransformRotate3D { x: axis(0), y: axis(1), z: axis(2), angle }))
+            } else {
+                // rotations around different axes: interpolated as quaternions
+                interpolate_matrices(&transform_to_matrix(from), &transform_to_matrix(to), t, false)
+            }
+        }
+    }
+}
+
+fn translate_components(transform: &StyleTransform) -> Option<(PixelValue, PixelValue, PixelValue)> {
+    let zero = PixelValue::zero();
+    match transform {
+        StyleTransform::Translate(v) => Some((v.x, v.y, zero)),
+        StyleTransform::Translate3D(v) => Some((v.x, v.y, v.z)),
+        StyleTransform::TranslateX(x) => Some((*x, zero, zero)),
+        StyleTransform::TranslateY(y) => Some((zero, *y, zero)),
+        StyleTransform::TranslateZ(z) => Some((zero, zero, *z)),
+        _ => None,
+    }
+}
+
+fn scale_components(transform: &StyleTransform) -> Option<(PercentageValue, PercentageValue, PercentageValue)> {
+    let one = PercentageValue::const_new(100);
+    match transform {
+        StyleTransform::Scale(s) => Some((s.x, s.y, one)),
+        StyleTransform::Scale3D(s) => Some((s.x, s.y, s.z)),
+        StyleTransform::ScaleX(x) => Some((*x, one, one)),
+        StyleTransform::ScaleY(y) => Some((one, *y, one)),
+        StyleTransform::ScaleZ(z) => Some((one, one, *z)),
+        _ => None,
+    }
+}
+
+/// Returns the normalized axis and the angle of a rotation, `None` for a zero axis
+fn rotate_components(transform: &StyleTransform) -> Option<([f64; 3], AngleValue)> {
+    let (axis, angle) = match transform {
+        StyleTransform::Rotate(a) | StyleTransform::RotateZ(a) => ([0.0, 0.0, 1.0], *a),
+        StyleTransform::RotateX(a) => ([1.0, 0.0, 0.0], *a),
+        StyleTransform::RotateY(a) => ([0.0, 1.0, 0.0], *a),
+        StyleTransform::Rotate3D(r) => ([r.x.normalized() as f64, r.y.normalized() as f64, r.z.normalized() as f64], r.angle),
+        _ => return None,
+    };
+    let length = libm::sqrt(dot(&axis, &axis));
+    if length == 0.0 {
+        return None;
+    }
+    Some(([axis[0] / length, axis[1] / length, axis[2] / length], angle))
+}
+
+/// Interpolates the inverse of the distances (the `m34` of their matrices), so that
+/// `perspective(none)` is an inverse of `0` and not an endless distance in pixels
+fn interpolate_perspective(from: &StyleTransform, to: &StyleTransform, t: f32) -> StyleTransform {
+    let inverse = |transform: &StyleTransform| match transform {
+        StyleTransform::Perspective(distance) if *transform != StyleTransform::PERSPECTIVE_NONE => 1.0 / distance.to_pixels(0.0).max(1.0),
+        _ => 0.0,
+    };
+    let inverse = inverse(from) + (inverse(to) - inverse(from)) * t;
+    if inverse <= 0.0 {
+        StyleTransform::PERSPECTIVE_NONE
+    } else {
+        StyleTransform::Perspective(PixelValue::px(1.0 / inverse))
+    }
+}
+
+/// Returns whether the function only transforms in the x / y plane
+fn is_2d_transform(transform: &StyleTransform) -> bool {
+    use self::StyleTransform::*;
+    match transform {
+        Matrix(_) | Translate(_) | TranslateX(_) | TranslateY(_) | Rotate(_) | RotateZ(_) | Scale(_) | ScaleX(_) | ScaleY(_)
+        | Skew(_) | SkewX(_) | SkewY(_) => true,
+        Matrix3D(_) | Translate3D(_) | TranslateZ(_) | Rotate3D(_) | RotateX(_) | RotateY(_) | Scale3D(_) | ScaleZ(_)
+        | Perspective(_) => false,
+    }
+}
+
+/// A 4x4 matrix in the layout of `matrix3d()`: `m[i][j]` is `m{i+1}{j+1}`, so points are
+/// row vectors multiplied from the left and the translation is in `m[3]`
+type Matrix4 = [[f64; 4]; 4];
+
+const IDENTITY_MATRIX: Matrix4 = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+
+fn multiply(a: &Matrix4, b: &Matrix4) -> Matrix4 {
+    let mut result = [[0.0; 4]; 4];
+    for (i, row) in result.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..4).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    result
+}
+
+/// Returns the matrix of a whole list: for `a b`, `b` is applied to the points first
+fn transforms_to_matrix(transforms: &[StyleTransform]) -> Matrix4 {
+    transforms.iter().fold(IDENTITY_MATRIX, |matrix, transform| multiply(&transform_to_matrix(transform), &matrix))
+}
+
+fn transform_to_matrix(transform: &StyleTransform) -> Matrix4 {
+    use self::StyleTransform::*;
+
+    let px = |v: &PixelValue| v.to_pixels(0.0) as f64;
+    let translate = |x: f64, y: f64, z: f64| {
+        let mut m = IDENTITY_MATRIX;
+        m[3] = [x, y, z, 1.0];
+        m
+    };
+    let scale = |x: f64, y: f64, z: f64| {
+        let mut m = IDENTITY_MATRIX;
+        (m[0][0], m[1][1], m[2][2]) = (x, y, z);
+        m
+    };
+    let skew = |x: &PercentageValue, y: &PercentageValue| {
+        let mut m = IDENTITY_MATRIX;
+        m[1][0] = libm::tan((x.normalized() as f64).to_radians());
+        m[0][1] = libm::tan((y.normalized() as f64).to_radians());
+        m
+    };
+
+    match transform {
+        Matrix(m) => [
+            [px(&m.a), px(&m.b), 0.0, 0.0],
+            [px(&m.c), px(&m.d), 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [px(&m.tx), px(&m.ty), 0.0, 1.0],
+        ],
+        Matrix3D(m) => [
+            [px(&m.m11), px(&m.m12), px(&m.m13), px(&m.m14)],
+            [px(&m.m21), px(&m.m22), px(&m.m23), px(&m.m24)],
+            [px(&m.m31), px(&m.m32), px(&m.m33), px(&m.m34)],
+            [px(&m.m41), px(&m.m42), px(&m.m43), px(&m.m44)],
+        ],
+        Translate(_) | Translate3D(_) | TranslateX(_) | TranslateY(_) | TranslateZ(_) => {
+            let (x, y, z) = translate_components(transform).unwrap_or_default();
+            translate(px(&x), px(&y), px(&z))
+        }
+        Scale(_) | Scale3D(_) | ScaleX(_) | ScaleY(_) | ScaleZ(_) => {
+            let (x, y, z) = scale_components(transform).unwrap_or_default();
+            scale(x.normalized() as f64, y.normalized() as f64, z.normalized() as f64)
+        }
+        Rotate(_) | Rotate3D(_) | RotateX(_) | RotateY(_) | RotateZ(_) => {
+            let ([x, y, z], angle) = match rotate_components(transform) {
+                Some(rotation) => rotation,
+                None => return IDENTITY_MATRIX,
+            };
+            let half_angle = (angle.to_degrees_unclamped() as f64).to_radians() / 2.0;
+            let (sc, sq) = (libm::sin(half_angle) * libm::cos(half_angle), libm::sin(half_angle) * libm::sin(half_angle));
+            [
+                [1.0 - 2.0 * (y * y + z * z) * sq, 2.0 * (x * y * sq + z * sc), 2.0 * (x * z * sq - y * sc), 0.0],
+                [2.0 * (x * y * sq - z * sc), 1.0 - 2.0 * (x * x + z * z) * sq, 2.0 * (y * z * sq + x * sc), 0.0],
+                [2.0 * (x * z * sq + y * sc), 2.0 * (y * z * sq - x * sc), 1.0 - 2.0 * (x * x + y * y) * sq, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]
+        }
+        Skew(s) => skew(&s.x, &s.y),
+        SkewX(x) => skew(x, &PercentageValue::const_new(0)),
+        SkewY(y) => skew(&PercentageValue::const_new(0), y),
+        Perspective(_) if *transform == StyleTransform::PERSPECTIVE_NONE => IDENTITY_MATRIX,
+        Perspective(distance) => {
+            let mut m = IDENTITY_MATRIX;
+            m[2][3] = -1.0 / px(distance).max(1.0);
+            m
+        }
+    }
+}
+
+/// Converts a matrix back to a `matrix()` (if `is_2d`) or `matrix3d()` function
+fn matrix_to_transform(m: &Matrix4, is_2d: bool) -> StyleTransform {
+    let px = |v: f64| PixelValue::px(v as f32);
+    if is_2d {
+        StyleTransform::Matrix(StyleTransformMatrix2D {
+            a: px(m[0][0]), b: px(m[0][1]), c: px(m[1][0]), d: px(m[1][1]), tx: px(m[3][0]), ty: px(m[3][1]),
+        })
+    } else {
+        StyleTransform::Matrix3D(StyleTransformMatrix3D {
+            m11: px(m[0][0]), m12: px(m[0][1]), m13: px(m[0][2]), m14: px(m[0][3]),
+            m21: px(m[1][0]), m22: px(m[1][1]), m23: px(m[1][2]), m24: px(m[1][3]),
+            m31: px(m[2][0]), m32: px(m[2][1]), m33: px(m[2][2]), m34: px(m[2][3]),
+            m41: px(m[3][0]), m42: px(m[3][1]), m43: px(m[3][2]), m44: px(m[3][3]),
+        })
+    }
+}
+
+/// Decomposes both matrices, interpolates the components and recomposes the result
+fn interpolate_matrices(from: &Matrix4, to: &Matrix4, t: f32, is_2d: bool) -> Option<StyleTransform> {
+    let (from, to) = (DecomposedMatrix::new(from)?, DecomposedMatrix::new(to)?);
+    Some(matrix_to_transform(&from.interpolate(&to, t as f64).recompose(), is_2d))
+}
+
+/// The components of a 4x4 matrix: the matrix is `scale * skew * rotation * translation * perspective`
+#[derive(Debug, Clone, PartialEq)]
+struct DecomposedMatrix {
+    translate: [f64; 3],
+    scale: [f64; 3],
+    /// The xy, xz and yz shear factors
+    skew: [f64; 3],
+    perspective: [f64; 4],
+    /// The rotation as a unit quaternion `(x, y, z, w)`
+    quaternion: [f64; 4],
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+/// Returns `a + b * factor`
+fn combine(a: &[f64; 3], b: &[f64; 3], factor: f64) -> [f64; 3] {
+    [a[0] + b[0] * factor, a[1] + b[1] * factor, a[2] + b[2] * factor]
+}
+
+impl DecomposedMatrix {
+    /// Decomposes the matrix, returns `None` if it isn't invertible
+    fn new(matrix: &Matrix4) -> Option<Self> {
+        if matrix[3][3] == 0.0 {
+            return None;
+        }
+        let mut m = *matrix;
+        for value in m.iter_mut().flatten() {
+            *value /= matrix[3][3];
+        }
+
+        // the upper 3x3 matrix is scale * skew * rotation
+        let mut rows = [[m[0][0], m[0][1], m[0][2]], [m[1][0], m[1][1], m[1][2]], [m[2][0], m[2][1], m[2][2]]];
+        let determinant = dot(&rows[0], &cross(&rows[1], &rows[2]));
+        if determinant.abs() < 1e-12 {
+            return None;
+        }
+
+        // solves `m[..3][3] = upper * perspective[..3]` with the inverse of the upper matrix
+        // (the transposed cofactors divided by the determinant)
+        let translate = [m[3][0], m[3][1], m[3][2]];
+        let perspective = if m[0][3] != 0.0 || m[1][3] != 0.0 || m[2][3] != 0.0 {
+            let columns = [[rows[0][0], rows[1][0], rows[2][0]], [rows[0][1], rows[1][1], rows[2][1]], [rows[0][2], rows[1][2], rows[2][2]]];
+            let inverse_rows = [cross(&columns[1], &columns[2]), cross(&columns[2], &columns[0]), cross(&columns[0], &columns[1])];
+            let rhs = [m[0][3], m[1][3], m[2][3]];
+            let p = [dot(&inverse_rows[0], &rhs) / determinant, dot(&inverse_rows[1], &rhs) / determinant, dot(&inverse_rows[2], &rhs) / determinant];
+            [p[0], p[1], p[2], 1.0 - dot(&translate, &p)]
+        } else {
+            [0.0, 0.0, 0.0, 1.0]
+        };
+
+        // Gram-Schmidt: the scale is the length of the rows, the skew the dependency between them
+        let length = |v: &[f64; 3]| libm::sqrt(dot(v, v));
+        let mut scale = [0.0; 3];
+        let mut skew = [0.0; 3];
+        scale[0] = length(&rows[0]);
+        rows[0] = combine(&[0.0; 3], &rows[0], 1.0 / scale[0]);
+        skew[0] = dot(&rows[0], &rows[1]);
+        rows[1] = combine(&rows[1], &rows[0], -skew[0]);
+        scale[1] = length(&rows[1]);
+        rows[1] = combine(&[0.0; 3], &rows[1], 1.0 / scale[1]);
+        skew[0] /= scale[1];
+        skew[1] = dot(&rows[0], &rows[2]);
+        rows[2] = combine(&rows[2], &rows[0], -skew[1]);
+        skew[2] = dot(&rows[1], &rows[2]);
+        rows[2] = combine(&rows[2], &rows[1], -skew[2]);
+        scale[2] = length(&rows[2]);
+        rows[2] = combine(&[0.0; 3], &rows[2], 1.0 / scale[2]);
+        skew[1] /= scale[2];
+        skew[2] /= scale[2];
+
+        // a mirrored coordinate system: flip the scale, so that the rotation is a proper rotation
+        if dot(&rows[0], &cross(&rows[1], &rows[2])) < 0.0 {
+            for i in 0..3 {
+                scale[i] = -scale[i];
+                rows[i] = combine(&[0.0; 3], &rows[i], -1.0);
+            }
+        }
+
+        // the quaternion of the rotation matrix, computed from its largest diagonal component
+        // (the spec's sign rules lose the signs of a rotation by 180deg)
+        let r = |i: usize, j: usize| rows[j][i];
+        let trace = r(0, 0) + r(1, 1) + r(2, 2);
+        let quaternion = if trace > 0.0 {
+            let s = 2.0 * libm::sqrt(trace + 1.0);
+            [(r(2, 1) - r(1, 2)) / s, (r(0, 2) - r(2, 0)) / s, (r(1, 0) - r(0, 1)) / s, s / 4.0]
+        } else if r(0, 0) > r(1, 1) && r(0, 0) > r(2, 2) {
+            let s = 2.0 * libm::sqrt(1.0 + r(0, 0) - r(1, 1) - r(2, 2));
+            [s / 4.0, (r(0, 1) + r(1, 0)) / s, (r(0, 2) + r(2, 0)) / s, (r(2, 1) - r(1, 2)) / s]
+        } else if r(1, 1) > r(2, 2) {
+            let s = 2.0 * libm::sqrt(1.0 + r(1, 1) - r(0, 0) - r(2, 2));
+            [(r(0, 1) + r(1, 0)) / s, s / 4.0, (r(1, 2) + r(2, 1)) / s, (r(0, 2) - r(2, 0)) / s]
+        } else {
+            let s = 2.0 * libm::sqrt(1.0 + r(2, 2) - r(0, 0) - r(1, 1));
+            [(r(0, 2) + r(2, 0)) / s, (r(1, 2) + r(2, 1)) / s, s / 4.0, (r(1, 0) - r(0, 1)) / s]
+        };
+
+        Some(DecomposedMatrix { translate, scale, skew, perspective, quaternion })
+    }
+
+    /// Interpolates the components linearly, the rotation with a spherical linear interpolation
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        let lerp3 = |a: &[f64; 3], b: &[f64; 3]| [lerp(a[0], b[0]), lerp(a[1], b[1]), lerp(a[2], b[2])];
+
+        let (from, to) = (self.quaternion, other.quaternion);
+        let product = (0..4).map(|i| from[i] * to[i]).sum::<f64>().clamp(-1.0, 1.0);
+        let quaternion = if product.abs() >= 1.0 {
+            from
+        } else {
+            let theta = libm::acos(product);
+            let w = libm::sin(t * theta) / libm::sqrt(1.0 - product * product);
+            let from_factor = libm::cos(t * theta) - product * w;
+            [0, 1, 2, 3].map(|i| from[i] * from_factor + to[i] * w)
+        };
+
+        DecomposedMatrix {
+            translate: lerp3(&self.translate, &other.translate),
+            scale: lerp3(&self.scale, &other.scale),
+            skew: lerp3(&self.skew, &other.skew),
+            perspective: [0, 1, 2, 3].map(|i| lerp(self.perspective[i], other.perspective[i])),
+            quaternion,
+        }
+    }
+
+    fn recompose(&self) -> Matrix4 {
+        let [x, y, z, w] = self.quaternion;
+        let [sx, sy, sz] = self.scale;
+        let scale = [[sx, 0.0, 0.0, 0.0], [0.0, sy, 0.0, 0.0], [0.0, 0.0, sz, 0.0], [0.0, 0.0, 0.0, 1.0]];
+        let mut skew = IDENTITY_MATRIX;
+        (skew[1][0], skew[2][0], skew[2][1]) = (self.skew[0], self.skew[1], self.skew[2]);
+        let rotation = [
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y + z * w), 2.0 * (x * z - y * w), 0.0],
+            [2.0 * (x * y - z * w), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z + x * w), 0.0],
+            [2.0 * (x * z + y * w), 2.0 * (y * z - x * w), 1.0 - 2.0 * (x * x + y * y), 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        let mut translate = IDENTITY_MATRIX;
+        translate[3] = [self.translate[0], self.translate[1], self.translate[2], 1.0];
+        let mut perspective = IDENTITY_MATRIX;
+        for (row, value) in perspective.iter_mut().zip(self.perspective.iter()) {
+            row[3] = *value;
+        }
+
+        [skew, rotation, translate, perspective].iter().fold(scale, |matrix, m| multiply(&matrix, m))
+    }
+}
+
+impl Property {
+    /// Returns the value of the property at `t` between `self` (`t = 0`) and `other` (`t = 1`),
+    /// `None` if the two properties are of a different type.
+    ///
+    /// Values that can't be interpolated (see `can_interpolate`) switch from `self` to `other`
+    /// at `t = 0.5`.
+    pub fn interpolate(&self, other: &Property, t: f32) -> Option<Property> {
+        if self.get_type() != other.get_type() {
+            return None;
+        }
+        match self.interpolate_smoothly(other, t) {
+            Some(property) => Some(property),
+            None if t < 0.5 => Some(self.clone()),
+            None => Some(other.clone()),
+        }
+    }
+
+    /// Returns whether the two values of the property can be interpolated smoothly, instead
+    /// of switching discretely at `t = 0.5`: both are exact values of an animatable property
+    /// (or the same keyword) and their structure matches (i.e. the number of color stops)
+    pub fn can_interpolate(&self, other: &Property) -> bool {
+        self.interpolate_smoothly(other, 0.5).is_some()
+    }
+
+    fn interpolate_smoothly(&self, other: &Property, t: f32) -> Option<Property> {
+        if let (Property::Transform(from), Property::Transform(to)) = (self, other) {
+            // `none` is the same as an empty list of functions
+            let list = |value: &Value<Vec<StyleTransform>>| match value {
+                Value::Exact(transforms) => Some(transforms.clone()),
+                Value::None => Some(Vec::new()),
+                _ => None,
+            };
+            let transforms = interpolate_transforms(&list(from)?, &list(to)?, t)?;
+            return Some(Property::Transform(if transforms.is_empty() { Value::None } else { Value::Exact(transforms) }));
+        }
+
+        macro_rules! interpolate_variants {
+            ($($variant:ident),+ $(,)?) => {
+                match (self, other) {
+                    $(
+                        (Property::$variant(a), Property::$variant(b)) => a.interpolate(b, t).map(Property::$variant),
+                    )+
+                    _ => None,
+                }
+            };
+        }
+
+        interpolate_variants!(
+            TextColor, FontSize, LetterSpacing, LineHeight, WordSpacing, TabWidth,
+            Width, Height, MinWidth, MinHeight, MaxWidth, MaxHeight, Top, Right, Left, Bottom,
+            FlexGrow, FlexShrink, BackgroundContent, BackgroundPosition, BackgroundSize,
+            PaddingTop, PaddingLeft, PaddingRight, PaddingBottom,
+            MarginTop, MarginLeft, MarginRight, MarginBottom,
+            BorderTopLeftRadius, BorderTopRightRadius, BorderBottomLeftRadius, BorderBottomRightRadius,
+            BorderTopColor, BorderRightColor, BorderLeftColor, BorderBottomColor,
+            BorderTopWidth, BorderRightWidth, BorderLeftWidth, BorderBottomWidth,
+            BoxShadowLeft, BoxShadowRight, BoxShadowTop, BoxShadowBottom,
+            Opacity, TransformOrigin, PerspectiveOrigin, Filter, BackdropFilter, TextShadow,
+        )
+    }
+}
+
+#[test]
+fn test_interpolate_properties() {
+    use crate::sheet::Stylesheet;
+
+    let property = |css: &str| Stylesheet::parse(&format!("a {{ {} }}", css)).unwrap().rules[0].declarations[0].property().clone();
+    let interpolate = |from: &str, to: &str, t: f32| property(from).interpolate(&property(to), t).map(|p| p.to_string());
+
+    for (from, to, expected) in [
+        ("margin-left: 10px", "margin-left: 20px", "margin-left: 15px"),
+        ("padding-top: 1em", "padding-top: 3em", "padding-top: 2em"),
+        ("border-top-width: 0px", "border-top-width: 4px", "border-top-width: 2px"),
+        ("border-left-color: #000000", "border-left-color: #ff0000", "border-left-color: rgba(128, 0, 0, 1)"),
+        ("opacity: 0", "opacity: 1", "opacity: 0.5"),
+        ("-box-shadow-top: 0px 0px 0px 0px #000000", "-box-shadow-top: 4px 2px 6px 0px #ffffff", "-box-shadow-top: 2px 1px 3px 0px rgba(128, 128, 128, 1)"),
+        ("filter: blur(0px, 0px) opacity(0%)", "filter: blur(4px, 2px) opacity(100%)", "filter: blur(2px, 1px) opacity(50%)"),
+        ("background-position: left top, 0px 0px", "background-position: right top, 10px 20px", "background-position: 50% top, 5px 10px"),
+        (
+            "background: linear-gradient(0deg, #000000 0%, #ffffff 100%)",
+            "background: linear-gradient(90deg, #ffffff 20%, #000000 100%)",
+            "background: linear-gradient(45deg, rgba(128, 128, 128, 1) 10%, rgba(128, 128, 128, 1) 100%)",
+        ),
+        // not animatable or not matching: switches at t = 0.5
+        ("display: block", "display: flex", "display: flex"),
+        ("width: auto", "width: 10px", "width: 10px"),
+        ("filter: blur(0px, 0px)", "filter: blur(4px, 2px) opacity(100%)", "filter: blur(4px, 2px) opacity(100%)"),
+        // percentages can't be resolved against the size they refer to
+        ("width: 10%", "width: 20px", "width: 20px"),
+        ("transform-origin: 50% 50%", "transform-origin: 0px 0px", "transform-origin: 0px 0px"),
+    ] {
+        assert_eq!(interpolate(from, to, 0.5).as_deref(), Some(expected), "{} => {}", from, to);
+    }
+    assert_eq!(interpolate("display: block", "display: flex", 0.49).as_deref(), Some("display: block"));
+    assert_eq!(interpolate("width: 10%", "width: 20px", 0.49).as_deref(), Some("width: 10%"));
+    // other lengths with different metrics are interpolated in pixels
+    assert_eq!(interpolate("width: 0px", "width: 1em", 0.5).as_deref(), Some("width: 8px"));
+    // overshooting timing functions extrapolate
+    assert_eq!(interpolate("width: 0px", "width: 10px", 1.5).as_deref(), Some("width: 15px"));
+    assert_eq!(interpolate("width: 0px", "height: 10px", 0.5), None);
+
+    assert!(property("opacity: 0").can_interpolate(&property("opacity: 1")));
+    assert!(!property("width: 10%").can_interpolate(&property("width: 20px")));
+    // the inherent methods are infallible
+    assert_eq!(ColorU::new_rgb(0, 0, 0).interpolate(&ColorU::new_rgb(255, 0, 0), 0.5), ColorU::new_rgb(128, 0, 0));
+    assert_eq!(PixelValue::px(0.0).interpolate(&PixelValue::px(10.0), 0.5), PixelValue::px(5.0));
+    assert!(!property("display: block").can_interpolate(&property("display: flex")));
+    assert!(!property("-box-shadow-top: inset 0px 0px 0px 0px #000000").can_interpolate(&property("-box-shadow-top: 1px 0px 0px 0px #000000")));
+}
+
+#[test]
+fn test_interpolate_transforms() {
+    use crate::sheet::Stylesheet;
+
+    let property = |css: &str| Stylesheet::parse(&format!("a {{ {} }}", css)).unwrap().rules[0].declarations[0].property().clone();
+    let interpolate = |from: &str, to: &str, t: f32| property(from).interpolate(&property(to), t).map(|p| p.to_string());
+
+    for (from, to, expected) in [
+        // pairwise, with the common primitive of different functions
+        ("transform: translateX(0px) rotate(0deg)", "transform: translate(100px, 20px) rotate(90deg)", "transform: translate(50px, 10px) rotate(45deg)"),
+        // the shorter list is padded with identity functions
+        ("transform: scale(1)", "transform: scale(2) translateX(10px)", "transform: scale(1.5, 1.5) translateX(5px)"),
+        ("transform: none", "transform: rotate(90deg)", "transform: rotate(45deg)"),
+        ("transform: matrix(1, 0, 0, 1, 0, 0)", "transform: matrix(2, 0, 0, 2, 20, 0)", "transform: matrix(1.5, 0, 0, 1.5, 10, 0)"),
+        ("transform: skewX(0deg)", "transform: skew(0.1turn, 1rad)", "transform: skew(18deg, 28.64789deg)"),
+        // perspectives are padded with `perspective(none)` and interpolate the inverse of their distance
+        ("transform: none", "transform: perspective(100px)", "transform: perspective(200px)"),
+        ("transform: scale(1)", "transform: scale(2) perspective(100px)", "transform: scale(1.5, 1.5) perspective(200px)"),
+        ("transform: perspective(50px)", "transform: perspective(100px)", "transform: perspective(66.667px)"),
+        ("transform: perspective(none)", "transform: perspective(none)", "transform: perspective(none)"),
+        // no common primitive: interpolated via matrix decomposition
+        ("transform: rotate(0deg)", "transform: translateX(100px)", "transform: matrix(1, 0, 0, 1, 50, 0)"),
+        ("transform: rotate(0deg)", "transform: skewX(0.125turn)", "transform: matrix(1, 0, 0.5, 1, 0, 0)"),
+        ("transform: rotateX(0deg)", "transform: rotateY(90deg)", "transform: matrix3d(0.707, 0, -0.707, 0, 0, 1, 0, 0, 0.707, 0, 0.707, 0, 0, 0, 0, 1)"),
+    ] {
+        assert_eq!(interpolate(from, to, 0.5).as_deref(), Some(expected), "{} => {}", from, to);
+    }
+    // a matrix that can't be decomposed switches at t = 0.5
+    assert_eq!(interpolate("transform: scale(0)", "transform: rotate(0deg) scale(1)", 0.5).as_deref(), Some("transform: rotate(0deg) scale(1, 1)"));
+
+    // decomposing and recomposing returns the same matrix (normalized, so that m44 is 1)
+    for css in ["transform: rotate(30deg) translate(10px, 5px) scale(2, -3) skewX(10deg)", "transform: perspective(100px) rotate3d(1, 2, 3, 40deg) translate3d(10px, 5px, 2px)"] {
+        let transforms = match property(css) {
+            Property::Transform(Value::Exact(transforms)) => transforms,
+            _ => panic!("{}", css),
+        };
+        let matrix = transforms_to_matrix(&transforms);
+        let recomposed = DecomposedMatrix::new(&matrix).unwrap().recompose();
+        for (a, b) in matrix.iter().flatten().zip(recomposed.iter().flatten()) {
+            assert!((a / matrix[3][3] - b).abs() < 1e-9, "{}: {:?} != {:?}", css, matrix, recomposed);
+        }
+    }
+}
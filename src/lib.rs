@@ -1,2 +1,11 @@
 pub mod sheet;
 pub mod property;
+pub mod tokenizer;
+pub mod parser;
+pub mod matching;
+pub mod cascade;
+pub mod media;
+pub mod animation;
+pub mod transition;
+pub mod interpolate;
+mod property_parser;
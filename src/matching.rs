@@ -0,0 +1,558 @@
+//! Matching of selectors against the nodes of a host UI tree
+
+use crate::sheet::*;
+
+/// A node of a UI tree that selectors can be matched against.
+///
+/// Usually implemented on a cheap handle to the node (i.e. a reference or an index into the tree),
+/// since the parent and siblings are returned by value.
+pub trait Element: Sized {
+    /// Node type, i.e. `NodeTypeTag::Div`. Compared case-insensitively against type selectors.
+    fn tag(&self) -> &NodeTypeTag;
+    /// The `id` of the node, if it has one
+    fn id(&self) -> Option<&str>;
+    /// Whether the node has the given class
+    fn has_class(&self, class: &str) -> bool;
+    /// Value of the attribute with the given name, `None` if the node doesn't have the attribute
+    fn attribute(&self, name: &str) -> Option<&str>;
+    /// Parent node, `None` for the root node
+    fn parent(&self) -> Option<Self>;
+    /// The sibling directly before this node
+    fn prev_sibling(&self) -> Option<Self>;
+    /// The sibling directly after this node
+    fn next_sibling(&self) -> Option<Self>;
+    /// The first child of this node, `None` if the node has no children
+    fn first_child(&self) -> Option<Self>;
+    /// The dynamic state of the node (hovered, focused, checked, etc.), empty by default
+    fn state(&self) -> ElementState {
+        ElementState::empty()
+    }
+}
+
+/// Set of dynamic states of an element, which the interactive pseudo-classes (`:hover`,
+/// `:checked`, etc.) are matched against. See `Path::state_dependencies` to find out which
+/// rules have to be re-matched when the state of an element changes.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ElementState(u32);
+
+impl ElementState {
+    /// `:hover` - the mouse is over the element
+    pub const HOVER: Self = ElementState(1 << 0);
+    /// `:active` - the mouse is pressed and over the element
+    pub const ACTIVE: Self = ElementState(1 << 1);
+    /// `:focus` - the element has the keyboard focus (also used for `:focus-within`)
+    pub const FOCUS: Self = ElementState(1 << 2);
+    /// `:focus-visible` - the element has the focus and the focus should be drawn, i.e. after keyboard navigation
+    pub const FOCUS_VISIBLE: Self = ElementState(1 << 3);
+    /// `:disabled` - the element can't be interacted with (`:enabled` if not set)
+    pub const DISABLED: Self = ElementState(1 << 4);
+    /// `:checked` - a checkbox, radio button or toggle is on
+    pub const CHECKED: Self = ElementState(1 << 5);
+    /// `:indeterminate` - a checkbox or progress bar is neither on nor off
+    pub const INDETERMINATE: Self = ElementState(1 << 6);
+    /// `:read-only` - the content of the element can't be edited
+    pub const READ_ONLY: Self = ElementState(1 << 7);
+    /// `:placeholder-shown` - a text input shows its placeholder text
+    pub const PLACEHOLDER_SHOWN: Self = ElementState(1 << 8);
+    /// `:visited` - the link has been visited
+    pub const VISITED: Self = ElementState(1 << 9);
+    /// `:target` - the element is the target of the current navigation
+    pub const TARGET: Self = ElementState(1 << 10);
+
+    /// Returns the empty set
+    pub const fn empty() -> Self {
+        ElementState(0)
+    }
+
+    /// Returns the set of all states
+    pub const fn all() -> Self {
+        ElementState((1 << 11) - 1)
+    }
+
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether all states of `other` are set
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns whether any of the states of `other` is set
+    pub const fn intersects(&self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+}
+
+impl ::std::ops::BitOr for ElementState {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        ElementState(self.0 | other.0)
+    }
+}
+
+impl ::std::ops::BitOrAssign for ElementState {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+impl ::std::ops::BitAnd for ElementState {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        ElementState(self.0 & other.0)
+    }
+}
+
+/// Symmetric difference: the states that differ between two sets, i.e. `old_state ^ new_state`
+impl ::std::ops::BitXor for ElementState {
+    type Output = Self;
+
+    fn bitxor(self, other: Self) -> Self {
+        ElementState(self.0 ^ other.0)
+    }
+}
+
+/// Returns whether the path matches the element, see `Path::matches`
+pub(crate) fn matches_path<E: Element>(path: &Path, element: &E) -> bool {
+    matches_complex_selector(&path.selectors, element)
+}
+
+fn is_combinator(selector: &PathSelector) -> bool {
+    use self::PathSelector::*;
+    match selector {
+        DirectChildren | Children | AdjacentSibling | GeneralSibling => true,
+        Global | Type(_) | Class(_) | Id(_) | Attribute(_) | PseudoSelector(_) => false,
+    }
+}
+
+/// Matches the rightmost compound selector against the element, then walks
+/// the tree according to the combinator and matches the rest of the selectors (right-to-left)
+fn matches_complex_selector<E: Element>(selectors: &[PathSelector], element: &E) -> bool {
+    let (rest, compound) = match selectors.iter().rposition(is_combinator) {
+        Some(i) => selectors.split_at(i + 1),
+        None => (&[][..], selectors),
+    };
+
+    if !compound.iter().all(|selector| matches_simple_selector(selector, element)) {
+        return false;
+    }
+
+    let (combinator, rest) = match rest.split_last() {
+        Some(s) => s,
+        None => return true,
+    };
+
+    match combinator {
+        PathSelector::DirectChildren => element.parent().is_some_and(|parent| matches_complex_selector(rest, &parent)),
+        PathSelector::Children => {
+            let mut ancestor = element.parent();
+            while let Some(current) = ancestor {
+                if matches_complex_selector(rest, &current) {
+                    return true;
+                }
+                ancestor = current.parent();
+            }
+            false
+        }
+        PathSelector::AdjacentSibling => element.prev_sibling().is_some_and(|sibling| matches_complex_selector(rest, &sibling)),
+        PathSelector::GeneralSibling => {
+            let mut sibling = element.prev_sibling();
+            while let Some(current) = sibling {
+                if matches_complex_selector(rest, &current) {
+                    return true;
+                }
+                sibling = current.prev_sibling();
+            }
+            false
+        }
+        _ => false,
+    }
+}
+
+fn matches_simple_selector<E: Element>(selector: &PathSelector, element: &E) -> bool {
+    use self::PathSelector::*;
+    match selector {
+        Global => true,
+        Type(tag) => element.tag().eq_ignore_ascii_case(tag),
+        Class(class) => element.has_class(class),
+        Id(id) => element.id() == Some(id.as_str()),
+        Attribute(attribute) => matches_attribute(attribute, element.attribute(&attribute.name)),
+        PseudoSelector(pseudo) => matches_pseudo_selector(pseudo, element),
+        DirectChildren | Children | AdjacentSibling | GeneralSibling => false,
+    }
+}
+
+fn matches_attribute(selector: &AttributeSelector, value: Option<&str>) -> bool {
+    let value = match value {
+        Some(v) => v,
+        None => return false,
+    };
+
+    let (value, expected) = match selector.case {
+        AttributeCase::Insensitive => (value.to_ascii_lowercase(), selector.value.to_ascii_lowercase()),
+        AttributeCase::Default | AttributeCase::Sensitive => (value.to_string(), selector.value.clone()),
+    };
+
+    use self::AttributeOperator::*;
+    match selector.operator {
+        Exists => true,
+        Equals => value == expected,
+        Includes => !expected.is_empty() && value.split_ascii_whitespace().any(|word| word == expected),
+        DashMatch => value == expected || value.strip_prefix(expected.as_str()).is_some_and(|rest| rest.starts_with('-')),
+        Prefix => !expected.is_empty() && value.starts_with(&expected),
+        Suffix => !expected.is_empty() && value.ends_with(&expected),
+        Substring => !expected.is_empty() && value.contains(&expected),
+    }
+}
+
+fn matches_pseudo_selector<E: Element>(selector: &PathPseudoSelector, element: &E) -> bool {
+    use self::PathPseudoSelector::*;
+    match selector {
+        FirstChild => element.prev_sibling().is_none(),
+        LastChild => element.next_sibling().is_none(),
+        OnlyChild => element.prev_sibling().is_none() && element.next_sibling().is_none(),
+        FirstOfType => sibling_index(element, false, |s| s.tag().eq_ignore_ascii_case(element.tag())) == 1,
+        LastOfType => sibling_index(element, true, |s| s.tag().eq_ignore_ascii_case(element.tag())) == 1,
+        OnlyOfType => {
+            sibling_index(element, false, |s| s.tag().eq_ignore_ascii_case(element.tag())) == 1
+                && sibling_index(element, true, |s| s.tag().eq_ignore_ascii_case(element.tag())) == 1
+        }
+        NthChild(nth, of) => matches_nth_child_of(nth, of, element, false),
+        NthLastChild(nth, of) => matches_nth_child_of(nth, of, element, true),
+        NthOfType(nth) => nth.matches(sibling_index(element, false, |s| s.tag().eq_ignore_ascii_case(element.tag()))),
+        NthLastOfType(nth) => nth.matches(sibling_index(element, true, |s| s.tag().eq_ignore_ascii_case(element.tag()))),
+        Empty => element.first_child().is_none(),
+        Root => element.parent().is_none(),
+        FocusWithin => element.state().contains(ElementState::FOCUS) || any_descendant(element, &|e: &E| e.state().contains(ElementState::FOCUS)),
+        Enabled => !element.state().contains(ElementState::DISABLED),
+        Hover | Active | Focus | FocusVisible | Disabled | Checked | Indeterminate | ReadOnly | PlaceholderShown | Visited | Target => {
+            element.state().contains(selector.state_dependencies())
+        }
+        Is(list) | Where(list) => list.iter().any(|path| matches_path(path, element)),
+        Not(list) => !list.iter().any(|path| matches_path(path, element)),
+        Has(list) => list.iter().any(|path| matches_relative_selector(&path.selectors, element)),
+    }
+}
+
+/// Matches a relative selector of `:has()` left-to-right, starting at the anchor element.
+/// A missing leading combinator is the descendant combinator.
+fn matches_relative_selector<E: Element>(selectors: &[PathSelector], anchor: &E) -> bool {
+    let (combinator, selectors) = match selectors.split_first() {
+        Some((first, rest)) if is_combinator(first) => (first, rest),
+        _ => (&PathSelector::Children, selectors),
+    };
+    let (compound, rest) = match selectors.iter().position(is_combinator) {
+        Some(i) => selectors.split_at(i),
+        None => (selectors, &[][..]),
+    };
+
+    let matches = |candidate: &E| {
+        compound.iter().all(|selector| matches_simple_selector(selector, candidate))
+            && (rest.is_empty() || matches_relative_selector(rest, candidate))
+    };
+
+    match combinator {
+        PathSelector::DirectChildren => any_child(anchor, &matches),
+        PathSelector::Children => any_descendant(anchor, &matches),
+        PathSelector::AdjacentSibling => anchor.next_sibling().is_some_and(|sibling| matches(&sibling)),
+        PathSelector::GeneralSibling => {
+            let mut sibling = anchor.next_sibling();
+            while let Some(current) = sibling {
+                if matches(&current) {
+                    return true;
+                }
+                sibling = current.next_sibling();
+            }
+            false
+        }
+        _ => false,
+    }
+}
+
+fn any_child<E: Element, F: Fn(&E) -> bool>(element: &E, f: &F) -> bool {
+    let mut child = element.first_child();
+    while let Some(current) = child {
+        if f(&current) {
+            return true;
+        }
+        child = current.next_sibling();
+    }
+    false
+}
+
+fn any_descendant<E: Element, F: Fn(&E) -> bool>(element: &E, f: &F) -> bool {
+    any_child(element, &|child: &E| f(child) || any_descendant(child, f))
+}
+
+/// `:nth-child(An+B of S)`: the element has to match `S` and only the siblings that match `S` are counted
+fn matches_nth_child_of<E: Element>(nth: &NthChildSelector, of: &[Path], element: &E, from_end: bool) -> bool {
+    let matches_of = |e: &E| of.is_empty() || of.iter().any(|path| matches_path(path, e));
+    matches_of(element) && nth.matches(sibling_index(element, from_end, matches_of))
+}
+
+/// Returns the 1-based position of the element among its siblings, only counting the
+/// siblings for which `filter` returns true. Counts from the last sibling if `from_end` is set.
+fn sibling_index<E: Element, F: Fn(&E) -> bool>(element: &E, from_end: bool, filter: F) -> i32 {
+    let step = |e: &E| if from_end { e.next_sibling() } else { e.prev_sibling() };
+    let mut index = 1;
+    let mut sibling = step(element);
+    while let Some(current) = sibling {
+        if filter(&current) {
+            index += 1;
+        }
+        sibling = step(&current);
+    }
+    index
+}
+
+/// Returns the states that the path depends on, see `Path::state_dependencies`
+pub(crate) fn state_dependencies(path: &Path) -> ElementState {
+    let mut state = ElementState::empty();
+    for selector in path.selectors.iter() {
+        if let PathSelector::PseudoSelector(pseudo) = selector {
+            state |= pseudo.state_dependencies();
+        }
+    }
+    state
+}
+
+impl PathPseudoSelector {
+    /// Returns the states that the pseudo-class depends on, including the states of its arguments
+    fn state_dependencies(&self) -> ElementState {
+        use self::PathPseudoSelector::*;
+        let list_dependencies = |list: &[Path]| list.iter().fold(ElementState::empty(), |state, path| state | state_dependencies(path));
+        match self {
+            Hover => ElementState::HOVER,
+            Active => ElementState::ACTIVE,
+            Focus | FocusWithin => ElementState::FOCUS,
+            FocusVisible => ElementState::FOCUS_VISIBLE,
+            Enabled | Disabled => ElementState::DISABLED,
+            Checked => ElementState::CHECKED,
+            Indeterminate => ElementState::INDETERMINATE,
+            ReadOnly => ElementState::READ_ONLY,
+            PlaceholderShown => ElementState::PLACEHOLDER_SHOWN,
+            Visited => ElementState::VISITED,
+            Target => ElementState::TARGET,
+            Is(list) | Not(list) | Where(list) | Has(list) | NthChild(_, list) | NthLastChild(_, list) => list_dependencies(list),
+            FirstChild | LastChild | OnlyChild | FirstOfType | LastOfType | OnlyOfType |
+            NthOfType(_) | NthLastOfType(_) | Empty | Root => ElementState::empty(),
+        }
+    }
+}
+
+/// `(tag, id, classes, attributes, parent)`
+#[cfg(test)]
+pub(crate) type TestNode = (NodeTypeTag, Option<&'static str>, &'static [&'static str], &'static [(&'static str, &'static str)], Option<usize>);
+
+/// Element of a tree that is stored as a flat list of nodes, for testing
+#[cfg(test)]
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct TestElement<'a> {
+    pub nodes: &'a [TestNode],
+    pub index: usize,
+    /// `(node index, state)` of the nodes that have a state
+    pub states: &'a [(usize, ElementState)],
+}
+
+#[cfg(test)]
+impl<'a> TestElement<'a> {
+    fn at(&self, index: usize) -> Self {
+        TestElement { index, ..*self }
+    }
+
+    fn siblings(&self) -> Vec<usize> {
+        let parent = self.nodes[self.index].4;
+        (0..self.nodes.len()).filter(|i| self.nodes[*i].4 == parent).collect()
+    }
+}
+
+#[cfg(test)]
+impl<'a> Element for TestElement<'a> {
+    fn tag(&self) -> &NodeTypeTag {
+        &self.nodes[self.index].0
+    }
+    fn id(&self) -> Option<&str> {
+        self.nodes[self.index].1
+    }
+    fn has_class(&self, class: &str) -> bool {
+        self.nodes[self.index].2.contains(&class)
+    }
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.nodes[self.index].3.iter().find(|(n, _)| *n == name).map(|(_, v)| *v)
+    }
+    fn parent(&self) -> Option<Self> {
+        self.nodes[self.index].4.map(|i| self.at(i))
+    }
+    fn prev_sibling(&self) -> Option<Self> {
+        let siblings = self.siblings();
+        let position = siblings.iter().position(|i| *i == self.index)?;
+        position.checked_sub(1).map(|p| self.at(siblings[p]))
+    }
+    fn next_sibling(&self) -> Option<Self> {
+        let siblings = self.siblings();
+        let position = siblings.iter().position(|i| *i == self.index)?;
+        siblings.get(position + 1).map(|i| self.at(*i))
+    }
+    fn first_child(&self) -> Option<Self> {
+        (0..self.nodes.len()).find(|i| self.nodes[*i].4 == Some(self.index)).map(|i| self.at(i))
+    }
+    fn state(&self) -> ElementState {
+        self.states.iter().find(|(i, _)| *i == self.index).map(|(_, state)| *state).unwrap_or_default()
+    }
+}
+
+#[test]
+fn test_matches_path() {
+    use self::NodeTypeTag::*;
+    // <body>
+    //   <div id="main" class="a">
+    //     <p class="x"/> <img data-state="open closed" lang="en-US"/> <p/> <div class="b"/>
+    //   </div>
+    // </body>
+    let nodes: &[TestNode] = &[
+        (Body, None, &[], &[], None),
+        (Div, Some("main"), &["a"], &[], Some(0)),
+        (P, None, &["x"], &[], Some(1)),
+        (Img, None, &[], &[("data-state", "open closed"), ("lang", "en-US")], Some(1)),
+        (P, None, &[], &[], Some(1)),
+        (Div, None, &["b"], &[], Some(1)),
+    ];
+    let element = |index| TestElement { nodes, index, states: &[(3, ElementState::HOVER)] };
+    let matches = |selector: &str, index| selector.parse::<Path>().unwrap().matches(&element(index));
+
+    assert!(matches("*", 0));
+    assert!(matches("div#main.a", 1));
+    assert!(!matches("div#main.b", 1));
+    assert!(matches("body > div > p", 2));
+    assert!(matches("body p", 2));
+    assert!(!matches("body > p", 2));
+    assert!(matches("p.x + img", 3));
+    assert!(!matches("p + p", 4));
+    assert!(matches("p.x ~ p", 4));
+    assert!(matches("img ~ div.b", 5));
+    assert!(!matches("div.b ~ img", 3));
+    assert!(matches(".a > p ~ .b", 5));
+
+    assert!(matches("[data-state]", 3));
+    assert!(matches("[data-state~=closed]", 3));
+    assert!(!matches("[data-state~=clos]", 3));
+    assert!(matches("[data-state^=open]", 3));
+    assert!(matches("[data-state$=\"ed\"]", 3));
+    assert!(matches("[data-state*=\"n c\"]", 3));
+    assert!(matches("[lang|=en]", 3));
+    assert!(!matches("[lang|=EN]", 3));
+    assert!(matches("[lang|=EN i]", 3));
+    assert!(!matches("[data-x]", 3));
+
+    assert!(matches("img:nth-child(2)", 3));
+    assert!(matches(":nth-child(even)", 5));
+    assert!(matches(":nth-child(odd)", 4));
+    assert!(matches(":nth-child(2n + 1)", 2));
+    assert!(!matches(":nth-child(2n + 1)", 3));
+    assert!(matches(":nth-child(0n + 3)", 4));
+    assert!(matches("p:first-child", 2));
+    assert!(!matches("img:first-child", 3));
+    assert!(matches("div:last-child", 5));
+    assert!(matches("div:only-child", 1));
+    assert!(!matches("p:only-child", 2));
+    assert!(matches("p:first-of-type", 2));
+    assert!(matches("p:last-of-type", 4));
+    assert!(matches("img:only-of-type", 3));
+    assert!(!matches("p:only-of-type", 2));
+    assert!(matches("body:root", 0));
+    assert!(!matches(":root", 1));
+    assert!(matches("img:empty", 3));
+    assert!(!matches("div:empty", 1));
+    assert!(matches(":nth-last-child(1)", 5));
+    assert!(matches(":nth-child(-n + 2)", 3));
+    assert!(!matches(":nth-child(-n + 2)", 4));
+    assert!(matches(":nth-child(2n - 1)", 4));
+    assert!(!matches(":nth-child(-1)", 2));
+    assert!(matches("p:nth-of-type(2)", 4));
+    assert!(matches("p:nth-last-of-type(2)", 2));
+    assert!(matches(":nth-child(2 of p)", 4));
+    assert!(!matches(":nth-child(2 of p)", 3));
+    assert!(matches(":nth-last-child(1 of :not(div))", 4));
+    assert!(matches("img:hover", 3));
+    assert!(!matches("p:hover", 2));
+    assert!(!matches("img:focus", 3));
+
+    assert!(matches(":is(img, p.x)", 2));
+    assert!(matches("div > :is(img, p.x)", 3));
+    assert!(!matches(":is(img, p.x)", 4));
+    assert!(matches("p:not(.x)", 4));
+    assert!(!matches("p:not(.y, :first-child)", 2));
+    assert!(matches(":where(#main) > div", 5));
+    assert!(matches("div:has(> img)", 1));
+    assert!(matches("body:has(p.x + img)", 0));
+    assert!(!matches("body:has(> p)", 0));
+    assert!(matches("p:has(+ img[lang], ~ .b)", 2));
+    assert!(!matches("p:has(~ img)", 4));
+    assert!(!matches(":has(.b):not(:has(> div))", 0));
+    assert!(matches(":has(.b):not(:has(> p))", 0));
+    assert!(matches("div:not(:has(*))", 5));
+
+    // <tab-bar> <Button/> <button/> </tab-bar>
+    let custom: &[TestNode] = &[
+        (NodeTypeTag::new("tab-bar"), None, &[], &[], None),
+        (Custom("Button".to_string()), None, &[], &[], Some(0)),
+        (NodeTypeTag::new("BUTTON"), None, &[], &[], Some(0)),
+    ];
+    let matches = |selector: &str, index| selector.parse::<Path>().unwrap().matches(&TestElement { nodes: custom, index, states: &[] });
+    assert!(matches("tab-bar > button", 1));
+    assert!(matches("TAB-BAR BUTTON:nth-of-type(2)", 2));
+    assert!(!matches("button:only-of-type", 1));
+    assert!(!matches("slider", 1));
+    assert!(!matches("div", 0));
+}
+
+#[test]
+fn test_matches_element_state() {
+    use self::NodeTypeTag::*;
+    // <div class="form"> <img class="input"/> <div class="checkbox"/> </div>
+    let nodes: &[TestNode] = &[
+        (Div, None, &["form"], &[], None),
+        (Img, None, &["input"], &[], Some(0)),
+        (Div, None, &["checkbox"], &[], Some(0)),
+    ];
+    let states = [
+        (1, ElementState::FOCUS | ElementState::FOCUS_VISIBLE | ElementState::PLACEHOLDER_SHOWN),
+        (2, ElementState::CHECKED | ElementState::DISABLED),
+    ];
+    let matches = |selector: &str, index| selector.parse::<Path>().unwrap().matches(&TestElement { nodes, index, states: &states });
+
+    assert!(matches(".input:focus:focus-visible", 1));
+    assert!(matches(".form:focus-within", 0));
+    assert!(!matches(".form:focus", 0));
+    assert!(!matches(".checkbox:focus-within", 2));
+    assert!(matches(":checked:disabled", 2));
+    assert!(!matches(":enabled", 2));
+    assert!(matches(":enabled", 1));
+    assert!(matches(":placeholder-shown", 1));
+    assert!(!matches(":is(:indeterminate, :read-only, :visited, :target, :hover, :active)", 1));
+
+    let dependencies = |selector: &str| selector.parse::<Path>().unwrap().state_dependencies();
+    assert_eq!(dependencies("div.a > p"), ElementState::empty());
+    assert_eq!(dependencies(".menu:hover > .item:is(:checked, .x)"), ElementState::HOVER | ElementState::CHECKED);
+    assert_eq!(dependencies(":enabled:focus-within"), ElementState::DISABLED | ElementState::FOCUS);
+
+    let stylesheet = Stylesheet::parse(".a:hover { width: 1px; } .b:checked { width: 2px; } .c { width: 3px; }").unwrap();
+    let changed = ElementState::HOVER ^ (ElementState::HOVER | ElementState::CHECKED);
+    let affected = stylesheet.rules_depending_on(changed).map(|r| r.path.to_string()).collect::<Vec<_>>();
+    assert_eq!(affected, vec![".b:checked"]);
+}
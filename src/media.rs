@@ -0,0 +1,406 @@
+//! Media queries (i.e. the `screen and (width >= 600px)` of an `@media` rule)
+//! and the environment they are evaluated against
+
+use std::{fmt, str::FromStr};
+
+use crate::parser::{self, CssParseError};
+use crate::property::FloatValue;
+
+/// The environment that media queries are evaluated against: the output device,
+/// the size of the window and the preferences of the user
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaEnvironment {
+    /// Type of the output device, `MediaType::Screen` by default
+    pub media_type: MediaType,
+    /// Width of the viewport in CSS pixels
+    pub width: f32,
+    /// Height of the viewport in CSS pixels
+    pub height: f32,
+    /// Device pixels per CSS pixel (`dppx`), i.e. `2.0` on a high-DPI display
+    pub resolution: f32,
+    /// `prefers-color-scheme`
+    pub color_scheme: ColorScheme,
+    /// `prefers-reduced-motion`
+    pub reduced_motion: ReducedMotion,
+}
+
+impl Default for MediaEnvironment {
+    fn default() -> Self {
+        MediaEnvironment {
+            media_type: MediaType::Screen,
+            width: 800.0,
+            height: 600.0,
+            resolution: 1.0,
+            color_scheme: ColorScheme::Light,
+            reduced_motion: ReducedMotion::NoPreference,
+        }
+    }
+}
+
+impl MediaEnvironment {
+    /// `Portrait` if the viewport is at least as high as it is wide, `Landscape` otherwise
+    pub fn orientation(&self) -> Orientation {
+        if self.height >= self.width { Orientation::Portrait } else { Orientation::Landscape }
+    }
+}
+
+/// Comma-separated list of media queries, i.e. `screen and (width >= 600px), print`.
+/// Matches if any of the queries match, an empty list matches every environment.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MediaQueryList(pub Vec<MediaQuery>);
+
+impl MediaQueryList {
+    /// Parses a media query list, i.e. `screen and (width >= 600px), print`.
+    /// Fails if any of the queries is invalid.
+    pub fn parse(media: &str) -> Result<Self, CssParseError> {
+        parser::parse_media_query_list(media)
+    }
+
+    pub fn matches(&self, environment: &MediaEnvironment) -> bool {
+        self.0.is_empty() || self.0.iter().any(|query| query.matches(environment))
+    }
+}
+
+impl FromStr for MediaQueryList {
+    type Err = CssParseError;
+
+    fn from_str(media: &str) -> Result<Self, Self::Err> {
+        Self::parse(media)
+    }
+}
+
+impl fmt::Display for MediaQueryList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, query) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", query)?;
+        }
+        Ok(())
+    }
+}
+
+/// One media query, i.e. `not print and (orientation: portrait)` or `(width < 600px)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MediaQuery {
+    pub qualifier: Option<MediaQualifier>,
+    /// `MediaType::All` if the query only has a condition
+    pub media_type: MediaType,
+    pub condition: Option<MediaCondition>,
+}
+
+impl MediaQuery {
+    /// Returns the `not all` query, which never matches. Invalid media queries are replaced by it.
+    pub fn never() -> Self {
+        MediaQuery { qualifier: Some(MediaQualifier::Not), media_type: MediaType::All, condition: None }
+    }
+
+    pub fn matches(&self, environment: &MediaEnvironment) -> bool {
+        let matches = self.media_type.matches(&environment.media_type)
+            && self.condition.as_ref().is_none_or(|condition| condition.matches(environment));
+        match self.qualifier {
+            Some(MediaQualifier::Not) => !matches,
+            Some(MediaQualifier::Only) | None => matches,
+        }
+    }
+}
+
+impl fmt::Display for MediaQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.qualifier, &self.media_type, &self.condition) {
+            (None, MediaType::All, Some(condition)) => write!(f, "{}", condition),
+            (qualifier, media_type, condition) => {
+                match qualifier {
+                    Some(MediaQualifier::Not) => write!(f, "not ")?,
+                    Some(MediaQualifier::Only) => write!(f, "only ")?,
+                    None => {}
+                }
+                write!(f, "{}", media_type)?;
+                match condition {
+                    // `or` is not allowed directly after the media type
+                    Some(c @ MediaCondition::Or(_)) => write!(f, " and ({})", c),
+                    Some(c) => write!(f, " and {}", c),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// `not` or `only` in front of the media type
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MediaQualifier {
+    /// `not screen`: inverts the result of the whole query
+    Not,
+    /// `only screen`: only hides the query from old browsers, has no effect
+    Only,
+}
+
+/// Type of output device
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MediaType {
+    /// `all` - matches every device
+    All,
+    /// `screen`
+    Screen,
+    /// `print`
+    Print,
+    /// Any other media type (i.e. the deprecated `tv`), never matches
+    Unknown(String),
+}
+
+impl MediaType {
+    /// Returns whether the media type of a query matches the media type of the environment
+    pub fn matches(&self, environment: &MediaType) -> bool {
+        match self {
+            MediaType::All => true,
+            MediaType::Unknown(_) => false,
+            media_type => media_type == environment,
+        }
+    }
+}
+
+impl fmt::Display for MediaType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MediaType::All => write!(f, "all"),
+            MediaType::Screen => write!(f, "screen"),
+            MediaType::Print => write!(f, "print"),
+            MediaType::Unknown(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Condition of a media query, i.e. `(width >= 600px) and (orientation: landscape)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MediaCondition {
+    /// `(width >= 600px)`
+    Feature(MediaFeature),
+    /// `not (width >= 600px)`
+    Not(Box<MediaCondition>),
+    /// `(width >= 600px) and (height >= 400px)`
+    And(Vec<MediaCondition>),
+    /// `(width < 600px) or (height < 400px)`
+    Or(Vec<MediaCondition>),
+}
+
+impl MediaCondition {
+    pub fn matches(&self, environment: &MediaEnvironment) -> bool {
+        match self {
+            MediaCondition::Feature(feature) => feature.matches(environment),
+            MediaCondition::Not(condition) => !condition.matches(environment),
+            MediaCondition::And(conditions) => conditions.iter().all(|c| c.matches(environment)),
+            MediaCondition::Or(conditions) => conditions.iter().any(|c| c.matches(environment)),
+        }
+    }
+
+    /// Writes the condition, with parentheses if it is not a single feature
+    fn fmt_in_parens(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MediaCondition::Feature(_) => write!(f, "{}", self),
+            _ => write!(f, "({})", self),
+        }
+    }
+}
+
+impl fmt::Display for MediaCondition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (conditions, separator) = match self {
+            MediaCondition::Feature(feature) => return write!(f, "({})", feature),
+            MediaCondition::Not(condition) => {
+                write!(f, "not ")?;
+                return condition.fmt_in_parens(f);
+            }
+            MediaCondition::And(conditions) => (conditions, " and "),
+            MediaCondition::Or(conditions) => (conditions, " or "),
+        };
+        for (i, condition) in conditions.iter().enumerate() {
+            if i != 0 {
+                write!(f, "{}", separator)?;
+            }
+            condition.fmt_in_parens(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A media feature, i.e. the `width >= 600px` in `(width >= 600px)`.
+/// `None` values are written without a value (`(orientation)`) and match any value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MediaFeature {
+    /// `(width >= 600px)`, `(min-width: 600px)`, `(400px < width <= 700px)`, etc.
+    Range(RangeFeature, MediaRange),
+    /// `(orientation: portrait)`
+    Orientation(Option<Orientation>),
+    /// `(prefers-color-scheme: dark)`
+    PrefersColorScheme(Option<ColorScheme>),
+    /// `(prefers-reduced-motion: reduce)`, `(prefers-reduced-motion)` matches `reduce`
+    PrefersReducedMotion(Option<ReducedMotion>),
+}
+
+impl MediaFeature {
+    pub fn matches(&self, environment: &MediaEnvironment) -> bool {
+        match self {
+            MediaFeature::Range(RangeFeature::Width, range) => range.contains(environment.width),
+            MediaFeature::Range(RangeFeature::Height, range) => range.contains(environment.height),
+            MediaFeature::Range(RangeFeature::Resolution, range) => range.contains(environment.resolution),
+            MediaFeature::Orientation(o) => o.is_none_or(|o| o == environment.orientation()),
+            MediaFeature::PrefersColorScheme(c) => c.is_none_or(|c| c == environment.color_scheme),
+            MediaFeature::PrefersReducedMotion(m) => m.unwrap_or(ReducedMotion::Reduce) == environment.reduced_motion,
+        }
+    }
+}
+
+impl fmt::Display for MediaFeature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MediaFeature::Range(feature, range) => {
+                let unit = match feature {
+                    RangeFeature::Width | RangeFeature::Height => "px",
+                    RangeFeature::Resolution => "dppx",
+                };
+                let operator = |bound: &RangeBound| if bound.inclusive { "<=" } else { "<" };
+                match (&range.min, &range.max) {
+                    (Some(min), Some(max)) if min == max && min.inclusive => write!(f, "{} = {}{}", feature, min.value, unit),
+                    (Some(min), Some(max)) => {
+                        write!(f, "{}{} {} {} {} {}{}", min.value, unit, operator(min), feature, operator(max), max.value, unit)
+                    }
+                    (Some(min), None) => write!(f, "{} {} {}{}", feature, if min.inclusive { ">=" } else { ">" }, min.value, unit),
+                    (None, Some(max)) => write!(f, "{} {} {}{}", feature, operator(max), max.value, unit),
+                    (None, None) => write!(f, "{}", feature),
+                }
+            }
+            MediaFeature::Orientation(o) => write_feature(f, "orientation", o),
+            MediaFeature::PrefersColorScheme(c) => write_feature(f, "prefers-color-scheme", c),
+            MediaFeature::PrefersReducedMotion(m) => write_feature(f, "prefers-reduced-motion", m),
+        }
+    }
+}
+
+fn write_feature<T: fmt::Display>(f: &mut fmt::Formatter, name: &str, value: &Option<T>) -> fmt::Result {
+    match value {
+        Some(value) => write!(f, "{}: {}", name, value),
+        None => write!(f, "{}", name),
+    }
+}
+
+/// Media features with a numeric value, which can be compared with `<`, `>=`, etc.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RangeFeature {
+    /// Width of the viewport, in CSS pixels
+    Width,
+    /// Height of the viewport, in CSS pixels
+    Height,
+    /// Device pixels per CSS pixel (`dppx`)
+    Resolution,
+}
+
+impl fmt::Display for RangeFeature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RangeFeature::Width => write!(f, "width"),
+            RangeFeature::Height => write!(f, "height"),
+            RangeFeature::Resolution => write!(f, "resolution"),
+        }
+    }
+}
+
+/// Range of values that a `RangeFeature` has to be in, unbounded if `min` / `max` is `None`
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MediaRange {
+    pub min: Option<RangeBound>,
+    pub max: Option<RangeBound>,
+}
+
+impl MediaRange {
+    pub fn contains(&self, value: f32) -> bool {
+        let value = FloatValue::new(value);
+        let above_min = self.min.is_none_or(|min| value > min.value || (min.inclusive && value == min.value));
+        let below_max = self.max.is_none_or(|max| value < max.value || (max.inclusive && value == max.value));
+        above_min && below_max
+    }
+}
+
+/// Lower or upper end of a `MediaRange`, in pixels or `dppx`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RangeBound {
+    pub value: FloatValue,
+    /// Whether the range includes the value (`<=`) or not (`<`)
+    pub inclusive: bool,
+}
+
+/// `orientation` media feature
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Orientation::Portrait => write!(f, "portrait"),
+            Orientation::Landscape => write!(f, "landscape"),
+        }
+    }
+}
+
+/// `prefers-color-scheme` media feature
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl fmt::Display for ColorScheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColorScheme::Light => write!(f, "light"),
+            ColorScheme::Dark => write!(f, "dark"),
+        }
+    }
+}
+
+/// `prefers-reduced-motion` media feature
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ReducedMotion {
+    NoPreference,
+    Reduce,
+}
+
+impl fmt::Display for ReducedMotion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReducedMotion::NoPreference => write!(f, "no-preference"),
+            ReducedMotion::Reduce => write!(f, "reduce"),
+        }
+    }
+}
+
+#[test]
+fn test_evaluate_media() {
+    use crate::sheet::Stylesheet;
+
+    let stylesheet = Stylesheet::parse(
+        "p { width: 10px; } \
+        @media (width < 600px) { p { width: 5px; } } \
+        @media screen and (min-width: 600px) and (orientation: landscape) { p { height: 20px; } } \
+        @media (prefers-color-scheme: dark), print { div { width: 1px; } } \
+        @media not screen { div { height: 1px; } }",
+    )
+    .unwrap();
+    let selected = |env: &MediaEnvironment| {
+        stylesheet.evaluate_media(env).rules.iter().map(|rule| rule.to_string()).collect::<Vec<_>>()
+    };
+
+    let desktop = MediaEnvironment { width: 1280.0, height: 800.0, ..Default::default() };
+    assert_eq!(selected(&desktop), vec!["p{width: 10px}", "p{height: 20px}"]);
+
+    let compact = MediaEnvironment { width: 360.0, height: 640.0, color_scheme: ColorScheme::Dark, ..Default::default() };
+    assert_eq!(selected(&compact), vec!["p{width: 10px}", "p{width: 5px}", "div{width: 1px}"]);
+
+    let print = MediaEnvironment { media_type: MediaType::Print, ..desktop };
+    assert_eq!(selected(&print), vec!["p{width: 10px}", "div{width: 1px}", "div{height: 1px}"]);
+    assert!(stylesheet.evaluate_media(&print).rules.iter().all(|rule| rule.media.is_empty()));
+}
@@ -0,0 +1,1825 @@
+//! Parses CSS source text into a `Stylesheet`, following the "Parsing" chapter of
+//! [CSS Syntax Module Level 3](https://www.w3.org/TR/css-syntax-3/#parsing).
+
+use std::fmt;
+
+use crate::animation::{Keyframe, KeyframesRule};
+use crate::media::*;
+use crate::property::{CombinedPropertyType, FloatValue, PType, PercentageValue, Property, SizeMetric};
+use crate::property_parser::{self, find_block_end, ValueParser};
+use crate::sheet::*;
+use crate::tokenizer::{SpannedToken, Token, Tokenizer};
+
+/// Line and column (both starting at 1) of a character in the CSS source
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourceLocation {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceLocation {
+    /// Calculates the line and column of the given byte offset
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let before = &source[..offset.min(source.len())];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Start and end location of a piece of CSS source (the end is exclusive)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourceSpan {
+    pub start: SourceLocation,
+    pub end: SourceLocation,
+}
+
+/// What went wrong while parsing a stylesheet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CssParseErrorKind {
+    /// The stylesheet ended in the middle of a rule
+    UnexpectedEof,
+    /// A token was found where it is not allowed, i.e. `width 5px` (missing colon)
+    UnexpectedToken,
+    /// A `}` without matching `{`, or a block that is never closed
+    UnbalancedBrace,
+    /// The key of a declaration is not a known CSS property
+    UnknownProperty(String),
+    /// The value of a declaration could not be parsed: `width: 5 apples`
+    InvalidValue { property: String },
+    /// `var(--my_id)` without a default value: dynamic properties always need a default
+    MissingVarDefault { property: String, dynamic_id: String },
+    /// The default value of a `var(--my_id, default)` could not be parsed for the property
+    InvalidVarDefault { property: String, dynamic_id: String },
+    /// The selector of a rule could not be parsed
+    InvalidSelector,
+    /// The prelude of an at-rule could not be parsed, i.e. `@layer 5px;`
+    InvalidAtRule { name: String },
+}
+
+impl fmt::Display for CssParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::CssParseErrorKind::*;
+        match self {
+            UnexpectedEof => write!(f, "unexpected end of stylesheet"),
+            UnexpectedToken => write!(f, "unexpected token"),
+            UnbalancedBrace => write!(f, "unbalanced brace"),
+            UnknownProperty(p) => write!(f, "unknown property \"{}\"", p),
+            InvalidValue { property } => write!(f, "invalid value for \"{}\"", property),
+            MissingVarDefault { property, dynamic_id } => {
+                write!(f, "missing default value for \"var(--{})\" in \"{}\"", dynamic_id, property)
+            }
+            InvalidVarDefault { property, dynamic_id } => {
+                write!(f, "invalid default value for \"var(--{})\" in \"{}\"", dynamic_id, property)
+            }
+            InvalidSelector => write!(f, "invalid selector"),
+            InvalidAtRule { name } => write!(f, "invalid @{} rule", name),
+        }
+    }
+}
+
+/// Error (or warning, if the parser could recover from it) that occurred while parsing
+/// a stylesheet, i.e. `3:12: invalid value for "width": "5 apples"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CssParseError {
+    pub kind: CssParseErrorKind,
+    /// Location of the offending source text
+    pub span: SourceSpan,
+    /// The offending source text, i.e. the invalid value or selector
+    pub token: String,
+}
+
+impl fmt::Display for CssParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.span.start, self.kind)?;
+        if !self.token.is_empty() {
+            write!(f, ": \"{}\"", self.token)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CssParseError {}
+
+impl CssParseError {
+    /// Creates an error pointing to the given tokens of the source. If the range is empty,
+    /// the error points to the end of the source.
+    fn new(source: &str, kind: CssParseErrorKind, tokens: &[SpannedToken]) -> Self {
+        let tokens = trim_whitespace(tokens);
+        let (start, end) = match (tokens.first(), tokens.last()) {
+            (Some(first), Some(last)) => (first.start, last.end),
+            _ => (source.len(), source.len()),
+        };
+        CssParseError {
+            kind,
+            span: SourceSpan {
+                start: SourceLocation::from_offset(source, start),
+                end: SourceLocation::from_offset(source, end),
+            },
+            token: source[start..end].trim().to_string(),
+        }
+    }
+}
+
+/// Parses a CSS source string into a `Stylesheet`, failing on the first error
+pub fn parse_stylesheet(css: &str) -> Result<Stylesheet, CssParseError> {
+    let (stylesheet, mut warnings) = parse_stylesheet_with_warnings(css);
+    if warnings.is_empty() {
+        Ok(stylesheet)
+    } else {
+        Err(warnings.remove(0))
+    }
+}
+
+/// Parses a CSS source string into a `Stylesheet`, recovering from errors the same way
+/// browsers do: invalid declarations and rules are skipped and reported as warnings,
+/// the rest of the stylesheet is still parsed.
+pub fn parse_stylesheet_with_warnings(css: &str) -> (Stylesheet, Vec<CssParseError>) {
+    let tokens = Tokenizer::tokenize(css);
+    let mut parser = Parser { source: css, tokens: &tokens, position: 0, warnings: Vec::new() };
+    let mut stylesheet = Stylesheet::empty();
+    parser.parse_rule_list(&mut stylesheet, None, &[]);
+    (stylesheet, parser.warnings)
+}
+
+/// Expands the value of a shorthand property into its longhand properties,
+/// i.e. `border: 1px solid red` => the width, style and color of all four sides
+pub fn expand_shorthand(shorthand: CombinedPropertyType, value: &str) -> Result<Vec<Property>, CssParseError> {
+    let tokens = Tokenizer::tokenize(value);
+    let mut input = ValueParser::new(&tokens);
+    input.skip_whitespace();
+    property_parser::parse_shorthand(shorthand, &mut input).map_err(|()| {
+        CssParseError::new(value, CssParseErrorKind::InvalidValue { property: shorthand.to_str().to_string() }, &tokens)
+    })
+}
+
+/// Rule-level parser over the tokens of a stylesheet
+struct Parser<'a> {
+    source: &'a str,
+    tokens: &'a [SpannedToken],
+    position: usize,
+    warnings: Vec<CssParseError>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.position).map(|t| &t.token)
+    }
+
+    /// Records a warning for the given range of tokens. If the range is empty,
+    /// the warning points to the end of the stylesheet.
+    fn warn(&mut self, kind: CssParseErrorKind, tokens: &[SpannedToken]) {
+        self.warnings.push(CssParseError::new(self.source, kind, tokens));
+    }
+
+    /// Parses the rules into the stylesheet, `layer` is the full name of the enclosing `@layer` block,
+    /// `media` are the queries of the enclosing `@media` blocks
+    fn parse_rule_list(&mut self, stylesheet: &mut Stylesheet, layer: Option<&str>, media: &[MediaQueryList]) {
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Whitespace | Token::Cdo | Token::Cdc => {
+                    self.position += 1;
+                }
+                Token::CloseCurly => {
+                    self.warn(CssParseErrorKind::UnbalancedBrace, &self.tokens[self.position..self.position + 1]);
+                    self.position += 1;
+                }
+                Token::AtKeyword(name) if name.eq_ignore_ascii_case("layer") => {
+                    self.consume_layer_rule(stylesheet, layer, media);
+                }
+                Token::AtKeyword(name) if name.eq_ignore_ascii_case("media") => {
+                    self.consume_media_rule(stylesheet, layer, media);
+                }
+                Token::AtKeyword(name) if name.eq_ignore_ascii_case("supports") => {
+                    self.consume_supports_rule(stylesheet, layer, media);
+                }
+                Token::AtKeyword(name) if name.eq_ignore_ascii_case("keyframes") => {
+                    self.consume_keyframes_rule(stylesheet, layer, media);
+                }
+                Token::AtKeyword(_) => {
+                    // other at-rules are not supported yet, skip them
+                    self.consume_at_rule();
+                }
+                _ => {
+                    for rule in self.consume_qualified_rule() {
+                        let source_index = stylesheet.rules.len();
+                        stylesheet.rules.push(RuleBlock { layer: layer.map(str::to_string), media: media.to_vec(), source_index, ..rule });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Consumes the prelude of an at-rule and its block (if it has one, and not a `;`).
+    /// Returns the prelude and the contents of the block.
+    fn consume_at_rule_parts(&mut self) -> (&'a [SpannedToken], Option<&'a [SpannedToken]>) {
+        self.position += 1;
+        let prelude_start = self.position;
+        let mut prelude_end = self.tokens.len();
+        let mut block = None;
+        while let Some(token) = self.peek() {
+            self.position += 1;
+            match token {
+                Token::Semicolon => {
+                    prelude_end = self.position - 1;
+                    break;
+                }
+                Token::OpenCurly => {
+                    prelude_end = self.position - 1;
+                    block = Some(self.consume_block());
+                    break;
+                }
+                t if t.closing_token().is_some() => {
+                    self.consume_block();
+                }
+                _ => {}
+            }
+        }
+        (&self.tokens[prelude_start..prelude_end], block)
+    }
+
+    /// Parses the rules of the block of an at-rule
+    fn parse_nested_rule_list(&mut self, block: &'a [SpannedToken], stylesheet: &mut Stylesheet, layer: Option<&str>, media: &[MediaQueryList]) {
+        let mut nested = Parser { source: self.source, tokens: block, position: 0, warnings: Vec::new() };
+        nested.parse_rule_list(stylesheet, layer, media);
+        self.warnings.append(&mut nested.warnings);
+    }
+
+    /// Consumes an `@layer a, b;` statement or an `@layer a { rules }` block
+    fn consume_layer_rule(&mut self, stylesheet: &mut Stylesheet, parent_layer: Option<&str>, media: &[MediaQueryList]) {
+        let rule_start = self.position;
+        let (prelude, block) = self.consume_at_rule_parts();
+        let prelude_end = rule_start + 1 + prelude.len();
+
+        let names = match parse_layer_names(prelude) {
+            // anonymous layers (`@layer { }`) are not supported
+            Ok(names) if !names.is_empty() && (block.is_none() || names.len() == 1) => names,
+            _ => {
+                self.warn(CssParseErrorKind::InvalidAtRule { name: "layer".to_string() }, &self.tokens[rule_start..prelude_end]);
+                return;
+            }
+        };
+
+        let full_names = names
+            .into_iter()
+            .map(|name| match parent_layer {
+                Some(parent) => format!("{}.{}", parent, name),
+                None => name,
+            })
+            .collect::<Vec<_>>();
+        for name in full_names.iter() {
+            stylesheet.declare_layer(name);
+        }
+
+        if let Some(block) = block {
+            self.parse_nested_rule_list(block, stylesheet, Some(&full_names[0]), media);
+        }
+    }
+
+    /// Consumes an `@media queries { rules }` block. Invalid media queries never match,
+    /// but the rest of the query list is still used (with a warning).
+    fn consume_media_rule(&mut self, stylesheet: &mut Stylesheet, layer: Option<&str>, parent_media: &[MediaQueryList]) {
+        let rule_start = self.position;
+        let (prelude, block) = self.consume_at_rule_parts();
+        let prelude_end = rule_start + 1 + prelude.len();
+
+        let (queries, all_valid) = parse_media_queries(prelude);
+        let block = match block {
+            Some(block) if all_valid => block,
+            Some(block) => {
+                self.warn(CssParseErrorKind::InvalidAtRule { name: "media".to_string() }, &self.tokens[rule_start..prelude_end]);
+                block
+            }
+            None => {
+                self.warn(CssParseErrorKind::InvalidAtRule { name: "media".to_string() }, &self.tokens[rule_start..prelude_end]);
+                return;
+            }
+        };
+
+        let mut media = parent_media.to_vec();
+        media.push(queries);
+        self.parse_nested_rule_list(block, stylesheet, layer, &media);
+    }
+
+    /// Consumes an `@supports condition { rules }` block. The condition is evaluated right away:
+    /// the rules are only added if this crate can parse the declarations and selectors in it.
+    fn consume_supports_rule(&mut self, stylesheet: &mut Stylesheet, layer: Option<&str>, media: &[MediaQueryList]) {
+        let rule_start = self.position;
+        let (prelude, block) = self.consume_at_rule_parts();
+        let prelude_end = rule_start + 1 + prelude.len();
+
+        let mut input = ValueParser::new(prelude);
+        let supported = self.parse_supports_condition(&mut input).and_then(|supported| input.expect_exhausted().map(|()| supported));
+        match (supported, block) {
+            (Ok(true), Some(block)) => self.parse_nested_rule_list(block, stylesheet, layer, media),
+            (Ok(false), Some(_)) => {}
+            _ => self.warn(CssParseErrorKind::InvalidAtRule { name: "supports".to_string() }, &self.tokens[rule_start..prelude_end]),
+        }
+    }
+
+    /// Parses and evaluates `not (a)`, `(a) and (b) and ...` or `(a) or (b) or ...`
+    fn parse_supports_condition(&self, input: &mut ValueParser<'a>) -> Result<bool, ()> {
+        if input.try_parse(|i| i.expect_ident_matching("not")).is_ok() {
+            return self.parse_supports_in_parens(input).map(|supported| !supported);
+        }
+
+        let mut supported = self.parse_supports_in_parens(input)?;
+        let mut is_or = None;
+        while let Some(Token::Ident(keyword)) = input.peek() {
+            let or = match keyword.to_ascii_lowercase().as_str() {
+                "and" => false,
+                "or" => true,
+                _ => break,
+            };
+            // `and` and `or` can't be mixed without parentheses
+            if is_or.is_some_and(|is_or| is_or != or) {
+                return Err(());
+            }
+            is_or = Some(or);
+            input.next();
+            let next = self.parse_supports_in_parens(input)?;
+            supported = if or { supported || next } else { supported && next };
+        }
+        Ok(supported)
+    }
+
+    /// Parses and evaluates `(property: value)`, `selector(a > b)` or a condition in parentheses.
+    /// Other functions and parenthesized expressions are valid, but never supported.
+    fn parse_supports_in_parens(&self, input: &mut ValueParser<'a>) -> Result<bool, ()> {
+        match input.next() {
+            Some(Token::OpenParen) => {
+                let args = input.block_contents();
+                if let Ok(supported) = args.clone().try_parse(|i| {
+                    let supported = self.parse_supports_condition(i)?;
+                    i.expect_exhausted()?;
+                    Ok(supported)
+                }) {
+                    return Ok(supported);
+                }
+                let mut lookahead = args.clone();
+                match (lookahead.next(), lookahead.next()) {
+                    (Some(Token::Ident(_)), Some(Token::Colon)) => {
+                        // `!important` is not allowed in the condition
+                        let tokens = args.remaining_tokens();
+                        Ok(!split_important(tokens).1 && self.parse_declaration(tokens).is_ok())
+                    }
+                    _ => Ok(false),
+                }
+            }
+            Some(Token::Function(name)) => {
+                let args = input.block_contents();
+                if name.eq_ignore_ascii_case("selector") {
+                    // only a single complex selector is allowed, not a list
+                    Ok(parse_selector_list(args.remaining_tokens()).is_ok_and(|paths| paths.len() == 1))
+                } else {
+                    Ok(false)
+                }
+            }
+            _ => Err(()),
+        }
+    }
+
+    /// Consumes a `@keyframes name { from { ... } 50% { ... } to { ... } }` rule
+    fn consume_keyframes_rule(&mut self, stylesheet: &mut Stylesheet, layer: Option<&str>, media: &[MediaQueryList]) {
+        let rule_start = self.position;
+        let (prelude, block) = self.consume_at_rule_parts();
+        let prelude_end = rule_start + 1 + prelude.len();
+
+        let mut input = ValueParser::new(prelude);
+        let name = property_parser::parse_animation_name(&mut input).and_then(|name| input.expect_exhausted().map(|()| name));
+        let (name, block) = match (name, block) {
+            (Ok(name), Some(block)) => (name, block),
+            _ => {
+                self.warn(CssParseErrorKind::InvalidAtRule { name: "keyframes".to_string() }, &self.tokens[rule_start..prelude_end]);
+                return;
+            }
+        };
+
+        let mut nested = Parser { source: self.source, tokens: block, position: 0, warnings: Vec::new() };
+        let keyframes = nested.parse_keyframe_list();
+        self.warnings.append(&mut nested.warnings);
+        stylesheet.keyframes.push(KeyframesRule { name: name.0, keyframes, layer: layer.map(str::to_string), media: media.to_vec() });
+    }
+
+    /// Parses the `from { ... }`, `50% { ... }` rules of a `@keyframes` block. Like the selectors of
+    /// style rules, a list of offsets (`0%, 100% { }`) results in one `Keyframe` per offset.
+    fn parse_keyframe_list(&mut self) -> Vec<Keyframe> {
+        let mut keyframes = Vec::new();
+        while let Some(token) = self.peek() {
+            if *token == Token::Whitespace {
+                self.position += 1;
+                continue;
+            }
+            let (prelude, block) = match self.consume_qualified_rule_parts() {
+                Some(parts) => parts,
+                None => break,
+            };
+            let offsets = match parse_keyframe_offsets(prelude) {
+                Ok(offsets) => offsets,
+                Err(()) => {
+                    self.warn(CssParseErrorKind::InvalidSelector, prelude);
+                    continue;
+                }
+            };
+            // `!important` is not allowed in keyframes, these declarations are ignored
+            let (declarations, _) = self.parse_declaration_list(block);
+            keyframes.extend(offsets.into_iter().map(|offset| Keyframe { offset, declarations: declarations.clone() }));
+        }
+        keyframes
+    }
+
+    /// Consumes an at-rule (up to and including the `;` or the `{}` block)
+    fn consume_at_rule(&mut self) {
+        self.consume_at_rule_parts();
+    }
+
+    /// Must be called right after a token opening a block: returns the tokens
+    /// of the block and advances past the closing token. If the block is never
+    /// closed, it is closed at the end of the stylesheet (with a warning).
+    fn consume_block(&mut self) -> &'a [SpannedToken] {
+        let start = self.position;
+        let end = find_block_end(self.tokens, start);
+        if end >= self.tokens.len() {
+            self.warn(CssParseErrorKind::UnbalancedBrace, &self.tokens[start - 1..start]);
+        }
+        self.position = (end + 1).min(self.tokens.len());
+        &self.tokens[start..end.min(self.tokens.len())]
+    }
+
+    /// Consumes a `selector { declarations }` rule. Since a `RuleBlock` only has one
+    /// selector, selector lists (`a, b { }`) result in one `RuleBlock` per selector.
+    fn consume_qualified_rule(&mut self) -> Vec<RuleBlock> {
+        let (prelude, block) = match self.consume_qualified_rule_parts() {
+            Some(parts) => parts,
+            None => return Vec::new(),
+        };
+
+        let paths = match parse_selector_list(prelude) {
+            Ok(paths) => paths,
+            Err(()) => {
+                // invalid selectors invalidate the entire rule
+                self.warn(CssParseErrorKind::InvalidSelector, prelude);
+                return Vec::new();
+            }
+        };
+        let (declarations, important_declarations) = self.parse_declaration_list(block);
+
+        paths
+            .into_iter()
+            .map(|path| RuleBlock {
+                path,
+                declarations: declarations.clone(),
+                important_declarations: important_declarations.clone(),
+                layer: None,
+                media: Vec::new(),
+                source_index: 0,
+            })
+            .collect()
+    }
+
+    /// Consumes the prelude and the `{}` block of a qualified rule, returns `None` (with a warning)
+    /// if the stylesheet ends before the block
+    fn consume_qualified_rule_parts(&mut self) -> Option<(&'a [SpannedToken], &'a [SpannedToken])> {
+        let prelude_start = self.position;
+        while let Some(token) = self.peek() {
+            self.position += 1;
+            match token {
+                Token::OpenCurly => break,
+                t if t.closing_token().is_some() => {
+                    self.consume_block();
+                }
+                _ => {}
+            }
+        }
+
+        if self.position == self.tokens.len() && self.tokens.last().map(|t| &t.token) != Some(&Token::OpenCurly) {
+            self.warn(CssParseErrorKind::UnexpectedEof, &[]);
+            return None;
+        }
+
+        let prelude = &self.tokens[prelude_start..self.position - 1];
+        Some((prelude, self.consume_block()))
+    }
+
+    /// Parses the declarations of a block, returns the normal and the `!important` declarations
+    fn parse_declaration_list(&mut self, block: &'a [SpannedToken]) -> (Vec<Declaration>, Vec<Declaration>) {
+        let mut declarations = Vec::new();
+        let mut important_declarations = Vec::new();
+        let mut i = 0;
+        while i < block.len() {
+            match &block[i].token {
+                Token::Whitespace | Token::Semicolon => {
+                    i += 1;
+                }
+                _ => {
+                    // a declaration ends at the next top-level semicolon
+                    let start = i;
+                    while i < block.len() && block[i].token != Token::Semicolon {
+                        if block[i].token.closing_token().is_some() {
+                            i = find_block_end(block, i + 1);
+                        }
+                        i += 1;
+                    }
+                    let end = i.min(block.len());
+                    // invalid declarations are skipped, the rest of the block is still valid
+                    match self.parse_declaration(&block[start..end]) {
+                        Ok((parsed, false)) => declarations.extend(parsed),
+                        Ok((parsed, true)) => important_declarations.extend(parsed),
+                        Err((kind, tokens)) => self.warn(kind, tokens),
+                    }
+                }
+            }
+        }
+        (declarations, important_declarations)
+    }
+
+    /// Parses one `key: value` declaration. Shorthands expand to one declaration per longhand.
+    /// Returns the declarations and whether they are `!important`.
+    /// On failure, returns the error and the offending tokens.
+    fn parse_declaration(&self, tokens: &'a [SpannedToken]) -> Result<(Vec<Declaration>, bool), DeclarationError<'a>> {
+        let mut input = ValueParser::new(tokens);
+        let key = match input.next() {
+            Some(Token::Ident(key)) => key.to_ascii_lowercase(),
+            Some(_) => return Err((CssParseErrorKind::UnexpectedToken, tokens)),
+            None => return Err((CssParseErrorKind::UnexpectedEof, &[])),
+        };
+        let key_tokens = &tokens[..tokens.len() - input.remaining_tokens().len()];
+        match input.next() {
+            Some(Token::Colon) => {}
+            Some(_) => {
+                let remaining = input.remaining_tokens();
+                let offending = &tokens[tokens.len() - remaining.len() - 1..tokens.len() - remaining.len()];
+                return Err((CssParseErrorKind::UnexpectedToken, offending));
+            }
+            None => return Err((CssParseErrorKind::UnexpectedEof, &[])),
+        }
+        input.skip_whitespace();
+
+        let property_key = match DeclarationKey::from_str(&key) {
+            Some(k) => k,
+            None => return Err((CssParseErrorKind::UnknownProperty(key), key_tokens)),
+        };
+
+        let (value_tokens, important) = split_important(input.remaining_tokens());
+        let mut input = ValueParser::new(value_tokens);
+
+        let mut lookahead = input.clone();
+        let declarations = match lookahead.expect_function() {
+            Ok((function, args)) if function.eq_ignore_ascii_case("var") && lookahead.is_exhausted() => {
+                parse_dynamic_declaration(key, property_key, value_tokens, args)?
+            }
+            _ => match property_key.parse_value(&mut input) {
+                Ok(properties) => properties.into_iter().map(Declaration::new_static).collect(),
+                Err(()) => return Err((CssParseErrorKind::InvalidValue { property: key }, value_tokens)),
+            },
+        };
+
+        Ok((declarations, important))
+    }
+}
+
+/// Key of a declaration, either a regular property or a shorthand for several properties
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum DeclarationKey {
+    Longhand(PType),
+    Shorthand(CombinedPropertyType),
+}
+
+impl DeclarationKey {
+    fn from_str(key: &str) -> Option<Self> {
+        PType::from_str(key)
+            .map(DeclarationKey::Longhand)
+            .or_else(|| CombinedPropertyType::from_str(key).map(DeclarationKey::Shorthand))
+    }
+
+    /// Parses the value of the declaration into the properties it sets
+    fn parse_value(self, input: &mut ValueParser) -> Result<Vec<Property>, ()> {
+        match self {
+            DeclarationKey::Longhand(property_type) => property_parser::parse_property(property_type, input).map(|p| vec![p]),
+            DeclarationKey::Shorthand(shorthand) => property_parser::parse_shorthand(shorthand, input),
+        }
+    }
+}
+
+/// Error of an invalid declaration and the offending tokens
+type DeclarationError<'a> = (CssParseErrorKind, &'a [SpannedToken]);
+
+/// Parses the arguments of a `var(--my_id, default)` value into a `DynamicProperty`.
+/// For shorthands, every longhand becomes a `DynamicProperty` with the same ID.
+fn parse_dynamic_declaration<'a>(
+    key: String,
+    property_key: DeclarationKey,
+    value_tokens: &'a [SpannedToken],
+    mut args: ValueParser<'a>,
+) -> Result<Vec<Declaration>, DeclarationError<'a>> {
+    let dynamic_id = match args.next() {
+        Some(Token::Ident(id)) if id.starts_with("--") && id.len() > 2 => id[2..].to_string(),
+        _ => return Err((CssParseErrorKind::InvalidValue { property: key }, value_tokens)),
+    };
+
+    match args.next() {
+        Some(Token::Comma) if !args.is_exhausted() => {}
+        None | Some(Token::Comma) => {
+            return Err((CssParseErrorKind::MissingVarDefault { property: key, dynamic_id }, value_tokens))
+        }
+        Some(_) => return Err((CssParseErrorKind::InvalidValue { property: key }, value_tokens)),
+    }
+
+    args.skip_whitespace();
+    let default_tokens = args.remaining_tokens();
+
+    match property_key.parse_value(&mut args) {
+        Ok(default_values) => Ok(default_values
+            .into_iter()
+            .map(|default_value| Declaration::new_dynamic(DynamicProperty { dynamic_id: dynamic_id.clone(), default_value }))
+            .collect()),
+        Err(()) => Err((CssParseErrorKind::InvalidVarDefault { property: key, dynamic_id }, default_tokens)),
+    }
+}
+
+/// Splits a trailing `!important` off the value of a declaration,
+/// returns the remaining tokens of the value and whether `!important` was found
+fn split_important(value_tokens: &[SpannedToken]) -> (&[SpannedToken], bool) {
+    let trimmed = trim_whitespace(value_tokens);
+    if let [rest @ .., last] = trimmed {
+        if let Token::Ident(ident) = &last.token {
+            if ident.eq_ignore_ascii_case("important") {
+                if let [rest @ .., bang] = trim_whitespace(rest) {
+                    if bang.token == Token::Delim('!') {
+                        return (rest, true);
+                    }
+                }
+            }
+        }
+    }
+    (value_tokens, false)
+}
+
+/// Parses the comma-separated layer names of an `@layer` rule, i.e. `base, theme.dark`
+fn parse_layer_names(prelude: &[SpannedToken]) -> Result<Vec<String>, ()> {
+    let mut input = ValueParser::new(prelude);
+    if input.is_exhausted() {
+        return Ok(Vec::new());
+    }
+    input.parse_comma_separated(|i| {
+        let mut name = i.expect_ident()?.to_string();
+        // no whitespace allowed in `theme.dark`
+        while let Some(Token::Delim('.')) = i.clone().next_including_whitespace() {
+            i.next_including_whitespace();
+            match i.next_including_whitespace() {
+                Some(Token::Ident(part)) => {
+                    name.push('.');
+                    name.push_str(part);
+                }
+                _ => return Err(()),
+            }
+        }
+        Ok(name)
+    })
+}
+
+/// Removes leading and trailing whitespace tokens
+fn trim_whitespace(tokens: &[SpannedToken]) -> &[SpannedToken] {
+    let start = tokens.iter().position(|t| t.token != Token::Whitespace).unwrap_or(tokens.len());
+    let end = tokens.iter().rposition(|t| t.token != Token::Whitespace).map(|i| i + 1).unwrap_or(start);
+    &tokens[start..end]
+}
+
+/// Parses the offsets of a keyframe: `from`, `to` or percentages between `0%` and `100%`
+fn parse_keyframe_offsets(prelude: &[SpannedToken]) -> Result<Vec<PercentageValue>, ()> {
+    ValueParser::new(prelude).parse_comma_separated(|i| match i.next() {
+        Some(Token::Ident(keyword)) if keyword.eq_ignore_ascii_case("from") => Ok(PercentageValue::new(0.0)),
+        Some(Token::Ident(keyword)) if keyword.eq_ignore_ascii_case("to") => Ok(PercentageValue::new(100.0)),
+        Some(Token::Percentage { value, .. }) if (0.0..=100.0).contains(value) => Ok(PercentageValue::new(*value)),
+        _ => Err(()),
+    })
+}
+
+/// Evaluates an `@supports` condition, i.e. `(display: grid) and (not selector(a:has(b)))`:
+/// returns whether this crate can parse the declarations and selectors in it.
+/// Fails if the condition itself is invalid.
+pub fn parse_supports_condition(condition: &str) -> Result<bool, CssParseError> {
+    let tokens = Tokenizer::tokenize(condition);
+    let parser = Parser { source: condition, tokens: &tokens, position: 0, warnings: Vec::new() };
+    let mut input = ValueParser::new(&tokens);
+    parser
+        .parse_supports_condition(&mut input)
+        .and_then(|supported| input.expect_exhausted().map(|()| supported))
+        .map_err(|()| CssParseError::new(condition, CssParseErrorKind::InvalidAtRule { name: "supports".to_string() }, &tokens))
+}
+
+/// Parses a comma-separated media query list from a string, such as `screen and (width >= 600px), print`
+pub fn parse_media_query_list(media: &str) -> Result<MediaQueryList, CssParseError> {
+    let tokens = Tokenizer::tokenize(media);
+    match parse_media_queries(&tokens) {
+        (queries, true) => Ok(queries),
+        (_, false) => Err(CssParseError::new(media, CssParseErrorKind::InvalidAtRule { name: "media".to_string() }, &tokens)),
+    }
+}
+
+/// Parses the prelude of an `@media` rule. Invalid queries are replaced by `not all`,
+/// returns the queries and whether all of them were valid.
+fn parse_media_queries(tokens: &[SpannedToken]) -> (MediaQueryList, bool) {
+    let mut input = ValueParser::new(tokens);
+    if input.is_exhausted() {
+        return (MediaQueryList::default(), true);
+    }
+    let mut all_valid = true;
+    let queries = input
+        .split_commas()
+        .into_iter()
+        .map(|mut part| {
+            parse_media_query(&mut part).unwrap_or_else(|()| {
+                all_valid = false;
+                MediaQuery::never()
+            })
+        })
+        .collect();
+    (MediaQueryList(queries), all_valid)
+}
+
+/// Parses one media query, i.e. `not screen and (color)` or `(width < 600px) or (height < 400px)`
+fn parse_media_query(input: &mut ValueParser) -> Result<MediaQuery, ()> {
+    if let Ok(condition) = input.try_parse(|i| {
+        let condition = parse_media_condition(i, true)?;
+        i.expect_exhausted()?;
+        Ok(condition)
+    }) {
+        return Ok(MediaQuery { qualifier: None, media_type: MediaType::All, condition: Some(condition) });
+    }
+
+    let mut ident = input.expect_ident()?.to_ascii_lowercase();
+    let qualifier = match ident.as_str() {
+        "not" => Some(MediaQualifier::Not),
+        "only" => Some(MediaQualifier::Only),
+        _ => None,
+    };
+    if qualifier.is_some() {
+        ident = input.expect_ident()?.to_ascii_lowercase();
+    }
+    let media_type = match ident.as_str() {
+        "all" => MediaType::All,
+        "screen" => MediaType::Screen,
+        "print" => MediaType::Print,
+        "not" | "only" | "and" | "or" | "layer" => return Err(()),
+        _ => MediaType::Unknown(ident),
+    };
+
+    let condition = match input.next() {
+        None => None,
+        // `or` is not allowed after the media type
+        Some(Token::Ident(and)) if and.eq_ignore_ascii_case("and") => Some(parse_media_condition(input, false)?),
+        Some(_) => return Err(()),
+    };
+    input.expect_exhausted()?;
+    Ok(MediaQuery { qualifier, media_type, condition })
+}
+
+/// Parses `not (a)`, `(a) and (b) and ...` or `(a) or (b) or ...`
+fn parse_media_condition(input: &mut ValueParser, allow_or: bool) -> Result<MediaCondition, ()> {
+    if input.try_parse(|i| i.expect_ident_matching("not")).is_ok() {
+        return Ok(MediaCondition::Not(Box::new(parse_media_in_parens(input)?)));
+    }
+
+    let first = parse_media_in_parens(input)?;
+    let mut conditions = vec![first];
+    let mut is_or = None;
+    while let Some(Token::Ident(keyword)) = input.peek() {
+        let or = match keyword.to_ascii_lowercase().as_str() {
+            "and" => false,
+            "or" if allow_or => true,
+            _ => break,
+        };
+        // `and` and `or` can't be mixed without parentheses
+        if is_or.is_some_and(|is_or| is_or != or) {
+            return Err(());
+        }
+        is_or = Some(or);
+        input.next();
+        conditions.push(parse_media_in_parens(input)?);
+    }
+
+    Ok(match is_or {
+        None => conditions.remove(0),
+        Some(false) => MediaCondition::And(conditions),
+        Some(true) => MediaCondition::Or(conditions),
+    })
+}
+
+/// Parses a media feature or a condition in parentheses, i.e. `(width >= 600px)` or `((a) or (b))`
+fn parse_media_in_parens(input: &mut ValueParser) -> Result<MediaCondition, ()> {
+    match input.next() {
+        Some(Token::OpenParen) => {}
+        _ => return Err(()),
+    }
+    let mut args = input.block_contents();
+    if let Ok(feature) = args.clone().try_parse(|i| {
+        let feature = parse_media_feature(i)?;
+        i.expect_exhausted()?;
+        Ok(feature)
+    }) {
+        return Ok(MediaCondition::Feature(feature));
+    }
+    let condition = parse_media_condition(&mut args, true)?;
+    args.expect_exhausted()?;
+    Ok(condition)
+}
+
+/// Comparison in a media feature range, i.e. the `<=` in `(width <= 600px)`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum RangeOperator {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+}
+
+impl RangeOperator {
+    /// Returns the operator with the operands swapped, i.e. `600px < width` => `width > 600px`
+    fn flip(self) -> Self {
+        use self::RangeOperator::*;
+        match self {
+            Less => Greater,
+            LessOrEqual => GreaterOrEqual,
+            Greater => Less,
+            GreaterOrEqual => LessOrEqual,
+            Equal => Equal,
+        }
+    }
+
+    /// Restricts the range to `feature <operator> value`
+    fn apply(self, range: &mut MediaRange, value: f32) {
+        let bound = |inclusive| Some(RangeBound { value: FloatValue::new(value), inclusive });
+        match self {
+            RangeOperator::Less => range.max = bound(false),
+            RangeOperator::LessOrEqual => range.max = bound(true),
+            RangeOperator::Greater => range.min = bound(false),
+            RangeOperator::GreaterOrEqual => range.min = bound(true),
+            RangeOperator::Equal => {
+                range.min = bound(true);
+                range.max = bound(true);
+            }
+        }
+    }
+}
+
+/// Parses a media feature (without the parentheses): `width: 600px`, `min-width: 600px`,
+/// `orientation`, `width >= 600px` or `400px < width <= 700px`
+fn parse_media_feature(input: &mut ValueParser) -> Result<MediaFeature, ()> {
+    let mut lookahead = input.clone();
+    if let Some(Token::Ident(name)) = lookahead.next() {
+        let name = name.to_ascii_lowercase();
+        match lookahead.next() {
+            // boolean context, i.e. `(orientation)`
+            None => {
+                *input = lookahead;
+                return match name.as_str() {
+                    "orientation" => Ok(MediaFeature::Orientation(None)),
+                    "prefers-color-scheme" => Ok(MediaFeature::PrefersColorScheme(None)),
+                    "prefers-reduced-motion" => Ok(MediaFeature::PrefersReducedMotion(None)),
+                    _ => {
+                        // range features match if they are not zero
+                        let feature = parse_range_feature(&name).ok_or(())?;
+                        let min = Some(RangeBound { value: FloatValue::new(0.0), inclusive: false });
+                        Ok(MediaFeature::Range(feature, MediaRange { min, max: None }))
+                    }
+                };
+            }
+            Some(Token::Colon) => {
+                *input = lookahead;
+                return parse_plain_media_feature(&name, input);
+            }
+            _ => {}
+        }
+    }
+
+    // range syntax: `name op value`, `value op name` or `value op name op value`
+    let mut range = MediaRange::default();
+    let feature = match input.peek() {
+        Some(Token::Ident(name)) => {
+            input.next();
+            let feature = parse_range_feature(&name.to_ascii_lowercase()).ok_or(())?;
+            let operator = parse_range_operator(input)?;
+            operator.apply(&mut range, parse_media_value(feature, input)?);
+            feature
+        }
+        _ => {
+            // the unit of the value depends on the feature after it
+            let mut value_input = input.clone();
+            input.next();
+            let first_operator = parse_range_operator(input)?;
+            let feature = parse_range_feature(&input.expect_ident()?.to_ascii_lowercase()).ok_or(())?;
+            first_operator.flip().apply(&mut range, parse_media_value(feature, &mut value_input)?);
+            if !input.is_exhausted() {
+                let second_operator = parse_range_operator(input)?;
+                let is_less = |o| matches!(o, RangeOperator::Less | RangeOperator::LessOrEqual);
+                let is_greater = |o| matches!(o, RangeOperator::Greater | RangeOperator::GreaterOrEqual);
+                if !(is_less(first_operator) && is_less(second_operator) || is_greater(first_operator) && is_greater(second_operator)) {
+                    return Err(());
+                }
+                second_operator.apply(&mut range, parse_media_value(feature, input)?);
+            }
+            feature
+        }
+    };
+    Ok(MediaFeature::Range(feature, range))
+}
+
+/// Parses the value of a `name: value` media feature, the name can have a `min-` or `max-` prefix
+fn parse_plain_media_feature(name: &str, input: &mut ValueParser) -> Result<MediaFeature, ()> {
+    let keyword = |input: &mut ValueParser| input.expect_ident().map(|i| i.to_ascii_lowercase());
+    match name {
+        "orientation" => match keyword(input)?.as_str() {
+            "portrait" => Ok(MediaFeature::Orientation(Some(Orientation::Portrait))),
+            "landscape" => Ok(MediaFeature::Orientation(Some(Orientation::Landscape))),
+            _ => Err(()),
+        },
+        "prefers-color-scheme" => match keyword(input)?.as_str() {
+            "light" => Ok(MediaFeature::PrefersColorScheme(Some(ColorScheme::Light))),
+            "dark" => Ok(MediaFeature::PrefersColorScheme(Some(ColorScheme::Dark))),
+            _ => Err(()),
+        },
+        "prefers-reduced-motion" => match keyword(input)?.as_str() {
+            "no-preference" => Ok(MediaFeature::PrefersReducedMotion(Some(ReducedMotion::NoPreference))),
+            "reduce" => Ok(MediaFeature::PrefersReducedMotion(Some(ReducedMotion::Reduce))),
+            _ => Err(()),
+        },
+        _ => {
+            let (operator, name) = match (name.strip_prefix("min-"), name.strip_prefix("max-")) {
+                (Some(name), _) => (RangeOperator::GreaterOrEqual, name),
+                (_, Some(name)) => (RangeOperator::LessOrEqual, name),
+                _ => (RangeOperator::Equal, name),
+            };
+            let feature = parse_range_feature(name).ok_or(())?;
+            let mut range = MediaRange::default();
+            operator.apply(&mut range, parse_media_value(feature, input)?);
+            Ok(MediaFeature::Range(feature, range))
+        }
+    }
+}
+
+fn parse_range_feature(name: &str) -> Option<RangeFeature> {
+    match name {
+        "width" => Some(RangeFeature::Width),
+        "height" => Some(RangeFeature::Height),
+        "resolution" => Some(RangeFeature::Resolution),
+        _ => None,
+    }
+}
+
+/// Parses `<`, `<=`, `>`, `>=` or `=`
+fn parse_range_operator(input: &mut ValueParser) -> Result<RangeOperator, ()> {
+    let operator = match input.next() {
+        Some(Token::Delim('=')) => return Ok(RangeOperator::Equal),
+        Some(Token::Delim('<')) => RangeOperator::Less,
+        Some(Token::Delim('>')) => RangeOperator::Greater,
+        _ => return Err(()),
+    };
+    // no whitespace allowed in `<=`
+    match input.clone().next_including_whitespace() {
+        Some(Token::Delim('=')) => {
+            input.next_including_whitespace();
+            Ok(match operator {
+                RangeOperator::Less => RangeOperator::LessOrEqual,
+                _ => RangeOperator::GreaterOrEqual,
+            })
+        }
+        _ => Ok(operator),
+    }
+}
+
+/// Parses the value of a range feature: a length in pixels for `width` / `height`,
+/// a resolution in `dppx` for `resolution`
+fn parse_media_value(feature: RangeFeature, input: &mut ValueParser) -> Result<f32, ()> {
+    match feature {
+        RangeFeature::Width | RangeFeature::Height => {
+            let value = property_parser::parse_pixel_value(input)?;
+            match value.metric {
+                SizeMetric::Percent => Err(()),
+                _ => Ok(value.to_pixels(0.0)),
+            }
+        }
+        RangeFeature::Resolution => match input.next() {
+            Some(Token::Dimension { value, unit, .. }) => match unit.to_ascii_lowercase().as_str() {
+                "dppx" | "x" => Ok(*value),
+                "dpi" => Ok(*value / 96.0),
+                "dpcm" => Ok(*value * 2.54 / 96.0),
+                _ => Err(()),
+            },
+            _ => Err(()),
+        },
+    }
+}
+
+/// Parses a comma-separated list of selectors from a string, such as `div.a > p, #b`
+pub fn parse_selectors(selectors: &str) -> Result<Vec<Path>, CssParseError> {
+    let tokens = Tokenizer::tokenize(selectors);
+    parse_selector_list(&tokens).map_err(|()| CssParseError::new(selectors, CssParseErrorKind::InvalidSelector, &tokens))
+}
+
+/// Parses exactly one selector from a string, such as `div.a > p`
+pub fn parse_selector(selector: &str) -> Result<Path, CssParseError> {
+    let tokens = Tokenizer::tokenize(selector);
+    let mut input = ValueParser::new(&tokens);
+    input
+        .try_parse(|i| {
+            let path = parse_complex_selector(i)?;
+            i.expect_exhausted()?;
+            Ok(path)
+        })
+        .map_err(|()| CssParseError::new(selector, CssParseErrorKind::InvalidSelector, &tokens))
+}
+
+/// Parses a comma-separated list of selectors, such as `div.a > p, #b`
+pub(crate) fn parse_selector_list(tokens: &[SpannedToken]) -> Result<Vec<Path>, ()> {
+    ValueParser::new(tokens)
+        .split_commas()
+        .into_iter()
+        .map(|mut part| parse_complex_selector(&mut part))
+        .collect()
+}
+
+/// Parses one complex selector, such as `div.a > p:hover`
+fn parse_complex_selector(input: &mut ValueParser) -> Result<Path, ()> {
+    let mut selectors = Vec::new();
+    let mut pseudo_element = None;
+    input.skip_whitespace();
+
+    loop {
+        let compound_start = selectors.len();
+        parse_compound_selector(input, &mut selectors)?;
+
+        // the pseudo-element has to be at the very end, `::before` is the same as `*::before`
+        if let Some(p) = parse_pseudo_element(input)? {
+            if selectors.len() == compound_start {
+                selectors.push(PathSelector::Global);
+            }
+            pseudo_element = Some(p);
+            input.expect_exhausted()?;
+            break;
+        }
+        if selectors.len() == compound_start {
+            return Err(());
+        }
+
+        // parse the combinator
+        let mut has_whitespace = false;
+        while let Some(Token::Whitespace) = input.clone().next_including_whitespace() {
+            input.next_including_whitespace();
+            has_whitespace = true;
+        }
+        let combinator = match input.peek() {
+            None => break,
+            Some(Token::Delim('>')) => PathSelector::DirectChildren,
+            Some(Token::Delim('+')) => PathSelector::AdjacentSibling,
+            Some(Token::Delim('~')) => PathSelector::GeneralSibling,
+            Some(_) if has_whitespace => {
+                selectors.push(PathSelector::Children);
+                continue;
+            }
+            Some(_) => return Err(()),
+        };
+        input.next();
+        input.skip_whitespace();
+        selectors.push(combinator);
+    }
+
+    Ok(Path { selectors, pseudo_element })
+}
+
+/// Parses a pseudo-element such as `::before` (or `:before`), returns `None` if the next
+/// tokens are not a pseudo-element, and an error for an unknown pseudo-element
+fn parse_pseudo_element(input: &mut ValueParser) -> Result<Option<PseudoElement>, ()> {
+    let mut lookahead = input.clone();
+    if lookahead.next_including_whitespace() != Some(&Token::Colon) {
+        return Ok(None);
+    }
+    let single_colon = match lookahead.clone().next_including_whitespace() {
+        Some(Token::Colon) => {
+            lookahead.next_including_whitespace();
+            false
+        }
+        _ => true,
+    };
+    let pseudo_element = match lookahead.next_including_whitespace() {
+        Some(Token::Ident(name)) => PseudoElement::from_str(name),
+        _ => None,
+    };
+    match pseudo_element {
+        Some(p) if !single_colon || p.allows_single_colon() => {
+            *input = lookahead;
+            Ok(Some(p))
+        }
+        // a pseudo-class
+        _ if single_colon => Ok(None),
+        _ => Err(()),
+    }
+}
+
+/// Parses a relative selector of `:has()`, which may start with a combinator, i.e. `> p`.
+/// The descendant combinator is implied and not stored if there is no leading combinator.
+fn parse_relative_selector(input: &mut ValueParser) -> Result<Path, ()> {
+    let combinator = match input.peek() {
+        Some(Token::Delim('>')) => Some(PathSelector::DirectChildren),
+        Some(Token::Delim('+')) => Some(PathSelector::AdjacentSibling),
+        Some(Token::Delim('~')) => Some(PathSelector::GeneralSibling),
+        _ => None,
+    };
+    if combinator.is_some() {
+        input.next();
+    }
+    let mut path = parse_complex_selector(input)?;
+    path.selectors.splice(0..0, combinator);
+    Ok(path)
+}
+
+/// Parses a compound selector (i.e. `div#id.class:hover`) until the next combinator
+fn parse_compound_selector(input: &mut ValueParser, selectors: &mut Vec<PathSelector>) -> Result<(), ()> {
+    loop {
+        let mut lookahead = input.clone();
+        match lookahead.next_including_whitespace() {
+            Some(Token::Delim('*')) => {
+                input.next_including_whitespace();
+                selectors.push(PathSelector::Global);
+            }
+            Some(Token::Ident(tag)) => {
+                input.next_including_whitespace();
+                let tag = NodeTypeTag::from_str(tag).ok_or(())?;
+                selectors.push(PathSelector::Type(tag));
+            }
+            Some(Token::Hash { value, is_id: true }) => {
+                input.next_including_whitespace();
+                selectors.push(PathSelector::Id(value.clone()));
+            }
+            Some(Token::Delim('.')) => {
+                input.next_including_whitespace();
+                match input.next_including_whitespace() {
+                    Some(Token::Ident(class)) => selectors.push(PathSelector::Class(class.clone())),
+                    _ => return Err(()),
+                }
+            }
+            Some(Token::OpenSquare) => {
+                input.next_including_whitespace();
+                let mut args = input.block_contents();
+                selectors.push(PathSelector::Attribute(parse_attribute_selector(&mut args)?));
+            }
+            Some(Token::Colon) => {
+                if parse_pseudo_element(&mut input.clone())?.is_some() {
+                    return Ok(());
+                }
+                input.next_including_whitespace();
+                let pseudo = parse_pseudo_selector(input)?;
+                selectors.push(PathSelector::PseudoSelector(pseudo));
+            }
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Parses the contents of an attribute selector (without the brackets), i.e. `data-state ^= "open" i`
+fn parse_attribute_selector(input: &mut ValueParser) -> Result<AttributeSelector, ()> {
+    let name = input.expect_ident()?.to_string();
+    let operator = match input.next() {
+        None => {
+            return Ok(AttributeSelector {
+                name,
+                operator: AttributeOperator::Exists,
+                value: String::new(),
+                case: AttributeCase::Default,
+            })
+        }
+        Some(Token::Delim('=')) => AttributeOperator::Equals,
+        Some(Token::Delim(c)) => {
+            let operator = match c {
+                '~' => AttributeOperator::Includes,
+                '|' => AttributeOperator::DashMatch,
+                '^' => AttributeOperator::Prefix,
+                '$' => AttributeOperator::Suffix,
+                '*' => AttributeOperator::Substring,
+                _ => return Err(()),
+            };
+            // no whitespace allowed between the two characters of the operator
+            match input.next_including_whitespace() {
+                Some(Token::Delim('=')) => operator,
+                _ => return Err(()),
+            }
+        }
+        Some(_) => return Err(()),
+    };
+    let value = match input.next() {
+        Some(Token::Ident(v)) | Some(Token::QuotedString(v)) => v.clone(),
+        _ => return Err(()),
+    };
+    let case = match input.next() {
+        None => AttributeCase::Default,
+        Some(Token::Ident(flag)) if flag.eq_ignore_ascii_case("i") => AttributeCase::Insensitive,
+        Some(Token::Ident(flag)) if flag.eq_ignore_ascii_case("s") => AttributeCase::Sensitive,
+        Some(_) => return Err(()),
+    };
+    input.expect_exhausted()?;
+    Ok(AttributeSelector { name, operator, value, case })
+}
+
+fn parse_pseudo_selector(input: &mut ValueParser) -> Result<PathPseudoSelector, ()> {
+    match input.next_including_whitespace() {
+        Some(Token::Ident(name)) => match name.to_ascii_lowercase().as_str() {
+            "first-child" => Ok(PathPseudoSelector::FirstChild),
+            "last-child" => Ok(PathPseudoSelector::LastChild),
+            "only-child" => Ok(PathPseudoSelector::OnlyChild),
+            "first-of-type" => Ok(PathPseudoSelector::FirstOfType),
+            "last-of-type" => Ok(PathPseudoSelector::LastOfType),
+            "only-of-type" => Ok(PathPseudoSelector::OnlyOfType),
+            "empty" => Ok(PathPseudoSelector::Empty),
+            "root" => Ok(PathPseudoSelector::Root),
+            "hover" => Ok(PathPseudoSelector::Hover),
+            "active" => Ok(PathPseudoSelector::Active),
+            "focus" => Ok(PathPseudoSelector::Focus),
+            "focus-visible" => Ok(PathPseudoSelector::FocusVisible),
+            "focus-within" => Ok(PathPseudoSelector::FocusWithin),
+            "enabled" => Ok(PathPseudoSelector::Enabled),
+            "disabled" => Ok(PathPseudoSelector::Disabled),
+            "checked" => Ok(PathPseudoSelector::Checked),
+            "indeterminate" => Ok(PathPseudoSelector::Indeterminate),
+            "read-only" => Ok(PathPseudoSelector::ReadOnly),
+            "placeholder-shown" => Ok(PathPseudoSelector::PlaceholderShown),
+            "visited" => Ok(PathPseudoSelector::Visited),
+            "target" => Ok(PathPseudoSelector::Target),
+            _ => Err(()),
+        },
+        Some(Token::Function(name)) if name.to_ascii_lowercase().starts_with("nth-") => {
+            let name = name.to_ascii_lowercase();
+            let mut args = input.block_contents();
+            let nth = parse_nth_child(&mut args)?;
+            match name.as_str() {
+                "nth-child" => Ok(PathPseudoSelector::NthChild(nth, parse_nth_of(&mut args)?)),
+                "nth-last-child" => Ok(PathPseudoSelector::NthLastChild(nth, parse_nth_of(&mut args)?)),
+                "nth-of-type" => args.expect_exhausted().map(|_| PathPseudoSelector::NthOfType(nth)),
+                "nth-last-of-type" => args.expect_exhausted().map(|_| PathPseudoSelector::NthLastOfType(nth)),
+                _ => Err(()),
+            }
+        }
+        Some(Token::Function(name)) => {
+            let name = name.to_ascii_lowercase();
+            let parse_selector: fn(&mut ValueParser) -> Result<Path, ()> = match name.as_str() {
+                "has" => parse_relative_selector,
+                _ => parse_complex_selector,
+            };
+            let list = input
+                .block_contents()
+                .split_commas()
+                .into_iter()
+                .map(|mut part| parse_selector(&mut part))
+                .collect::<Result<Vec<_>, ()>>()?;
+            if list.iter().any(|path| path.pseudo_element.is_some()) {
+                return Err(());
+            }
+            match name.as_str() {
+                "is" => Ok(PathPseudoSelector::Is(list)),
+                "not" => Ok(PathPseudoSelector::Not(list)),
+                "where" => Ok(PathPseudoSelector::Where(list)),
+                "has" => Ok(PathPseudoSelector::Has(list)),
+                _ => Err(()),
+            }
+        }
+        _ => Err(()),
+    }
+}
+
+/// Parses the `An+B` argument of `:nth-child()`: `3`, `even`, `odd`, `2n + 1`, `-n+3`, `2n-1`, etc.
+fn parse_nth_child(input: &mut ValueParser) -> Result<NthChildSelector, ()> {
+    // values out of the range of an i32 saturate
+    let integer = |value: f32, is_integer: bool| if is_integer { Ok(value as i32) } else { Err(()) };
+    // `A` and the rest of the token after the `n`, i.e. `-1` for `2n-1`
+    let (repeat, rest) = match input.next() {
+        Some(Token::Number { value, is_integer, .. }) => return Ok(NthChildSelector::Number(integer(*value, *is_integer)?)),
+        Some(Token::Ident(i)) if i.eq_ignore_ascii_case("even") => return Ok(NthChildSelector::Even),
+        Some(Token::Ident(i)) if i.eq_ignore_ascii_case("odd") => return Ok(NthChildSelector::Odd),
+        Some(Token::Dimension { value, is_integer, unit, .. }) => (integer(*value, *is_integer)?, unit.as_str()),
+        Some(Token::Ident(i)) => match i.strip_prefix('-') {
+            Some(rest) => (-1, rest),
+            None => (1, i.as_str()),
+        },
+        // `+n`, no whitespace allowed after the `+`
+        Some(Token::Delim('+')) => match input.next_including_whitespace() {
+            Some(Token::Ident(i)) if !i.starts_with('-') => (1, i.as_str()),
+            _ => return Err(()),
+        },
+        _ => return Err(()),
+    };
+
+    let rest = rest.to_ascii_lowercase();
+    let offset = match rest.as_str() {
+        "n" => match input.peek() {
+            None => 0,
+            // the `of S` part of `:nth-child()` follows
+            Some(Token::Ident(i)) if i.eq_ignore_ascii_case("of") => 0,
+            _ => match input.next() {
+                Some(Token::Number { value, is_integer, has_sign: true }) => integer(*value, *is_integer)?,
+                Some(Token::Delim(sign @ ('+' | '-'))) => match input.next() {
+                    Some(Token::Number { value, is_integer, has_sign: false }) => {
+                        integer(if *sign == '-' { -*value } else { *value }, *is_integer)?
+                    }
+                    _ => return Err(()),
+                },
+                _ => return Err(()),
+            },
+        },
+        "n-" => match input.next() {
+            Some(Token::Number { value, is_integer, has_sign: false }) => integer(-*value, *is_integer)?,
+            _ => return Err(()),
+        },
+        // `n-1`: the tokenizer reads the offset as part of the identifier
+        _ => match rest.strip_prefix("n-") {
+            Some(digits) if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) => {
+                integer(-digits.parse::<f32>().map_err(|_| ())?, true)?
+            }
+            _ => return Err(()),
+        },
+    };
+    Ok(NthChildSelector::Pattern(NthChildPattern { repeat, offset }))
+}
+
+/// Parses the optional `of S` part of `:nth-child()`, returns an empty list if it is missing
+fn parse_nth_of(input: &mut ValueParser) -> Result<Vec<Path>, ()> {
+    match input.next() {
+        None => Ok(Vec::new()),
+        Some(Token::Ident(of)) if of.eq_ignore_ascii_case("of") => {
+            let list = parse_selector_list(input.remaining_tokens())?;
+            if list.iter().any(|path| path.pseudo_element.is_some()) {
+                return Err(());
+            }
+            Ok(list)
+        }
+        Some(_) => Err(()),
+    }
+}
+
+#[test]
+fn test_parse_stylesheet() {
+    use crate::property::*;
+
+    let css = "
+        /* comment */
+        div#my_id .my_class > p:hover, * {
+            width: 100px;
+            color: #ff0000;
+            background: linear-gradient(to right, red, blue 50%);
+        }
+        @charset \"utf-8\";
+    ";
+    let stylesheet = parse_stylesheet(css).unwrap();
+    let declarations = vec![
+        Declaration::new_static(Property::Width(Value::Exact(LayoutWidth(PixelValue::px(100.0))))),
+        Declaration::new_static(Property::TextColor(Value::Exact(StyleTextColor(ColorU::RED)))),
+        Declaration::new_static(Property::BackgroundContent(Value::Exact(vec![
+            StyleBackgroundContent::LinearGradient(LinearGradient {
+                direction: Direction::FromTo(DirectionCorner::Left, DirectionCorner::Right),
+                extend_mode: ExtendMode::Clamp,
+                stops: vec![
+                    NormalizedLinearColorStop { offset: PercentageValue::new(0.0), color: ColorU::RED },
+                    NormalizedLinearColorStop { offset: PercentageValue::new(50.0), color: ColorU::BLUE },
+                ],
+            }),
+        ]))),
+    ];
+
+    assert_eq!(stylesheet, Stylesheet {
+        rules: vec![
+            RuleBlock {
+                path: Path {
+                    selectors: vec![
+                        PathSelector::Type(NodeTypeTag::Div),
+                        PathSelector::Id("my_id".to_string()),
+                        PathSelector::Children,
+                        PathSelector::Class("my_class".to_string()),
+                        PathSelector::DirectChildren,
+                        PathSelector::Type(NodeTypeTag::P),
+                        PathSelector::PseudoSelector(PathPseudoSelector::Hover),
+                    ],
+                    pseudo_element: None,
+                },
+                declarations: declarations.clone(),
+                important_declarations: Vec::new(),
+                layer: None,
+                media: Vec::new(),
+                source_index: 0,
+            },
+            RuleBlock {
+                path: Path { selectors: vec![PathSelector::Global], pseudo_element: None },
+                declarations,
+                important_declarations: Vec::new(),
+                layer: None,
+                media: Vec::new(),
+                source_index: 1,
+            },
+        ],
+        ..Default::default()
+    });
+}
+
+#[test]
+fn test_parse_stylesheet_errors() {
+    let error = |css: &str| parse_stylesheet(css).unwrap_err();
+    let location = |line, column| SourceLocation { line, column };
+
+    let e = error("div { widht: 5px; }");
+    assert_eq!(e.kind, CssParseErrorKind::UnknownProperty("widht".to_string()));
+    assert_eq!(e.span, SourceSpan { start: location(1, 7), end: location(1, 12) });
+    assert_eq!(e.token, "widht");
+
+    let e = error("div {\n  width: 5 apples;\n}");
+    assert_eq!(e.kind, CssParseErrorKind::InvalidValue { property: "width".to_string() });
+    assert_eq!(e.span, SourceSpan { start: location(2, 10), end: location(2, 18) });
+    assert_eq!(e.token, "5 apples");
+    assert_eq!(e.to_string(), "2:10: invalid value for \"width\": \"5 apples\"");
+
+    assert_eq!(error("div { font-size: auto; }").kind, CssParseErrorKind::InvalidValue { property: "font-size".to_string() });
+    assert_eq!(error("div { background-color: none; }").kind, CssParseErrorKind::InvalidValue { property: "background-color".to_string() });
+    assert_eq!(error("div { width 5px; }").kind, CssParseErrorKind::UnexpectedToken);
+    assert_eq!(error("div { width: 5px;").kind, CssParseErrorKind::UnbalancedBrace);
+    assert_eq!(error("div } p { }").kind, CssParseErrorKind::InvalidSelector);
+    assert_eq!(error("} p { }").kind, CssParseErrorKind::UnbalancedBrace);
+    assert_eq!(error("div > { }").token, "div >");
+}
+
+#[test]
+fn test_parse_stylesheet_recovery() {
+    use crate::property::*;
+
+    let css = "
+        div { width: 5 apples; height: 10px; colr: red; }
+        p >> span { width: 5px; }
+        p { color: red; }
+        } div { width: 3px
+    ";
+    let (stylesheet, warnings) = parse_stylesheet_with_warnings(css);
+
+    assert_eq!(
+        warnings.iter().map(|w| w.kind.clone()).collect::<Vec<_>>(),
+        vec![
+            CssParseErrorKind::InvalidValue { property: "width".to_string() },
+            CssParseErrorKind::UnknownProperty("colr".to_string()),
+            CssParseErrorKind::InvalidSelector,
+            CssParseErrorKind::UnbalancedBrace,
+            CssParseErrorKind::UnbalancedBrace,
+        ]
+    );
+    assert_eq!(stylesheet.rules.iter().map(|r| r.declarations.clone()).collect::<Vec<_>>(), vec![
+        vec![Declaration::new_static(Property::Height(Value::Exact(LayoutHeight(PixelValue::px(10.0)))))],
+        vec![Declaration::new_static(Property::TextColor(Value::Exact(StyleTextColor(ColorU::RED))))],
+        vec![Declaration::new_static(Property::Width(Value::Exact(LayoutWidth(PixelValue::px(3.0)))))],
+    ]);
+}
+
+#[test]
+fn test_parse_dynamic_declaration() {
+    use crate::property::*;
+
+    let stylesheet = parse_stylesheet("#my_div { padding-top: var(--my_id, 400px); }").unwrap();
+    assert_eq!(stylesheet.rules[0].declarations, vec![Declaration::new_dynamic(DynamicProperty {
+        dynamic_id: "my_id".to_string(),
+        default_value: Property::PaddingTop(Value::Exact(LayoutPaddingTop(PixelValue::px(400.0)))),
+    })]);
+
+    let error = |css: &str| parse_stylesheet(css).unwrap_err();
+    assert_eq!(
+        error("div { width: var(--my_id); }").kind,
+        CssParseErrorKind::MissingVarDefault { property: "width".to_string(), dynamic_id: "my_id".to_string() }
+    );
+    let e = error("div { width: var(--my_id, red); }");
+    assert_eq!(
+        e.kind,
+        CssParseErrorKind::InvalidVarDefault { property: "width".to_string(), dynamic_id: "my_id".to_string() }
+    );
+    assert_eq!(e.token, "red");
+    let e = error("div { width: var(my_id, 5px); }");
+    assert_eq!(e.kind, CssParseErrorKind::InvalidValue { property: "width".to_string() });
+    assert_eq!(e.token, "var(my_id, 5px)");
+}
+
+#[test]
+fn test_parse_shorthand_declaration() {
+    use crate::property::*;
+
+    let stylesheet = parse_stylesheet("div { padding: var(--p, 5px 10px); overflow: hidden; }").unwrap();
+    let padding = |p: Property| Declaration::new_dynamic(DynamicProperty { dynamic_id: "p".to_string(), default_value: p });
+    assert_eq!(stylesheet.rules[0].declarations, vec![
+        padding(Property::PaddingTop(Value::Exact(LayoutPaddingTop(PixelValue::px(5.0))))),
+        padding(Property::PaddingRight(Value::Exact(LayoutPaddingRight(PixelValue::px(10.0))))),
+        padding(Property::PaddingBottom(Value::Exact(LayoutPaddingBottom(PixelValue::px(5.0))))),
+        padding(Property::PaddingLeft(Value::Exact(LayoutPaddingLeft(PixelValue::px(10.0))))),
+        Declaration::new_static(Property::OverflowX(Value::Exact(LayoutOverflow::Hidden))),
+        Declaration::new_static(Property::OverflowY(Value::Exact(LayoutOverflow::Hidden))),
+    ]);
+
+    let e = parse_stylesheet("div {\n    margin: 1px 2px 3px 4px 5px;\n}").unwrap_err();
+    assert_eq!(e.to_string(), "2:13: invalid value for \"margin\": \"1px 2px 3px 4px 5px\"");
+    let e = CombinedPropertyType::Border.expand("1px solid red blue").unwrap_err();
+    assert_eq!(e.kind, CssParseErrorKind::InvalidValue { property: "border".to_string() });
+}
+
+#[test]
+fn test_parse_selector() {
+    use self::PathSelector::*;
+
+    let path: Path = "div.a + p ~ img:nth-child(2n + 1)".parse().unwrap();
+    assert_eq!(path.selectors, vec![
+        Type(NodeTypeTag::Div),
+        Class("a".to_string()),
+        AdjacentSibling,
+        Type(NodeTypeTag::P),
+        GeneralSibling,
+        Type(NodeTypeTag::Img),
+        PseudoSelector(PathPseudoSelector::NthChild(NthChildSelector::Pattern(NthChildPattern { repeat: 2, offset: 1 }), Vec::new())),
+    ]);
+
+    // `Display` output parses back to the same path
+    for selector in ["div.a+p~img:nth-child(2n + 1)", "#id>.a .b:hover", "*~div+p", "p:first-child", "[data-x][y|=\"en\"]", ":is(.a, div>p) :where(#b)", "p:not(.x):has(+img, .b)", "p.a:hover::before", "div *::selection"] {
+        assert_eq!(selector.parse::<Path>().unwrap().to_string(), selector);
+    }
+    assert_eq!("div~p".parse::<Path>(), "div ~ p".parse::<Path>());
+    assert_eq!("p:after".parse::<Path>(), "p::after".parse::<Path>());
+    assert_eq!("::placeholder".parse::<Path>().unwrap().to_string(), "*::placeholder");
+    let path: Path = ".a ::BEFORE".parse().unwrap();
+    assert_eq!(path.selectors, vec![Class("a".to_string()), Children, Global]);
+    assert_eq!(path.pseudo_element, Some(PseudoElement::Before));
+
+    let path: Path = "div:has(> img, p)".parse().unwrap();
+    assert_eq!(path.selectors, vec![
+        Type(NodeTypeTag::Div),
+        PseudoSelector(PathPseudoSelector::Has(vec![
+            Path { selectors: vec![DirectChildren, Type(NodeTypeTag::Img)], pseudo_element: None },
+            Path { selectors: vec![Type(NodeTypeTag::P)], pseudo_element: None },
+        ])),
+    ]);
+
+    let list = Path::parse_list("div > p, .a+.b").unwrap();
+    assert_eq!(list, vec!["div>p".parse().unwrap(), ".a+.b".parse().unwrap()]);
+
+    for invalid in ["", "div +", "~ p", "div + > p", "div, p", "div..a", "[a=]", "[a ~ = b]", "[a=b c]", "[=b]", ":is()", ":not(div,)", ":where(> p)", ":has()", ":matches(p)", "p::before.a", "p::after > p", "p::before::after", "::marker", "input:placeholder", "p:first", "div:last", ":is(p::before)", ":nth-child(1 of p::after)"] {
+        let e = invalid.parse::<Path>().unwrap_err();
+        assert_eq!(e.kind, CssParseErrorKind::InvalidSelector);
+    }
+}
+
+#[test]
+fn test_parse_attribute_selector() {
+    let attribute = |selector: &str| match selector.parse::<Path>().unwrap().selectors.as_slice() {
+        [PathSelector::Attribute(a)] => (a.name.clone(), a.operator, a.value.clone(), a.case),
+        other => panic!("not an attribute selector: {:?}", other),
+    };
+
+    assert_eq!(attribute("[data-x]"), ("data-x".to_string(), AttributeOperator::Exists, String::new(), AttributeCase::Default));
+    assert_eq!(attribute("[a=b]"), ("a".to_string(), AttributeOperator::Equals, "b".to_string(), AttributeCase::Default));
+    assert_eq!(attribute("[ a ~= 'b c' ]"), ("a".to_string(), AttributeOperator::Includes, "b c".to_string(), AttributeCase::Default));
+    assert_eq!(attribute("[a|=en]").1, AttributeOperator::DashMatch);
+    assert_eq!(attribute("[a^=b]").1, AttributeOperator::Prefix);
+    assert_eq!(attribute("[a$=b]").1, AttributeOperator::Suffix);
+    assert_eq!(attribute("[a*=\"b\" i]"), ("a".to_string(), AttributeOperator::Substring, "b".to_string(), AttributeCase::Insensitive));
+    assert_eq!(attribute("[a=b S]").3, AttributeCase::Sensitive);
+
+    let path: Path = "div[data-state^=\"open\" i]".parse().unwrap();
+    assert_eq!(path.to_string(), "div[data-state^=\"open\" i]");
+}
+
+#[test]
+fn test_parse_layers_and_important() {
+    use crate::property::*;
+
+    let css = "
+        @layer base, theme;
+        @layer theme.dark {
+            p { width: 5px !important; height: 2px; }
+        }
+        div { height: 10px ! IMPORTANT; }
+        @layer base { div { height: var(--h, 1px) !important; } }
+    ";
+    let stylesheet = parse_stylesheet(css).unwrap();
+    assert_eq!(stylesheet.layers, vec!["base".to_string(), "theme".to_string(), "theme.dark".to_string()]);
+    // sub-layers have a lower precedence than their parent layer
+    assert_eq!(sort_layers(stylesheet.layers.iter().map(|l| l.as_str())), vec!["base", "theme.dark", "theme"]);
+    assert_eq!(
+        stylesheet.rules.iter().map(|r| r.layer.as_deref()).collect::<Vec<_>>(),
+        vec![Some("theme.dark"), None, Some("base")]
+    );
+    assert_eq!(
+        stylesheet.rules[0].important_declarations,
+        vec![Declaration::new_static(Property::Width(Value::Exact(LayoutWidth(PixelValue::px(5.0)))))]
+    );
+    assert_eq!(stylesheet.rules[0].declarations, vec![Declaration::new_static(Property::Height(Value::Exact(LayoutHeight(PixelValue::px(2.0)))))]);
+    assert!(stylesheet.rules[1].declarations.is_empty() && stylesheet.rules[1].important_declarations.len() == 1);
+    assert!(stylesheet.rules[2].declarations.is_empty() && stylesheet.rules[2].important_declarations.len() == 1);
+
+    let written = stylesheet.to_string();
+    assert_eq!(
+        written,
+        "@layer base,theme,theme.dark;@layer theme.dark{p{height: 2px;width: 5px !important}}div{height: 10px !important}\
+        @layer base{div{height: var(--h, 1px) !important}}"
+    );
+    assert_eq!(parse_stylesheet(&written).unwrap(), stylesheet);
+    assert_eq!(
+        format!("{:#}", stylesheet),
+        "@layer base, theme, theme.dark;\n\n@layer theme.dark {\n    p {\n        height: 2px;\n        width: 5px !important;\n    }\n}\n\n\
+        div {\n    height: 10px !important;\n}\n\n@layer base {\n    div {\n        height: var(--h, 1px) !important;\n    }\n}\n"
+    );
+    assert_eq!(parse_stylesheet(&format!("{:#}", stylesheet)).unwrap(), stylesheet);
+
+    let (_, warnings) = parse_stylesheet_with_warnings("@layer { p { width: 5px; } } @layer a b; @layer a . b; div { width: !important; }");
+    assert_eq!(
+        warnings.iter().map(|w| (w.kind.clone(), w.token.as_str())).collect::<Vec<_>>(),
+        vec![
+            (CssParseErrorKind::InvalidAtRule { name: "layer".to_string() }, "@layer"),
+            (CssParseErrorKind::InvalidAtRule { name: "layer".to_string() }, "@layer a b"),
+            (CssParseErrorKind::InvalidAtRule { name: "layer".to_string() }, "@layer a . b"),
+            (CssParseErrorKind::InvalidValue { property: "width".to_string() }, ""),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_nth_child() {
+    let nth = |selector: &str| match selector.parse::<Path>().map(|p| p.selectors) {
+        Ok(selectors) => match selectors.as_slice() {
+            [PathSelector::PseudoSelector(PathPseudoSelector::NthChild(nth, _))] => Some(*nth),
+            _ => None,
+        },
+        Err(_) => None,
+    };
+    let pattern = |repeat, offset| Some(NthChildSelector::Pattern(NthChildPattern { repeat, offset }));
+
+    assert_eq!(nth(":nth-child(3)"), Some(NthChildSelector::Number(3)));
+    assert_eq!(nth(":nth-child(-3)"), Some(NthChildSelector::Number(-3)));
+    assert_eq!(nth(":nth-child( EVEN )"), Some(NthChildSelector::Even));
+    assert_eq!(nth(":nth-child(2n+1)"), pattern(2, 1));
+    assert_eq!(nth(":nth-child(2n - 1)"), pattern(2, -1));
+    assert_eq!(nth(":nth-child(2n-1)"), pattern(2, -1));
+    assert_eq!(nth(":nth-child(2n- 1)"), pattern(2, -1));
+    assert_eq!(nth(":nth-child(-n+3)"), pattern(-1, 3));
+    assert_eq!(nth(":nth-child(-n-10)"), pattern(-1, -10));
+    assert_eq!(nth(":nth-child(+n)"), pattern(1, 0));
+    assert_eq!(nth(":nth-child(n)"), pattern(1, 0));
+    assert_eq!(nth(":nth-child(-2N +4)"), pattern(-2, 4));
+    // huge values saturate, matching must not overflow
+    assert_eq!(nth(":nth-child(n-2147483648)"), pattern(1, i32::MIN));
+    assert_eq!(nth(":nth-child(2n -3000000000)"), pattern(2, i32::MIN));
+    assert_eq!(nth(":nth-child(-3000000000n + 5)"), pattern(i32::MIN, 5));
+    assert!(nth(":nth-child(n-2147483648)").unwrap().matches(1));
+    assert!(nth(":nth-child(2n -3000000000)").unwrap().matches(2));
+    assert!(!nth(":nth-child(2n -3000000000)").unwrap().matches(3));
+    assert!(nth(":nth-child(-3000000000n + 5)").unwrap().matches(5));
+    assert!(!nth(":nth-child(-3000000000n + 5)").unwrap().matches(4));
+    assert!(!nth(":nth-child(2147483647)").unwrap().matches(-1));
+    for invalid in [":nth-child(n+)", ":nth-child(2n + -1)", ":nth-child(+ n)", ":nth-child(1.5n)", ":nth-child(2n 1)", ":nth-child(n-a)", ":nth-child(odd of)"] {
+        assert_eq!(nth(invalid), None, "{}", invalid);
+    }
+
+    let path: Path = "p:nth-last-child(-n + 2 of .a, div.b)".parse().unwrap();
+    assert_eq!(path.selectors[1], PathSelector::PseudoSelector(PathPseudoSelector::NthLastChild(
+        NthChildSelector::Pattern(NthChildPattern { repeat: -1, offset: 2 }),
+        vec![".a".parse().unwrap(), "div.b".parse().unwrap()],
+    )));
+    for selector in [":nth-child(odd of .a)", ":nth-last-child(-1n + 2 of .a, div.b)", ":nth-of-type(3n - 1)", ":nth-last-of-type(1n)", ":only-of-type:empty:root"] {
+        assert_eq!(selector.parse::<Path>().unwrap().to_string(), selector);
+    }
+    assert!(":nth-of-type(2 of .a)".parse::<Path>().is_err());
+}
+
+#[test]
+fn test_parse_media_queries() {
+    for (query, expected) in [
+        ("screen", "screen"),
+        ("ONLY screen AND (min-width: 600px)", "only screen and (width >= 600px)"),
+        ("not print and (orientation:landscape)", "not print and (orientation: landscape)"),
+        ("(max-width: 37.5em)", "(width <= 600px)"),
+        ("(400px < width <= 700px)", "(400px < width <= 700px)"),
+        ("(600px <= width)", "(width >= 600px)"),
+        ("(width = 12pt), (resolution >= 192dpi)", "(width = 16px), (resolution >= 2dppx)"),
+        ("(width < 600px) or ((height) and (prefers-color-scheme: dark))", "(width < 600px) or ((height > 0px) and (prefers-color-scheme: dark))"),
+        ("not (prefers-reduced-motion)", "not (prefers-reduced-motion)"),
+        ("screen and ((width < 600px) or (orientation: portrait))", "screen and ((width < 600px) or (orientation: portrait))"),
+        ("", ""),
+    ] {
+        let queries = parse_media_query_list(query).unwrap();
+        assert_eq!(queries.to_string(), expected, "{}", query);
+        assert_eq!(parse_media_query_list(expected).unwrap(), queries);
+    }
+    for invalid in [
+        "screen or (width > 1px)", "(width < 1px) and (height < 1px) or (color)", "(width < = 1px)", "(1px < width > 2px)",
+        "(width: 50%)", "(resolution: 2px)", "(orientation: up)", "(min-orientation: portrait)", "only", "screen print",
+    ] {
+        assert!(parse_media_query_list(invalid).is_err(), "{}", invalid);
+    }
+
+    let css = "@media screen and (width < 600px), tv { @layer base { p { width: 5px; } } } \
+        @layer base { @media print { @media (orientation: portrait) { div { height: 10px; } } } } \
+        @media (width: big) { a { width: 1px; } } @media print;";
+    let (stylesheet, warnings) = parse_stylesheet_with_warnings(css);
+    assert_eq!(
+        stylesheet.rules.iter().map(|r| (r.layer.as_deref(), r.media.iter().map(|m| m.to_string()).collect::<Vec<_>>())).collect::<Vec<_>>(),
+        vec![
+            (Some("base"), vec!["screen and (width < 600px), tv".to_string()]),
+            (Some("base"), vec!["print".to_string(), "(orientation: portrait)".to_string()]),
+            (None, vec!["not all".to_string()]),
+        ]
+    );
+    assert_eq!(
+        warnings.iter().map(|w| (w.kind.clone(), w.token.as_str())).collect::<Vec<_>>(),
+        vec![
+            (CssParseErrorKind::InvalidAtRule { name: "media".to_string() }, "@media (width: big)"),
+            (CssParseErrorKind::InvalidAtRule { name: "media".to_string() }, "@media print"),
+        ]
+    );
+
+    let written = stylesheet.to_string();
+    assert_eq!(
+        written,
+        "@layer base;@layer base{@media screen and (width < 600px), tv{p{width: 5px}}\
+        @media print{@media (orientation: portrait){div{height: 10px}}}}@media not all{a{width: 1px}}"
+    );
+    assert_eq!(parse_stylesheet(&written).unwrap(), stylesheet);
+    assert_eq!(parse_stylesheet(&format!("{:#}", stylesheet)).unwrap(), stylesheet);
+}
+
+#[test]
+fn test_parse_supports() {
+    for (condition, expected) in [
+        ("(display: flex)", true),
+        ("(DISPLAY:flex)", true),
+        ("(display: grid)", false),
+        ("(colour: red)", false),
+        ("(width: var(--w, 5px))", true),
+        ("(padding: 1px 2px)", true),
+        ("(width: 5px !important)", false),
+        // `auto` / `none` are only supported by the properties whose grammar allows them
+        ("(color: auto)", false),
+        ("(opacity: none)", false),
+        ("(width: auto) and (max-width: none) and (float: none)", true),
+        ("(display: flex) and (not (color: auto))", true),
+        ("not (display: grid)", true),
+        ("(display: flex) and (width: 5 apples)", false),
+        ("(display: grid) or ((width: 5px) and (height: 5px))", true),
+        ("selector(div > p:nth-child(2n + 1))", true),
+        ("selector(a, b)", false),
+        ("selector(p:unknown)", false),
+        ("font-format(woff2) or (width: 5px)", true),
+        ("(unknown syntax here)", false),
+    ] {
+        assert_eq!(parse_supports_condition(condition).unwrap(), expected, "{}", condition);
+    }
+    for invalid in ["display: flex", "(width: 5px) and (height: 5px) or (color: red)", "not not (width: 5px)", "(width: 5px) (height: 5px)", ""] {
+        assert!(parse_supports_condition(invalid).is_err(), "{}", invalid);
+    }
+
+    let css = "@supports (display: flex) { @media print { p { width: 5px; } } } \
+        @supports (display: grid) { p { height: 5px; } } \
+        @layer base { @supports not selector(a::after:hover) { div { width: 1px; } } } \
+        @supports display: flex { a { width: 2px; } }";
+    let (stylesheet, warnings) = parse_stylesheet_with_warnings(css);
+    assert_eq!(stylesheet.to_string(), "@layer base;@media print{p{width: 5px}}@layer base{div{width: 1px}}");
+    assert_eq!(
+        warnings.iter().map(|w| (w.kind.clone(), w.token.as_str())).collect::<Vec<_>>(),
+        vec![(CssParseErrorKind::InvalidAtRule { name: "supports".to_string() }, "@supports display: flex")]
+    );
+}
+
+#[test]
+fn test_parse_keyframes() {
+    let css = "@keyframes slide { from { width: 0px; } 50%, 75% { width: 5px !important; height: 2px; } TO { width: 10px; } } \
+        @layer base { @media print { @keyframes \"fade out\" { 0% { color: red; } } } } \
+        @keyframes none { } \
+        @keyframes bad { 120% { width: 1px; } 100% { width: 2px; } }";
+    let (stylesheet, warnings) = parse_stylesheet_with_warnings(css);
+    assert_eq!(stylesheet.keyframes.len(), 3);
+    assert_eq!(
+        stylesheet.keyframes[0].keyframes.iter().map(|k| (k.offset.get(), k.declarations.len())).collect::<Vec<_>>(),
+        vec![(0.0, 1), (50.0, 1), (75.0, 1), (100.0, 1)]
+    );
+    assert_eq!(stylesheet.keyframes[1].name, "fade out");
+    assert_eq!(stylesheet.keyframes[1].layer.as_deref(), Some("base"));
+    assert_eq!(stylesheet.find_keyframes("bad").map(|k| k.keyframes.len()), Some(1));
+    assert_eq!(
+        warnings.iter().map(|w| (w.kind.clone(), w.token.as_str())).collect::<Vec<_>>(),
+        vec![
+            (CssParseErrorKind::InvalidAtRule { name: "keyframes".to_string() }, "@keyframes none"),
+            (CssParseErrorKind::InvalidSelector, "120%"),
+        ]
+    );
+
+    assert_eq!(
+        stylesheet.to_string(),
+        "@layer base;@keyframes slide{0%{width: 0px}50%{height: 2px}75%{height: 2px}100%{width: 10px}}\
+        @layer base{@media print{@keyframes \"fade out\"{0%{color: rgba(255, 0, 0, 1)}}}}@keyframes bad{100%{width: 2px}}"
+    );
+    for text in [stylesheet.to_string(), format!("{:#}", stylesheet)] {
+        assert_eq!(parse_stylesheet(&text).unwrap(), stylesheet);
+    }
+}
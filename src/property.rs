@@ -18,10 +18,12 @@ pub enum CombinedPropertyType {
     BoxShadow,
     BackgroundColor,
     BackgroundImage,
+    Animation,
 }
 
 impl CombinedPropertyType {
     /// Parses a CSS key, such as `width` from a string:
+    #[allow(clippy::should_implement_trait, reason = "returns an `Option`, there is no error to report")]
     pub fn from_str(input: &str) -> Option<Self> {
         let input = input.trim();
         match input {
@@ -37,6 +39,7 @@ impl CombinedPropertyType {
             "box-shadow" => Some(CombinedPropertyType::BoxShadow),
             "background-color" => Some(CombinedPropertyType::BackgroundColor),
             "background-image" => Some(CombinedPropertyType::BackgroundImage),
+            "animation" => Some(CombinedPropertyType::Animation),
             _ => None,
         }
     }
@@ -56,10 +59,163 @@ impl CombinedPropertyType {
             CombinedPropertyType::BoxShadow => "box-shadow",
             CombinedPropertyType::BackgroundColor => "background-color",
             CombinedPropertyType::BackgroundImage => "background-image",
+            CombinedPropertyType::Animation => "animation",
+        }
+    }
+
+    /// Expands the value of this shorthand into the longhand properties it sets,
+    /// i.e. `margin: 1px 2px` => `MarginTop`, `MarginRight`, `MarginBottom` and `MarginLeft`
+    pub fn expand(&self, value: &str) -> Result<Vec<Property>, crate::parser::CssParseError> {
+        crate::parser::expand_shorthand(*self, value)
+    }
+
+    /// Returns the types of the longhand properties this shorthand sets, in the order `expand()` returns them
+    pub const fn longhands(&self) -> &'static [PType] {
+        use self::PType::*;
+        match self {
+            CombinedPropertyType::BorderRadius => &[BorderTopLeftRadius, BorderTopRightRadius, BorderBottomRightRadius, BorderBottomLeftRadius],
+            CombinedPropertyType::Overflow => &[OverflowX, OverflowY],
+            CombinedPropertyType::Margin => &[MarginTop, MarginRight, MarginBottom, MarginLeft],
+            CombinedPropertyType::Border => &[
+                BorderTopWidth, BorderTopStyle, BorderTopColor,
+                BorderRightWidth, BorderRightStyle, BorderRightColor,
+                BorderBottomWidth, BorderBottomStyle, BorderBottomColor,
+                BorderLeftWidth, BorderLeftStyle, BorderLeftColor,
+            ],
+            CombinedPropertyType::BorderLeft => &[BorderLeftWidth, BorderLeftStyle, BorderLeftColor],
+            CombinedPropertyType::BorderRight => &[BorderRightWidth, BorderRightStyle, BorderRightColor],
+            CombinedPropertyType::BorderTop => &[BorderTopWidth, BorderTopStyle, BorderTopColor],
+            CombinedPropertyType::BorderBottom => &[BorderBottomWidth, BorderBottomStyle, BorderBottomColor],
+            CombinedPropertyType::Padding => &[PaddingTop, PaddingRight, PaddingBottom, PaddingLeft],
+            CombinedPropertyType::BoxShadow => &[BoxShadowTop, BoxShadowRight, BoxShadowBottom, BoxShadowLeft],
+            CombinedPropertyType::BackgroundColor | CombinedPropertyType::BackgroundImage => &[BackgroundContent],
+            CombinedPropertyType::Animation => &[
+                AnimationName, AnimationDuration, AnimationTimingFunction, AnimationDelay,
+                AnimationIterationCount, AnimationDirection, AnimationFillMode, AnimationPlayState,
+            ],
+        }
+    }
+
+    /// The reverse of `expand()`: writes the longhands (in the order of `longhands()`) as the
+    /// shortest equivalent value of this shorthand, i.e. `5px 10px` for a `margin`.
+    /// Returns `None` if the longhands can't be expressed by this shorthand.
+    pub fn collapse(&self, properties: &[Property]) -> Option<String> {
+        let types = properties.iter().map(|p| p.get_type());
+        if !types.eq(self.longhands().iter().copied()) {
+            return None;
+        }
+
+        let values = properties.iter().map(|p| PropertyValue(p).to_string()).collect::<Vec<_>>();
+        let value = match self {
+            CombinedPropertyType::BorderRadius
+            | CombinedPropertyType::Margin
+            | CombinedPropertyType::Padding => collapse_sides(&values),
+            CombinedPropertyType::Overflow if values[0] == values[1] => values[0].clone(),
+            CombinedPropertyType::Overflow => values.join(" "),
+            CombinedPropertyType::Border => {
+                let (top, rest) = values.split_at(3);
+                if rest.chunks(3).any(|side| side != top) {
+                    return None;
+                }
+                collapse_border_side(top)
+            }
+            CombinedPropertyType::BorderLeft
+            | CombinedPropertyType::BorderRight
+            | CombinedPropertyType::BorderTop
+            | CombinedPropertyType::BorderBottom => collapse_border_side(&values),
+            CombinedPropertyType::BoxShadow if values.iter().all(|v| *v == values[0]) => values[0].clone(),
+            CombinedPropertyType::BoxShadow => return None,
+            // already a longhand ("background"), nothing to collapse
+            CombinedPropertyType::BackgroundColor | CombinedPropertyType::BackgroundImage => return None,
+            CombinedPropertyType::Animation => collapse_animations(&values)?,
+        };
+
+        // i.e. `padding-top: auto` can't be written as a `padding`
+        match self.expand(&value) {
+            Ok(expanded) if expanded == properties => Some(value),
+            _ => None,
         }
     }
 }
 
+/// Writes only the value of a property, see `Property::fmt_value`
+struct PropertyValue<'a>(&'a Property);
+
+impl<'a> fmt::Display for PropertyValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_value(f)
+    }
+}
+
+/// Writes the top, right, bottom and left values with the 1- to 4-value rule of `margin` / `padding`
+fn collapse_sides(values: &[String]) -> String {
+    let (top, right, bottom, left) = (&values[0], &values[1], &values[2], &values[3]);
+    if right != left {
+        values.join(" ")
+    } else if top != bottom {
+        format!("{} {} {}", top, right, bottom)
+    } else if top != right {
+        format!("{} {}", top, right)
+    } else {
+        top.clone()
+    }
+}
+
+/// Writes the comma-separated lists of the `animation-*` longhands (in the order of `longhands()`)
+/// as a list of `animation` values, omitting components that have their initial value
+fn collapse_animations(values: &[String]) -> Option<String> {
+    let lists = values.iter().map(|v| split_list(v)).collect::<Vec<_>>();
+    let count = lists[0].len();
+    if lists.iter().any(|list| list.len() != count) {
+        return None;
+    }
+
+    // initial values of the timing function, delay, iteration count, direction, fill mode and play state
+    let defaults = ["ease", "0ms", "1", "normal", "none", "running"];
+    let animations = (0..count)
+        .map(|i| {
+            // the first time is the duration, so it can't be omitted if there is a delay
+            let mut parts = vec![lists[1][i]];
+            parts.extend((2..8).filter(|&list| lists[list][i] != defaults[list - 2]).map(|list| lists[list][i]));
+            parts.push(lists[0][i]);
+            parts.join(" ")
+        })
+        .collect::<Vec<_>>();
+    Some(animations.join(", "))
+}
+
+/// Splits a comma-separated list (as written by `write_value_list`) into its items,
+/// ignoring the commas inside of functions (`cubic-bezier(0, 0, 1, 1)`) and strings
+fn split_list(value: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let (mut depth, mut quote, mut start) = (0, None, 0);
+    for (i, c) in value.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            (_, Some(_)) => {}
+            ('(', None) => depth += 1,
+            (')', None) => depth -= 1,
+            (',', None) if depth == 0 => {
+                items.push(value[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(value[start..].trim());
+    items
+}
+
+/// Writes the width, style and color of a border side, omitting components that are `initial`
+fn collapse_border_side(values: &[String]) -> String {
+    if values.iter().all(|v| *v == values[0]) {
+        // `initial` or `inherit`
+        return values[0].clone();
+    }
+    values.iter().filter(|v| *v != "initial").cloned().collect::<Vec<_>>().join(" ")
+}
+
 /// Represents a CSS key (for example `"border-radius"` => `BorderRadius`).
 /// You can also derive this key from a `Property` by calling `Property::get_type()`.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -138,177 +294,534 @@ pub enum PType {
     Filter,
     BackdropFilter,
     TextShadow,
+    Content,
+    AnimationName,
+    AnimationDuration,
+    AnimationTimingFunction,
+    AnimationDelay,
+    AnimationIterationCount,
+    AnimationDirection,
+    AnimationFillMode,
+    AnimationPlayState,
+    TransitionProperty,
+    TransitionDuration,
+    TransitionTimingFunction,
+    TransitionDelay,
+}
+
+impl PType {
+    /// Parses a CSS key, such as `width` from a string:
+    #[allow(clippy::should_implement_trait, reason = "returns an `Option`, there is no error to report")]
+    pub fn from_str(input: &str) -> Option<Self> {
+        let input = input.trim();
+        match input {
+            "color" => Some(PType::TextColor),
+            "font-size" => Some(PType::FontSize),
+            "font-family" => Some(PType::FontFamily),
+            "text-align" => Some(PType::TextAlign),
+            "letter-spacing" => Some(PType::LetterSpacing),
+            "line-height" => Some(PType::LineHeight),
+            "word-spacing" => Some(PType::WordSpacing),
+            "tab-width" => Some(PType::TabWidth),
+            "cursor" => Some(PType::Cursor),
+            "display" => Some(PType::Display),
+            "float" => Some(PType::Float),
+            "box-sizing" => Some(PType::BoxSizing),
+            "width" => Some(PType::Width),
+            "height" => Some(PType::Height),
+            "min-width" => Some(PType::MinWidth),
+            "min-height" => Some(PType::MinHeight),
+            "max-width" => Some(PType::MaxWidth),
+            "max-height" => Some(PType::MaxHeight),
+            "position" => Some(PType::Position),
+            "top" => Some(PType::Top),
+            "right" => Some(PType::Right),
+            "left" => Some(PType::Left),
+            "bottom" => Some(PType::Bottom),
+            "flex-wrap" => Some(PType::FlexWrap),
+            "flex-direction" => Some(PType::FlexDirection),
+            "flex-grow" => Some(PType::FlexGrow),
+            "flex-shrink" => Some(PType::FlexShrink),
+            "justify-content" => Some(PType::JustifyContent),
+            "align-items" => Some(PType::AlignItems),
+            "align-content" => Some(PType::AlignContent),
+            "background" => Some(PType::BackgroundContent),
+            "background-position" => Some(PType::BackgroundPosition),
+            "background-size" => Some(PType::BackgroundSize),
+            "background-repeat" => Some(PType::BackgroundRepeat),
+            "overflow-x" => Some(PType::OverflowX),
+            "overflow-y" => Some(PType::OverflowY),
+            "padding-top" => Some(PType::PaddingTop),
+            "padding-left" => Some(PType::PaddingLeft),
+            "padding-right" => Some(PType::PaddingRight),
+            "padding-bottom" => Some(PType::PaddingBottom),
+            "margin-top" => Some(PType::MarginTop),
+            "margin-left" => Some(PType::MarginLeft),
+            "margin-right" => Some(PType::MarginRight),
+            "margin-bottom" => Some(PType::MarginBottom),
+            "border-top-left-radius" => Some(PType::BorderTopLeftRadius),
+            "border-top-right-radius" => Some(PType::BorderTopRightRadius),
+            "border-bottom-left-radius" => Some(PType::BorderBottomLeftRadius),
+            "border-bottom-right-radius" => Some(PType::BorderBottomRightRadius),
+            "border-top-color" => Some(PType::BorderTopColor),
+            "border-right-color" => Some(PType::BorderRightColor),
+            "border-left-color" => Some(PType::BorderLeftColor),
+            "border-bottom-color" => Some(PType::BorderBottomColor),
+            "border-top-style" => Some(PType::BorderTopStyle),
+            "border-right-style" => Some(PType::BorderRightStyle),
+            "border-left-style" => Some(PType::BorderLeftStyle),
+            "border-bottom-style" => Some(PType::BorderBottomStyle),
+            "border-top-width" => Some(PType::BorderTopWidth),
+            "border-right-width" => Some(PType::BorderRightWidth),
+            "border-left-width" => Some(PType::BorderLeftWidth),
+            "border-bottom-width" => Some(PType::BorderBottomWidth),
+            "-box-shadow-left" => Some(PType::BoxShadowLeft),
+            "-box-shadow-right" => Some(PType::BoxShadowRight),
+            "-box-shadow-top" => Some(PType::BoxShadowTop),
+            "-box-shadow-bottom" => Some(PType::BoxShadowBottom),
+            "-scrollbar-style" => Some(PType::ScrollbarStyle),
+            "opacity" => Some(PType::Opacity),
+            "transform" => Some(PType::Transform),
+            "transform-origin" => Some(PType::TransformOrigin),
+            "perspective-origin" => Some(PType::PerspectiveOrigin),
+            "backface-visibility" => Some(PType::BackfaceVisibility),
+            "mix-blend-mode" => Some(PType::MixBlendMode),
+            "filter" => Some(PType::Filter),
+            "backdrop-filter" => Some(PType::BackdropFilter),
+            "text-shadow" => Some(PType::TextShadow),
+            "content" => Some(PType::Content),
+            "animation-name" => Some(PType::AnimationName),
+            "animation-duration" => Some(PType::AnimationDuration),
+            "animation-timing-function" => Some(PType::AnimationTimingFunction),
+            "animation-delay" => Some(PType::AnimationDelay),
+            "animation-iteration-count" => Some(PType::AnimationIterationCount),
+            "animation-direction" => Some(PType::AnimationDirection),
+            "animation-fill-mode" => Some(PType::AnimationFillMode),
+            "animation-play-state" => Some(PType::AnimationPlayState),
+            "transition-property" => Some(PType::TransitionProperty),
+            "transition-duration" => Some(PType::TransitionDuration),
+            "transition-timing-function" => Some(PType::TransitionTimingFunction),
+            "transition-delay" => Some(PType::TransitionDelay),
+            _ => None,
+        }
+    }
+
+    /// Returns the original string that was used to construct this `CssPropertyType`.
+    pub const fn to_str(&self) -> &'static str {
+        match self {
+            PType::TextColor => "color",
+            PType::FontSize => "font-size",
+            PType::FontFamily => "font-family",
+            PType::TextAlign => "text-align",
+            PType::LetterSpacing => "letter-spacing",
+            PType::LineHeight => "line-height",
+            PType::WordSpacing => "word-spacing",
+            PType::TabWidth => "tab-width",
+            PType::Cursor => "cursor",
+            PType::Display => "display",
+            PType::Float => "float",
+            PType::BoxSizing => "box-sizing",
+            PType::Width => "width",
+            PType::Height => "height",
+            PType::MinWidth => "min-width",
+            PType::MinHeight => "min-height",
+            PType::MaxWidth => "max-width",
+            PType::MaxHeight => "max-height",
+            PType::Position => "position",
+            PType::Top => "top",
+            PType::Right => "right",
+            PType::Left => "left",
+            PType::Bottom => "bottom",
+            PType::FlexWrap => "flex-wrap",
+            PType::FlexDirection => "flex-direction",
+            PType::FlexGrow => "flex-grow",
+            PType::FlexShrink => "flex-shrink",
+            PType::JustifyContent => "justify-content",
+            PType::AlignItems => "align-items",
+            PType::AlignContent => "align-content",
+            PType::BackgroundContent => "background",
+            PType::BackgroundPosition => "background-position",
+            PType::BackgroundSize => "background-size",
+            PType::BackgroundRepeat => "background-repeat",
+            PType::OverflowX => "overflow-x",
+            PType::OverflowY => "overflow-y",
+            PType::PaddingTop => "padding-top",
+            PType::PaddingLeft => "padding-left",
+            PType::PaddingRight => "padding-right",
+            PType::PaddingBottom => "padding-bottom",
+            PType::MarginTop => "margin-top",
+            PType::MarginLeft => "margin-left",
+            PType::MarginRight => "margin-right",
+            PType::MarginBottom => "margin-bottom",
+            PType::BorderTopLeftRadius => "border-top-left-radius",
+            PType::BorderTopRightRadius => "border-top-right-radius",
+            PType::BorderBottomLeftRadius => "border-bottom-left-radius",
+            PType::BorderBottomRightRadius => "border-bottom-right-radius",
+            PType::BorderTopColor => "border-top-color",
+            PType::BorderRightColor => "border-right-color",
+            PType::BorderLeftColor => "border-left-color",
+            PType::BorderBottomColor => "border-bottom-color",
+            PType::BorderTopStyle => "border-top-style",
+            PType::BorderRightStyle => "border-right-style",
+            PType::BorderLeftStyle => "border-left-style",
+            PType::BorderBottomStyle => "border-bottom-style",
+            PType::BorderTopWidth => "border-top-width",
+            PType::BorderRightWidth => "border-right-width",
+            PType::BorderLeftWidth => "border-left-width",
+            PType::BorderBottomWidth => "border-bottom-width",
+            PType::BoxShadowLeft => "-box-shadow-left",
+            PType::BoxShadowRight => "-box-shadow-right",
+            PType::BoxShadowTop => "-box-shadow-top",
+            PType::BoxShadowBottom => "-box-shadow-bottom",
+            PType::ScrollbarStyle => "-scrollbar-style",
+            PType::Opacity => "opacity",
+            PType::Transform => "transform",
+            PType::TransformOrigin => "transform-origin",
+            PType::PerspectiveOrigin => "perspective-origin",
+            PType::BackfaceVisibility => "backface-visibility",
+            PType::MixBlendMode => "mix-blend-mode",
+            PType::Filter => "filter",
+            PType::BackdropFilter => "backdrop-filter",
+            PType::TextShadow => "text-shadow",
+            PType::Content => "content",
+            PType::AnimationName => "animation-name",
+            PType::AnimationDuration => "animation-duration",
+            PType::AnimationTimingFunction => "animation-timing-function",
+            PType::AnimationDelay => "animation-delay",
+            PType::AnimationIterationCount => "animation-iteration-count",
+            PType::AnimationDirection => "animation-direction",
+            PType::AnimationFillMode => "animation-fill-mode",
+            PType::AnimationPlayState => "animation-play-state",
+            PType::TransitionProperty => "transition-property",
+            PType::TransitionDuration => "transition-duration",
+            PType::TransitionTimingFunction => "transition-timing-function",
+            PType::TransitionDelay => "transition-delay",
+        }
+    }
+
+    /// All property types, in declaration order
+    pub const ALL: [PType; 87] = [
+        PType::TextColor, PType::FontSize, PType::FontFamily, PType::TextAlign, PType::LetterSpacing,
+        PType::LineHeight, PType::WordSpacing, PType::TabWidth, PType::Cursor, PType::Display, PType::Float,
+        PType::BoxSizing, PType::Width, PType::Height, PType::MinWidth, PType::MinHeight, PType::MaxWidth,
+        PType::MaxHeight, PType::Position, PType::Top, PType::Right, PType::Left, PType::Bottom,
+        PType::FlexWrap, PType::FlexDirection, PType::FlexGrow, PType::FlexShrink, PType::JustifyContent,
+        PType::AlignItems, PType::AlignContent, PType::BackgroundContent, PType::BackgroundPosition,
+        PType::BackgroundSize, PType::BackgroundRepeat, PType::OverflowX, PType::OverflowY, PType::PaddingTop,
+        PType::PaddingLeft, PType::PaddingRight, PType::PaddingBottom, PType::MarginTop, PType::MarginLeft,
+        PType::MarginRight, PType::MarginBottom, PType::BorderTopLeftRadius, PType::BorderTopRightRadius,
+        PType::BorderBottomLeftRadius, PType::BorderBottomRightRadius, PType::BorderTopColor,
+        PType::BorderRightColor, PType::BorderLeftColor, PType::BorderBottomColor, PType::BorderTopStyle,
+        PType::BorderRightStyle, PType::BorderLeftStyle, PType::BorderBottomStyle, PType::BorderTopWidth,
+        PType::BorderRightWidth, PType::BorderLeftWidth, PType::BorderBottomWidth, PType::BoxShadowLeft,
+        PType::BoxShadowRight, PType::BoxShadowTop, PType::BoxShadowBottom, PType::ScrollbarStyle,
+        PType::Opacity, PType::Transform, PType::TransformOrigin, PType::PerspectiveOrigin,
+        PType::BackfaceVisibility, PType::MixBlendMode, PType::Filter, PType::BackdropFilter,
+        PType::TextShadow, PType::Content, PType::AnimationName, PType::AnimationDuration,
+        PType::AnimationTimingFunction, PType::AnimationDelay, PType::AnimationIterationCount,
+        PType::AnimationDirection, PType::AnimationFillMode, PType::AnimationPlayState,
+        PType::TransitionProperty, PType::TransitionDuration, PType::TransitionTimingFunction,
+        PType::TransitionDelay,
+    ];
+
+    /// Returns the initial value of the property, which is used when the property is set to
+    /// `initial` or isn't set at all (and isn't inherited)
+    pub fn initial_value(&self) -> Property {
+        match self {
+            PType::TextColor => Property::TextColor(Value::Exact(StyleTextColor(ColorU::BLACK))),
+            PType::FontSize => Property::FontSize(Value::default()),
+            PType::FontFamily => Property::FontFamily(Value::default()),
+            PType::TextAlign => Property::TextAlign(Value::default()),
+            PType::LetterSpacing => Property::LetterSpacing(Value::default()),
+            PType::LineHeight => Property::LineHeight(Value::default()),
+            PType::WordSpacing => Property::WordSpacing(Value::default()),
+            PType::TabWidth => Property::TabWidth(Value::default()),
+            PType::Cursor => Property::Cursor(Value::default()),
+            PType::Display => Property::Display(Value::default()),
+            PType::Float => Property::Float(Value::default()),
+            PType::BoxSizing => Property::BoxSizing(Value::default()),
+            PType::Width => Property::Width(Value::Auto),
+            PType::Height => Property::Height(Value::Auto),
+            PType::MinWidth => Property::MinWidth(Value::default()),
+            PType::MinHeight => Property::MinHeight(Value::default()),
+            PType::MaxWidth => Property::MaxWidth(Value::default()),
+            PType::MaxHeight => Property::MaxHeight(Value::default()),
+            PType::Position => Property::Position(Value::default()),
+            PType::Top => Property::Top(Value::Auto),
+            PType::Right => Property::Right(Value::Auto),
+            PType::Left => Property::Left(Value::Auto),
+            PType::Bottom => Property::Bottom(Value::Auto),
+            PType::FlexWrap => Property::FlexWrap(Value::default()),
+            PType::FlexDirection => Property::FlexDirection(Value::default()),
+            PType::FlexGrow => Property::FlexGrow(Value::default()),
+            PType::FlexShrink => Property::FlexShrink(Value::default()),
+            PType::JustifyContent => Property::JustifyContent(Value::default()),
+            PType::AlignItems => Property::AlignItems(Value::default()),
+            PType::AlignContent => Property::AlignContent(Value::default()),
+            PType::BackgroundContent => Property::BackgroundContent(Value::None),
+            PType::BackgroundPosition => Property::BackgroundPosition(Value::default()),
+            PType::BackgroundSize => Property::BackgroundSize(Value::default()),
+            PType::BackgroundRepeat => Property::BackgroundRepeat(Value::default()),
+            PType::OverflowX => Property::OverflowX(Value::default()),
+            PType::OverflowY => Property::OverflowY(Value::default()),
+            PType::PaddingTop => Property::PaddingTop(Value::default()),
+            PType::PaddingLeft => Property::PaddingLeft(Value::default()),
+            PType::PaddingRight => Property::PaddingRight(Value::default()),
+            PType::PaddingBottom => Property::PaddingBottom(Value::default()),
+            PType::MarginTop => Property::MarginTop(Value::default()),
+            PType::MarginLeft => Property::MarginLeft(Value::default()),
+            PType::MarginRight => Property::MarginRight(Value::default()),
+            PType::MarginBottom => Property::MarginBottom(Value::default()),
+            PType::BorderTopLeftRadius => Property::BorderTopLeftRadius(Value::default()),
+            PType::BorderTopRightRadius => Property::BorderTopRightRadius(Value::default()),
+            PType::BorderBottomLeftRadius => Property::BorderBottomLeftRadius(Value::default()),
+            PType::BorderBottomRightRadius => Property::BorderBottomRightRadius(Value::default()),
+            PType::BorderTopColor => Property::BorderTopColor(Value::default()),
+            PType::BorderRightColor => Property::BorderRightColor(Value::default()),
+            PType::BorderLeftColor => Property::BorderLeftColor(Value::default()),
+            PType::BorderBottomColor => Property::BorderBottomColor(Value::default()),
+            PType::BorderTopStyle => Property::BorderTopStyle(Value::default()),
+            PType::BorderRightStyle => Property::BorderRightStyle(Value::default()),
+            PType::BorderLeftStyle => Property::BorderLeftStyle(Value::default()),
+            PType::BorderBottomStyle => Property::BorderBottomStyle(Value::default()),
+            PType::BorderTopWidth => Property::BorderTopWidth(Value::default()),
+            PType::BorderRightWidth => Property::BorderRightWidth(Value::default()),
+            PType::BorderLeftWidth => Property::BorderLeftWidth(Value::default()),
+            PType::BorderBottomWidth => Property::BorderBottomWidth(Value::default()),
+            PType::BoxShadowLeft => Property::BoxShadowLeft(Value::None),
+            PType::BoxShadowRight => Property::BoxShadowRight(Value::None),
+            PType::BoxShadowTop => Property::BoxShadowTop(Value::None),
+            PType::BoxShadowBottom => Property::BoxShadowBottom(Value::None),
+            PType::ScrollbarStyle => Property::ScrollbarStyle(Value::default()),
+            PType::Opacity => Property::Opacity(Value::default()),
+            PType::Transform => Property::Transform(Value::None),
+            PType::TransformOrigin => Property::TransformOrigin(Value::default()),
+            PType::PerspectiveOrigin => Property::PerspectiveOrigin(Value::default()),
+            PType::BackfaceVisibility => Property::BackfaceVisibility(Value::default()),
+            PType::MixBlendMode => Property::MixBlendMode(Value::default()),
+            PType::Filter => Property::Filter(Value::None),
+            PType::BackdropFilter => Property::BackdropFilter(Value::None),
+            PType::TextShadow => Property::TextShadow(Value::None),
+            PType::Content => Property::Content(Value::None),
+            PType::AnimationName => Property::AnimationName(Value::None),
+            PType::AnimationDuration => Property::AnimationDuration(Value::Exact(vec![StyleDuration::default()])),
+            PType::AnimationTimingFunction => Property::AnimationTimingFunction(Value::Exact(vec![TimingFunction::default()])),
+            PType::AnimationDelay => Property::AnimationDelay(Value::Exact(vec![StyleDuration::default()])),
+            PType::AnimationIterationCount => Property::AnimationIterationCount(Value::Exact(vec![StyleIterationCount::default()])),
+            PType::AnimationDirection => Property::AnimationDirection(Value::Exact(vec![StyleAnimationDirection::default()])),
+            PType::AnimationFillMode => Property::AnimationFillMode(Value::Exact(vec![StyleAnimationFillMode::default()])),
+            PType::AnimationPlayState => Property::AnimationPlayState(Value::Exact(vec![StyleAnimationPlayState::default()])),
+            PType::TransitionProperty => Property::TransitionProperty(Value::Exact(vec![StyleTransitionProperty::All])),
+            PType::TransitionDuration => Property::TransitionDuration(Value::Exact(vec![StyleDuration::default()])),
+            PType::TransitionTimingFunction => Property::TransitionTimingFunction(Value::Exact(vec![TimingFunction::default()])),
+            PType::TransitionDelay => Property::TransitionDelay(Value::Exact(vec![StyleDuration::default()])),
+        }
+    }
+}
+
+/// Evaluates `$expr` with `$value` bound to the `Value<T>` of any property
+macro_rules! with_value {
+    ($property:expr, $value:ident => $expr:expr) => {
+        match $property {
+            Property::TextColor($value) => $expr,
+            Property::FontSize($value) => $expr,
+            Property::FontFamily($value) => $expr,
+            Property::TextAlign($value) => $expr,
+            Property::LetterSpacing($value) => $expr,
+            Property::LineHeight($value) => $expr,
+            Property::WordSpacing($value) => $expr,
+            Property::TabWidth($value) => $expr,
+            Property::Cursor($value) => $expr,
+            Property::Display($value) => $expr,
+            Property::Float($value) => $expr,
+            Property::BoxSizing($value) => $expr,
+            Property::Width($value) => $expr,
+            Property::Height($value) => $expr,
+            Property::MinWidth($value) => $expr,
+            Property::MinHeight($value) => $expr,
+            Property::MaxWidth($value) => $expr,
+            Property::MaxHeight($value) => $expr,
+            Property::Position($value) => $expr,
+            Property::Top($value) => $expr,
+            Property::Right($value) => $expr,
+            Property::Left($value) => $expr,
+            Property::Bottom($value) => $expr,
+            Property::FlexWrap($value) => $expr,
+            Property::FlexDirection($value) => $expr,
+            Property::FlexGrow($value) => $expr,
+            Property::FlexShrink($value) => $expr,
+            Property::JustifyContent($value) => $expr,
+            Property::AlignItems($value) => $expr,
+            Property::AlignContent($value) => $expr,
+            Property::BackgroundContent($value) => $expr,
+            Property::BackgroundPosition($value) => $expr,
+            Property::BackgroundSize($value) => $expr,
+            Property::BackgroundRepeat($value) => $expr,
+            Property::OverflowX($value) => $expr,
+            Property::OverflowY($value) => $expr,
+            Property::PaddingTop($value) => $expr,
+            Property::PaddingLeft($value) => $expr,
+            Property::PaddingRight($value) => $expr,
+            Property::PaddingBottom($value) => $expr,
+            Property::MarginTop($value) => $expr,
+            Property::MarginLeft($value) => $expr,
+            Property::MarginRight($value) => $expr,
+            Property::MarginBottom($value) => $expr,
+            Property::BorderTopLeftRadius($value) => $expr,
+            Property::BorderTopRightRadius($value) => $expr,
+            Property::BorderBottomLeftRadius($value) => $expr,
+            Property::BorderBottomRightRadius($value) => $expr,
+            Property::BorderTopColor($value) => $expr,
+            Property::BorderRightColor($value) => $expr,
+            Property::BorderLeftColor($value) => $expr,
+            Property::BorderBottomColor($value) => $expr,
+            Property::BorderTopStyle($value) => $expr,
+            Property::BorderRightStyle($value) => $expr,
+            Property::BorderLeftStyle($value) => $expr,
+            Property::BorderBottomStyle($value) => $expr,
+            Property::BorderTopWidth($value) => $expr,
+            Property::BorderRightWidth($value) => $expr,
+            Property::BorderLeftWidth($value) => $expr,
+            Property::BorderBottomWidth($value) => $expr,
+            Property::BoxShadowLeft($value) => $expr,
+            Property::BoxShadowRight($value) => $expr,
+            Property::BoxShadowTop($value) => $expr,
+            Property::BoxShadowBottom($value) => $expr,
+            Property::ScrollbarStyle($value) => $expr,
+            Property::Opacity($value) => $expr,
+            Property::Transform($value) => $expr,
+            Property::TransformOrigin($value) => $expr,
+            Property::PerspectiveOrigin($value) => $expr,
+            Property::BackfaceVisibility($value) => $expr,
+            Property::MixBlendMode($value) => $expr,
+            Property::Filter($value) => $expr,
+            Property::BackdropFilter($value) => $expr,
+            Property::TextShadow($value) => $expr,
+            Property::Content($value) => $expr,
+            Property::AnimationName($value) => $expr,
+            Property::AnimationDuration($value) => $expr,
+            Property::AnimationTimingFunction($value) => $expr,
+            Property::AnimationDelay($value) => $expr,
+            Property::AnimationIterationCount($value) => $expr,
+            Property::AnimationDirection($value) => $expr,
+            Property::AnimationFillMode($value) => $expr,
+            Property::AnimationPlayState($value) => $expr,
+            Property::TransitionProperty($value) => $expr,
+            Property::TransitionDuration($value) => $expr,
+            Property::TransitionTimingFunction($value) => $expr,
+            Property::TransitionDelay($value) => $expr,
+        }
+    };
 }
 
 impl Property {
-    // /// Returns the original string that was used to construct this `CssPropertyType`.
-    // pub fn from_str(&self, input: &str) -> Option<Self> {
-    //     match input {
-    //         "color" => Some(PType::TextColor),
-    //         "font-size" => Some(PType::FontSize),
-    //         "font-family" => Some(PType::FontFamily),
-    //         "text-align" => Some(PType::TextAlign),
-    //         "letter-spacing" => Some(PType::LetterSpacing),
-    //         "line-height" => Some(PType::LineHeight),
-    //         "word-spacing" => Some(PType::WordSpacing),
-    //         "tab-width" => Some(PType::TabWidth),
-    //         "cursor" => Some(PType::Cursor),
-    //         "display" => Some(PType::Display),
-    //         "float" => Some(PType::Float),
-    //         "box-sizing" => Some(PType::BoxSizing),
-    //         "width" => Some(PType::Width),
-    //         "height" => Some(PType::Height),
-    //         "min-width" => Some(PType::MinWidth),
-    //         "min-height" => Some(PType::MinHeight),
-    //         "max-width" => Some(PType::MaxWidth),
-    //         "max-height" => Some(PType::MaxHeight),
-    //         "position" => Some(PType::Position),
-    //         "top" => Some(PType::Top),
-    //         "right" => Some(PType::Right),
-    //         "left" => Some(PType::Left),
-    //         "bottom" => Some(PType::Bottom),
-    //         "flex-wrap" => Some(PType::FlexWrap),
-    //         "flex-direction" => Some(PType::FlexDirection),
-    //         "flex-grow" => Some(PType::FlexGrow),
-    //         "flex-shrink" => Some(PType::FlexShrink),
-    //         "justify-content" => Some(PType::JustifyContent),
-    //         "align-items" => Some(PType::AlignItems),
-    //         "align-content" => Some(PType::AlignContent),
-    //         "background" => Some(PType::BackgroundContent),
-    //         "background-position" => Some(PType::BackgroundPosition),
-    //         "background-size" => Some(PType::BackgroundSize),
-    //         "background-repeat" => Some(PType::BackgroundRepeat),
-    //         "overflow-x" => Some(PType::OverflowX),
-    //         "overflow-y" => Some(PType::OverflowY),
-    //         "padding-top" => Some(PType::PaddingTop),
-    //         "padding-left" => Some(PType::PaddingLeft),
-    //         "padding-right" => Some(PType::PaddingRight),
-    //         "padding-bottom" => Some(PType::PaddingBottom),
-    //         "margin-top" => Some(PType::MarginTop),
-    //         "margin-left" => Some(PType::MarginLeft),
-    //         "margin-right" => Some(PType::MarginRight),
-    //         "margin-bottom" => Some(PType::MarginBottom),
-    //         "border-top-left-radius" => Some(PType::BorderTopLeftRadius),
-    //         "border-top-right-radius" => Some(PType::BorderTopRightRadius),
-    //         "border-bottom-left-radius" => Some(PType::BorderBottomLeftRadius),
-    //         "border-bottom-right-radius" => Some(PType::BorderBottomRightRadius),
-    //         "border-top-color" => Some(PType::BorderTopColor),
-    //         "border-right-color" => Some(PType::BorderRightColor),
-    //         "border-left-color" => Some(PType::BorderLeftColor),
-    //         "border-bottom-color" => Some(PType::BorderBottomColor),
-    //         "border-top-style" => Some(PType::BorderTopStyle),
-    //         "border-right-style" => Some(PType::BorderRightStyle),
-    //         "border-left-style" => Some(PType::BorderLeftStyle),
-    //         "border-bottom-style" => Some(PType::BorderBottomStyle),
-    //         "border-top-width" => Some(PType::BorderTopWidth),
-    //         "border-right-width" => Some(PType::BorderRightWidth),
-    //         "border-left-width" => Some(PType::BorderLeftWidth),
-    //         "border-bottom-width" => Some(PType::BorderBottomWidth),
-    //         "-box-shadow-left" => Some(PType::BoxShadowLeft),
-    //         "-box-shadow-right" => Some(PType::BoxShadowRight),
-    //         "-box-shadow-top" => Some(PType::BoxShadowTop),
-    //         "-box-shadow-bottom" => Some(PType::BoxShadowBottom),
-    //         "-scrollbar-style" => Some(PType::ScrollbarStyle),
-    //         "opacity" => Some(PType::Opacity),
-    //         "transform" => Some(PType::Transform),
-    //         "transform-origin" => Some(PType::TransformOrigin),
-    //         "perspective-origin" => Some(PType::PerspectiveOrigin),
-    //         "backface-visibility" => Some(PType::BackfaceVisibility),
-    //         "mix-blend-mode" => Some(PType::MixBlendMode),
-    //         "filter" => Some(PType::Filter),
-    //         "backdrop-filter" => Some(PType::BackdropFilter),
-    //         "text-shadow" => Some(PType::TextShadow),
-    //         _ => None,
-    //     }
-    // }
-    //
-    // /// Returns the original string that was used to construct this `CssPropertyType`.
-    // pub const fn to_str(&self) -> &'static str {
-    //     match self {
-    //         PType::TextColor => "color",
-    //         PType::FontSize => "font-size",
-    //         PType::FontFamily => "font-family",
-    //         PType::TextAlign => "text-align",
-    //         PType::LetterSpacing => "letter-spacing",
-    //         PType::LineHeight => "line-height",
-    //         PType::WordSpacing => "word-spacing",
-    //         PType::TabWidth => "tab-width",
-    //         PType::Cursor => "cursor",
-    //         PType::Display => "display",
-    //         PType::Float => "float",
-    //         PType::BoxSizing => "box-sizing",
-    //         PType::Width => "width",
-    //         PType::Height => "height",
-    //         PType::MinWidth => "min-width",
-    //         PType::MinHeight => "min-height",
-    //         PType::MaxWidth => "max-width",
-    //         PType::MaxHeight => "max-height",
-    //         PType::Position => "position",
-    //         PType::Top => "top",
-    //         PType::Right => "right",
-    //         PType::Left => "left",
-    //         PType::Bottom => "bottom",
-    //         PType::FlexWrap => "flex-wrap",
-    //         PType::FlexDirection => "flex-direction",
-    //         PType::FlexGrow => "flex-grow",
-    //         PType::FlexShrink => "flex-shrink",
-    //         PType::JustifyContent => "justify-content",
-    //         PType::AlignItems => "align-items",
-    //         PType::AlignContent => "align-content",
-    //         PType::BackgroundContent => "background",
-    //         PType::BackgroundPosition => "background-position",
-    //         PType::BackgroundSize => "background-size",
-    //         PType::BackgroundRepeat => "background-repeat",
-    //         PType::OverflowX => "overflow-x",
-    //         PType::OverflowY => "overflow-y",
-    //         PType::PaddingTop => "padding-top",
-    //         PType::PaddingLeft => "padding-left",
-    //         PType::PaddingRight => "padding-right",
-    //         PType::PaddingBottom => "padding-bottom",
-    //         PType::MarginTop => "margin-top",
-    //         PType::MarginLeft => "margin-left",
-    //         PType::MarginRight => "margin-right",
-    //         PType::MarginBottom => "margin-bottom",
-    //         PType::BorderTopLeftRadius => "border-top-left-radius",
-    //         PType::BorderTopRightRadius => "border-top-right-radius",
-    //         PType::BorderBottomLeftRadius => "border-bottom-left-radius",
-    //         PType::BorderBottomRightRadius => "border-bottom-right-radius",
-    //         PType::BorderTopColor => "border-top-color",
-    //         PType::BorderRightColor => "border-right-color",
-    //         PType::BorderLeftColor => "border-left-color",
-    //         PType::BorderBottomColor => "border-bottom-color",
-    //         PType::BorderTopStyle => "border-top-style",
-    //         PType::BorderRightStyle => "border-right-style",
-    //         PType::BorderLeftStyle => "border-left-style",
-    //         PType::BorderBottomStyle => "border-bottom-style",
-    //         PType::BorderTopWidth => "border-top-width",
-    //         PType::BorderRightWidth => "border-right-width",
-    //         PType::BorderLeftWidth => "border-left-width",
-    //         PType::BorderBottomWidth => "border-bottom-width",
-    //         PType::BoxShadowLeft => "-box-shadow-left",
-    //         PType::BoxShadowRight => "-box-shadow-right",
-    //         PType::BoxShadowTop => "-box-shadow-top",
-    //         PType::BoxShadowBottom => "-box-shadow-bottom",
-    //         PType::ScrollbarStyle => "-scrollbar-style",
-    //         PType::Opacity => "opacity",
-    //         PType::Transform => "transform",
-    //         PType::TransformOrigin => "transform-origin",
-    //         PType::PerspectiveOrigin => "perspective-origin",
-    //         PType::BackfaceVisibility => "backface-visibility",
-    //         PType::MixBlendMode => "mix-blend-mode",
-    //         PType::Filter => "filter",
-    //         PType::BackdropFilter => "backdrop-filter",
-    //         PType::TextShadow => "text-shadow",
-    //     }
-    // }
+    /// Returns whether the value of the property is `initial`
+    pub const fn is_initial(&self) -> bool {
+        with_value!(self, v => v.is_initial())
+    }
+
+    /// Returns whether the value of the property is `inherit`
+    pub const fn is_inherit(&self) -> bool {
+        with_value!(self, v => v.is_inherit())
+    }
+
+    /// Returns the CSS key of this property, i.e. `Width(...)` => `PType::Width`
+    pub const fn get_type(&self) -> PType {
+        match self {
+            Property::TextColor(_) => PType::TextColor,
+            Property::FontSize(_) => PType::FontSize,
+            Property::FontFamily(_) => PType::FontFamily,
+            Property::TextAlign(_) => PType::TextAlign,
+            Property::LetterSpacing(_) => PType::LetterSpacing,
+            Property::LineHeight(_) => PType::LineHeight,
+            Property::WordSpacing(_) => PType::WordSpacing,
+            Property::TabWidth(_) => PType::TabWidth,
+            Property::Cursor(_) => PType::Cursor,
+            Property::Display(_) => PType::Display,
+            Property::Float(_) => PType::Float,
+            Property::BoxSizing(_) => PType::BoxSizing,
+            Property::Width(_) => PType::Width,
+            Property::Height(_) => PType::Height,
+            Property::MinWidth(_) => PType::MinWidth,
+            Property::MinHeight(_) => PType::MinHeight,
+            Property::MaxWidth(_) => PType::MaxWidth,
+            Property::MaxHeight(_) => PType::MaxHeight,
+            Property::Position(_) => PType::Position,
+            Property::Top(_) => PType::Top,
+            Property::Right(_) => PType::Right,
+            Property::Left(_) => PType::Left,
+            Property::Bottom(_) => PType::Bottom,
+            Property::FlexWrap(_) => PType::FlexWrap,
+            Property::FlexDirection(_) => PType::FlexDirection,
+            Property::FlexGrow(_) => PType::FlexGrow,
+            Property::FlexShrink(_) => PType::FlexShrink,
+            Property::JustifyContent(_) => PType::JustifyContent,
+            Property::AlignItems(_) => PType::AlignItems,
+            Property::AlignContent(_) => PType::AlignContent,
+            Property::BackgroundContent(_) => PType::BackgroundContent,
+            Property::BackgroundPosition(_) => PType::BackgroundPosition,
+            Property::BackgroundSize(_) => PType::BackgroundSize,
+            Property::BackgroundRepeat(_) => PType::BackgroundRepeat,
+            Property::OverflowX(_) => PType::OverflowX,
+            Property::OverflowY(_) => PType::OverflowY,
+            Property::PaddingTop(_) => PType::PaddingTop,
+            Property::PaddingLeft(_) => PType::PaddingLeft,
+            Property::PaddingRight(_) => PType::PaddingRight,
+            Property::PaddingBottom(_) => PType::PaddingBottom,
+            Property::MarginTop(_) => PType::MarginTop,
+            Property::MarginLeft(_) => PType::MarginLeft,
+            Property::MarginRight(_) => PType::MarginRight,
+            Property::MarginBottom(_) => PType::MarginBottom,
+            Property::BorderTopLeftRadius(_) => PType::BorderTopLeftRadius,
+            Property::BorderTopRightRadius(_) => PType::BorderTopRightRadius,
+            Property::BorderBottomLeftRadius(_) => PType::BorderBottomLeftRadius,
+            Property::BorderBottomRightRadius(_) => PType::BorderBottomRightRadius,
+            Property::BorderTopColor(_) => PType::BorderTopColor,
+            Property::BorderRightColor(_) => PType::BorderRightColor,
+            Property::BorderLeftColor(_) => PType::BorderLeftColor,
+            Property::BorderBottomColor(_) => PType::BorderBottomColor,
+            Property::BorderTopStyle(_) => PType::BorderTopStyle,
+            Property::BorderRightStyle(_) => PType::BorderRightStyle,
+            Property::BorderLeftStyle(_) => PType::BorderLeftStyle,
+            Property::BorderBottomStyle(_) => PType::BorderBottomStyle,
+            Property::BorderTopWidth(_) => PType::BorderTopWidth,
+            Property::BorderRightWidth(_) => PType::BorderRightWidth,
+            Property::BorderLeftWidth(_) => PType::BorderLeftWidth,
+            Property::BorderBottomWidth(_) => PType::BorderBottomWidth,
+            Property::BoxShadowLeft(_) => PType::BoxShadowLeft,
+            Property::BoxShadowRight(_) => PType::BoxShadowRight,
+            Property::BoxShadowTop(_) => PType::BoxShadowTop,
+            Property::BoxShadowBottom(_) => PType::BoxShadowBottom,
+            Property::ScrollbarStyle(_) => PType::ScrollbarStyle,
+            Property::Opacity(_) => PType::Opacity,
+            Property::Transform(_) => PType::Transform,
+            Property::TransformOrigin(_) => PType::TransformOrigin,
+            Property::PerspectiveOrigin(_) => PType::PerspectiveOrigin,
+            Property::BackfaceVisibility(_) => PType::BackfaceVisibility,
+            Property::MixBlendMode(_) => PType::MixBlendMode,
+            Property::Filter(_) => PType::Filter,
+            Property::BackdropFilter(_) => PType::BackdropFilter,
+            Property::TextShadow(_) => PType::TextShadow,
+            Property::Content(_) => PType::Content,
+            Property::AnimationName(_) => PType::AnimationName,
+            Property::AnimationDuration(_) => PType::AnimationDuration,
+            Property::AnimationTimingFunction(_) => PType::AnimationTimingFunction,
+            Property::AnimationDelay(_) => PType::AnimationDelay,
+            Property::AnimationIterationCount(_) => PType::AnimationIterationCount,
+            Property::AnimationDirection(_) => PType::AnimationDirection,
+            Property::AnimationFillMode(_) => PType::AnimationFillMode,
+            Property::AnimationPlayState(_) => PType::AnimationPlayState,
+            Property::TransitionProperty(_) => PType::TransitionProperty,
+            Property::TransitionDuration(_) => PType::TransitionDuration,
+            Property::TransitionTimingFunction(_) => PType::TransitionTimingFunction,
+            Property::TransitionDelay(_) => PType::TransitionDelay,
+        }
+    }
 
     /// Returns whether this property will be inherited during cascading
     pub const fn is_inheritable(&self) -> bool {
         use self::Property::*;
-        match self {
-            TextColor(_) | FontFamily(_) | FontSize(_) | LineHeight(_) | TextAlign(_) => true,
-            _ => false,
-        }
+        matches!(self, TextColor(_) | FontFamily(_) | FontSize(_) | LineHeight(_) | TextAlign(_))
     }
 
     /// Returns whether this property can trigger a re-layout (important for incremental layout and caching layouted DOMs).
@@ -321,7 +834,8 @@ impl Property {
         // FontFamily, FontSize, LetterSpacing and LineHeight can affect
         // the text layout and therefore the screen layout
 
-        match self {
+        !matches!(
+            self,
             TextColor(_)
             | Cursor(_)
             | BackgroundContent(_)
@@ -353,9 +867,20 @@ impl Property {
             | MixBlendMode(_)
             | Filter(_)
             | BackdropFilter(_)
-            | TextShadow(_) => false,
-            _ => true,
-        }
+            | TextShadow(_)
+            | AnimationName(_)
+            | AnimationDuration(_)
+            | AnimationTimingFunction(_)
+            | AnimationDelay(_)
+            | AnimationIterationCount(_)
+            | AnimationDirection(_)
+            | AnimationFillMode(_)
+            | AnimationPlayState(_)
+            | TransitionProperty(_)
+            | TransitionDuration(_)
+            | TransitionTimingFunction(_)
+            | TransitionDelay(_)
+        )
     }
 
     /// Returns whether the property is a GPU property (currently only opacity and transforms)
@@ -414,34 +939,22 @@ impl<T> Value<T> {
 
     #[inline]
     pub const fn is_auto(&self) -> bool {
-        match self {
-            Value::Auto => true,
-            _ => false,
-        }
+        matches!(self, Value::Auto)
     }
 
     #[inline]
     pub const fn is_none(&self) -> bool {
-        match self {
-            Value::None => true,
-            _ => false,
-        }
+        matches!(self, Value::None)
     }
 
     #[inline]
     pub const fn is_initial(&self) -> bool {
-        match self {
-            Value::Initial => true,
-            _ => false,
-        }
+        matches!(self, Value::Initial)
     }
 
     #[inline]
     pub const fn is_inherit(&self) -> bool {
-        match self {
-            Value::Inherit => true,
-            _ => false,
-        }
+        matches!(self, Value::Inherit)
     }
 }
 
@@ -463,8 +976,83 @@ impl<T: Default> Default for Value<T> {
     }
 }
 
+impl<T: fmt::Display> fmt::Display for Value<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Auto => write!(f, "auto"),
+            Value::None => write!(f, "none"),
+            Value::Initial => write!(f, "initial"),
+            Value::Inherit => write!(f, "inherit"),
+            Value::Exact(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+/// Implements `Display` for wrapper types that are written exactly like their inner value
+macro_rules! impl_display_newtype {
+    ($($t:ident),+ $(,)?) => {
+        $(
+            impl fmt::Display for $t {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "{}", self.0)
+                }
+            }
+        )+
+    };
+}
+
+impl_display_newtype!(
+    StyleTextColor, StyleFontSize, StyleLetterSpacing, StyleLineHeight,
+    StyleWordSpacing, StyleTabWidth, LayoutWidth, LayoutMinWidth,
+    LayoutMaxWidth, LayoutHeight, LayoutMinHeight, LayoutMaxHeight,
+    LayoutTop, LayoutLeft, LayoutRight, LayoutBottom,
+    LayoutFlexGrow, LayoutFlexShrink, LayoutPaddingTop, LayoutPaddingLeft,
+    LayoutPaddingRight, LayoutPaddingBottom, LayoutMarginTop, LayoutMarginLeft,
+    LayoutMarginRight, LayoutMarginBottom, StyleBorderTopLeftRadius, StyleBorderTopRightRadius,
+    StyleBorderBottomLeftRadius, StyleBorderBottomRightRadius, StyleBorderTopColor, StyleBorderRightColor,
+    StyleBorderLeftColor, StyleBorderBottomColor, StyleBorderTopStyle, StyleBorderRightStyle,
+    StyleBorderLeftStyle, StyleBorderBottomStyle, LayoutBorderTopWidth, LayoutBorderRightWidth,
+    LayoutBorderLeftWidth, LayoutBorderBottomWidth,
+);
+
+/// Writes a `Value` holding a list, i.e. the `blur(5px) opacity(50%)` of a `filter`
+fn write_value_list<T: fmt::Display>(f: &mut fmt::Formatter, value: &Value<Vec<T>>, separator: &str) -> fmt::Result {
+    match value {
+        Value::Exact(items) => write_list(f, items, separator),
+        Value::Auto => write!(f, "auto"),
+        Value::None => write!(f, "none"),
+        Value::Initial => write!(f, "initial"),
+        Value::Inherit => write!(f, "inherit"),
+    }
+}
+
+fn write_list<T: fmt::Display>(f: &mut fmt::Formatter, items: &[T], separator: &str) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i != 0 {
+            write!(f, "{}", separator)?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+/// Writes a double-quoted CSS string, escaping quotes, backslashes and newlines
+pub(crate) fn write_css_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\a ")?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
 /// Represents one parsed CSS key-value pair, such as `"width: 20px"` => `CssProperty::Width(LayoutWidth::px(20.0))`
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[allow(clippy::large_enum_variant, reason = "boxing the scrollbar style would change the public variant")]
 pub enum Property {
     TextColor(Value<StyleTextColor>),
     FontSize(Value<StyleFontSize>),
@@ -540,6 +1128,135 @@ pub enum Property {
     Filter(Value<Vec<StyleFilter>>),
     BackdropFilter(Value<Vec<StyleFilter>>),
     TextShadow(Value<StyleBoxShadow>),
+    /// Generated content of the `::before` and `::after` pseudo-elements, `none` (or `normal`) generates no content
+    Content(Value<Vec<StyleContent>>),
+    /// Names of the `@keyframes` rules to run, the other `animation-*` lists are
+    /// repeated (or truncated) to the length of this list
+    AnimationName(Value<Vec<StyleAnimationName>>),
+    AnimationDuration(Value<Vec<StyleDuration>>),
+    AnimationTimingFunction(Value<Vec<TimingFunction>>),
+    AnimationDelay(Value<Vec<StyleDuration>>),
+    AnimationIterationCount(Value<Vec<StyleIterationCount>>),
+    AnimationDirection(Value<Vec<StyleAnimationDirection>>),
+    AnimationFillMode(Value<Vec<StyleAnimationFillMode>>),
+    AnimationPlayState(Value<Vec<StyleAnimationPlayState>>),
+    /// Properties that transition when their computed value changes, the other `transition-*`
+    /// lists are repeated (or truncated) to the length of this list. `none` transitions nothing.
+    TransitionProperty(Value<Vec<StyleTransitionProperty>>),
+    TransitionDuration(Value<Vec<StyleDuration>>),
+    TransitionTimingFunction(Value<Vec<TimingFunction>>),
+    TransitionDelay(Value<Vec<StyleDuration>>),
+}
+
+impl Property {
+    /// Writes only the value of this property (without the key) as CSS, i.e. `5px` for `width: 5px`
+    ///
+    /// The output parses back to the same property, except for values without a CSS syntax:
+    /// fonts loaded from memory (`StyleFontFamily::Ref`) are written as a quoted placeholder name
+    /// and exact `-scrollbar-style` values are written as `initial`. These don't round-trip.
+    pub fn fmt_value(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::Property::*;
+        match self {
+            TextColor(v) => write!(f, "{}", v),
+            FontSize(v) => write!(f, "{}", v),
+            FontFamily(v) => write_value_list(f, v, ", "),
+            TextAlign(v) => write!(f, "{}", v),
+            LetterSpacing(v) => write!(f, "{}", v),
+            LineHeight(v) => write!(f, "{}", v),
+            WordSpacing(v) => write!(f, "{}", v),
+            TabWidth(v) => write!(f, "{}", v),
+            Cursor(v) => write!(f, "{}", v),
+            Display(v) => write!(f, "{}", v),
+            Float(v) => write!(f, "{}", v),
+            BoxSizing(v) => write!(f, "{}", v),
+            Width(v) => write!(f, "{}", v),
+            Height(v) => write!(f, "{}", v),
+            MinWidth(v) => write!(f, "{}", v),
+            MinHeight(v) => write!(f, "{}", v),
+            MaxWidth(v) => write!(f, "{}", v),
+            MaxHeight(v) => write!(f, "{}", v),
+            Position(v) => write!(f, "{}", v),
+            Top(v) => write!(f, "{}", v),
+            Right(v) => write!(f, "{}", v),
+            Left(v) => write!(f, "{}", v),
+            Bottom(v) => write!(f, "{}", v),
+            FlexWrap(v) => write!(f, "{}", v),
+            FlexDirection(v) => write!(f, "{}", v),
+            FlexGrow(v) => write!(f, "{}", v),
+            FlexShrink(v) => write!(f, "{}", v),
+            JustifyContent(v) => write!(f, "{}", v),
+            AlignItems(v) => write!(f, "{}", v),
+            AlignContent(v) => write!(f, "{}", v),
+            BackgroundContent(v) => write_value_list(f, v, ", "),
+            BackgroundPosition(v) => write_value_list(f, v, ", "),
+            BackgroundSize(v) => write_value_list(f, v, ", "),
+            BackgroundRepeat(v) => write_value_list(f, v, ", "),
+            OverflowX(v) => write!(f, "{}", v),
+            OverflowY(v) => write!(f, "{}", v),
+            PaddingTop(v) => write!(f, "{}", v),
+            PaddingLeft(v) => write!(f, "{}", v),
+            PaddingRight(v) => write!(f, "{}", v),
+            PaddingBottom(v) => write!(f, "{}", v),
+            MarginTop(v) => write!(f, "{}", v),
+            MarginLeft(v) => write!(f, "{}", v),
+            MarginRight(v) => write!(f, "{}", v),
+            MarginBottom(v) => write!(f, "{}", v),
+            BorderTopLeftRadius(v) => write!(f, "{}", v),
+            BorderTopRightRadius(v) => write!(f, "{}", v),
+            BorderBottomLeftRadius(v) => write!(f, "{}", v),
+            BorderBottomRightRadius(v) => write!(f, "{}", v),
+            BorderTopColor(v) => write!(f, "{}", v),
+            BorderRightColor(v) => write!(f, "{}", v),
+            BorderLeftColor(v) => write!(f, "{}", v),
+            BorderBottomColor(v) => write!(f, "{}", v),
+            BorderTopStyle(v) => write!(f, "{}", v),
+            BorderRightStyle(v) => write!(f, "{}", v),
+            BorderLeftStyle(v) => write!(f, "{}", v),
+            BorderBottomStyle(v) => write!(f, "{}", v),
+            BorderTopWidth(v) => write!(f, "{}", v),
+            BorderRightWidth(v) => write!(f, "{}", v),
+            BorderLeftWidth(v) => write!(f, "{}", v),
+            BorderBottomWidth(v) => write!(f, "{}", v),
+            BoxShadowLeft(v) => write!(f, "{}", v),
+            BoxShadowRight(v) => write!(f, "{}", v),
+            BoxShadowTop(v) => write!(f, "{}", v),
+            BoxShadowBottom(v) => write!(f, "{}", v),
+            // scrollbar styles can only be constructed from Rust code, there is no CSS syntax
+            // for them (not round-trippable, see above)
+            ScrollbarStyle(Value::Exact(_)) => write!(f, "initial"),
+            ScrollbarStyle(keyword) => write!(f, "{}", keyword.clone().map_property(|_| "")),
+            Opacity(v) => write!(f, "{}", v),
+            Transform(v) => write_value_list(f, v, " "),
+            TransformOrigin(v) => write!(f, "{}", v),
+            PerspectiveOrigin(v) => write!(f, "{}", v),
+            BackfaceVisibility(v) => write!(f, "{}", v),
+            MixBlendMode(v) => write!(f, "{}", v),
+            Filter(v) => write_value_list(f, v, " "),
+            BackdropFilter(v) => write_value_list(f, v, " "),
+            TextShadow(v) => write!(f, "{}", v),
+            Content(v) => write_value_list(f, v, " "),
+            AnimationName(v) => write_value_list(f, v, ", "),
+            AnimationDuration(v) => write_value_list(f, v, ", "),
+            AnimationTimingFunction(v) => write_value_list(f, v, ", "),
+            AnimationDelay(v) => write_value_list(f, v, ", "),
+            AnimationIterationCount(v) => write_value_list(f, v, ", "),
+            AnimationDirection(v) => write_value_list(f, v, ", "),
+            AnimationFillMode(v) => write_value_list(f, v, ", "),
+            AnimationPlayState(v) => write_value_list(f, v, ", "),
+            TransitionProperty(v) => write_value_list(f, v, ", "),
+            TransitionDuration(v) => write_value_list(f, v, ", "),
+            TransitionTimingFunction(v) => write_value_list(f, v, ", "),
+            TransitionDelay(v) => write_value_list(f, v, ", "),
+        }
+    }
+}
+
+/// Writes the property as a CSS `key: value` pair (without the trailing semicolon)
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: ", self.get_type().to_str())?;
+        self.fmt_value(f)
+    }
 }
 
 /// Only used for calculations: Point coordinate (x, y) in layout space.
@@ -710,7 +1427,7 @@ impl LayoutRect {
     #[inline]
     pub fn get_scroll_rect<I: Iterator<Item = Self>>(&self, children: I) -> Option<Self> {
         let children_union = Self::union(children)?;
-        Self::union([*self, children_union].iter().map(|r| *r))
+        Self::union([*self, children_union].iter().copied())
     }
 
     // Returns if b overlaps a
@@ -864,7 +1581,7 @@ impl FloatValue {
 
     #[inline]
     pub fn new(value: f32) -> Self {
-        Self((value * FP_PRECISION_MULTIPLIER) as isize)
+        Self(libm::roundf(value * FP_PRECISION_MULTIPLIER) as isize)
     }
 
     #[inline]
@@ -985,21 +1702,27 @@ impl AngleValue {
     /// Returns the value of the AngleMetric in degrees
     #[inline]
     pub fn to_degrees(&self) -> f32 {
-        let val = match self.metric {
-            AngleMetric::Degree => self.number.get(),
-            AngleMetric::Radians => self.number.get() / 400.0 * 360.0,
-            AngleMetric::Grad => self.number.get() / (2.0 * core::f32::consts::PI) * 360.0,
-            AngleMetric::Turn => self.number.get() * 360.0,
-            AngleMetric::Percent => self.number.get() / 100.0 * 360.0,
-        };
+        let val = self.to_degrees_unclamped();
 
         // clamp the degree to a positive value from 0 to 360 (so 410deg = 50deg)
         let mut val = val % 360.0;
         if val < 0.0 {
-            val = 360.0 + val;
+            val += 360.0;
         }
         val
     }
+
+    /// Same as `to_degrees()`, but without wrapping the value into the 0 - 360 degree range
+    #[inline]
+    pub fn to_degrees_unclamped(&self) -> f32 {
+        match self.metric {
+            AngleMetric::Degree => self.number.get(),
+            AngleMetric::Radians => self.number.get() / (2.0 * core::f32::consts::PI) * 360.0,
+            AngleMetric::Grad => self.number.get() / 400.0 * 360.0,
+            AngleMetric::Turn => self.number.get() * 360.0,
+            AngleMetric::Percent => self.number.get() / 100.0 * 360.0,
+        }
+    }
 }
 
 /// Wrapper around FloatValue, represents a percentage instead
@@ -1042,8 +1765,9 @@ impl PercentageValue {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum AngleMetric {
+    #[default]
     Degree,
     Radians,
     Grad,
@@ -1051,12 +1775,6 @@ pub enum AngleMetric {
     Percent,
 }
 
-impl Default for AngleMetric {
-    fn default() -> AngleMetric {
-        AngleMetric::Degree
-    }
-}
-
 impl fmt::Display for AngleMetric {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::AngleMetric::*;
@@ -1071,28 +1789,23 @@ impl fmt::Display for AngleMetric {
 }
 
 /// Enum representing the metric associated with a number (px, pt, em, etc.)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 #[repr(C)]
 pub enum SizeMetric {
+    #[default]
     Px,
     Pt,
     Em,
     Percent,
 }
 
-impl Default for SizeMetric {
-    fn default() -> Self {
-        SizeMetric::Px
-    }
-}
-
 impl fmt::Display for SizeMetric {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::SizeMetric::*;
         match self {
             Px => write!(f, "px"),
             Pt => write!(f, "pt"),
-            Em => write!(f, "pt"),
+            Em => write!(f, "em"),
             Percent => write!(f, "%"),
         }
     }
@@ -1270,20 +1983,111 @@ pub enum StyleFontFamily {
     Ref(Arc<FontData>),
 }
 
+impl fmt::Display for StyleFontFamily {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::StyleFontFamily::*;
+        match self {
+            // generic families such as `sans-serif` must not be quoted
+            System(s) if is_unquoted_font_name(s) => write!(f, "{}", s),
+            System(s) => write_css_string(f, s),
+            File(s) => {
+                write!(f, "url(")?;
+                write_css_string(f, s)?;
+                write!(f, ")")
+            }
+            // already-decoded fonts can't be expressed in CSS: the placeholder parses back
+            // as a system font, not as the original font data
+            Ref(r) => write!(f, "\"<font data: {} bytes>\"", r.0.len()),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct FontData(pub Vec<u8>);
 
+/// Returns whether the font name can be written without quotes, i.e. `Times New Roman`
+fn is_unquoted_font_name(name: &str) -> bool {
+    let is_ident = |word: &str| {
+        let mut chars = word.chars();
+        let starts_ok = match chars.next() {
+            Some('-') => matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_'),
+            Some(c) => c.is_ascii_alphabetic() || c == '_',
+            None => false,
+        };
+        starts_ok && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    let is_keyword = ["initial", "inherit", "auto", "none"].iter().any(|k| name.eq_ignore_ascii_case(k));
+    !is_keyword && name.split(' ').all(is_ident)
+}
+
+/// One item of the `content` property, i.e. `"Chapter "`, `counter(chapter)` or `attr(title)`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleContent {
+    /// A quoted string
+    String(String),
+    /// `counter(name)` or `counter(name, upper-roman)`: the value of the innermost counter with the given name
+    Counter { name: String, style: Option<String> },
+    /// `counters(name, ".")` or `counters(name, ".", upper-roman)`: the values of all counters
+    /// with the given name, from the outermost to the innermost, joined with the separator
+    Counters { name: String, separator: String, style: Option<String> },
+    /// `attr(name)`: the value of the attribute of the element
+    Attr(String),
+    /// `open-quote`
+    OpenQuote,
+    /// `close-quote`
+    CloseQuote,
+    /// `no-open-quote`
+    NoOpenQuote,
+    /// `no-close-quote`
+    NoCloseQuote,
+}
+
+impl fmt::Display for StyleContent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::StyleContent::*;
+        match self {
+            String(s) => write_css_string(f, s),
+            Counter { name, style } => {
+                write!(f, "counter({}", name)?;
+                if let Some(style) = style {
+                    write!(f, ", {}", style)?;
+                }
+                write!(f, ")")
+            }
+            Counters { name, separator, style } => {
+                write!(f, "counters({}, ", name)?;
+                write_css_string(f, separator)?;
+                if let Some(style) = style {
+                    write!(f, ", {}", style)?;
+                }
+                write!(f, ")")
+            }
+            Attr(name) => write!(f, "attr({})", name),
+            OpenQuote => write!(f, "open-quote"),
+            CloseQuote => write!(f, "close-quote"),
+            NoOpenQuote => write!(f, "no-open-quote"),
+            NoCloseQuote => write!(f, "no-close-quote"),
+        }
+    }
+}
+
 /// Horizontal text alignment enum (left, center, right) - default: `Center`
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum StyleTextAlign {
+    #[default]
     Left,
     Center,
     Right,
 }
 
-impl Default for StyleTextAlign {
-    fn default() -> Self {
-        StyleTextAlign::Left
+impl fmt::Display for StyleTextAlign {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::StyleTextAlign::*;
+        write!(f, "{}", match self {
+            Left => "left",
+            Center => "center",
+            Right => "right",
+        })
     }
 }
 
@@ -1327,7 +2131,7 @@ impl Default for StyleTabWidth {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum StyleCursor {
     /// `alias`
     Alias,
@@ -1344,6 +2148,7 @@ pub enum StyleCursor {
     /// `crosshair`
     Crosshair,
     /// `default` - note: called "arrow" in winit
+    #[default]
     Default,
     /// `e-resize`
     EResize,
@@ -1391,50 +2196,99 @@ pub enum StyleCursor {
     ZoomOut,
 }
 
-impl Default for StyleCursor {
-    fn default() -> StyleCursor {
-        StyleCursor::Default
+impl fmt::Display for StyleCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::StyleCursor::*;
+        write!(f, "{}", match self {
+            Alias => "alias",
+            AllScroll => "all-scroll",
+            Cell => "cell",
+            ColResize => "col-resize",
+            ContextMenu => "context-menu",
+            Copy => "copy",
+            Crosshair => "crosshair",
+            Default => "default",
+            EResize => "e-resize",
+            EwResize => "ew-resize",
+            Grab => "grab",
+            Grabbing => "grabbing",
+            Help => "help",
+            Move => "move",
+            NResize => "n-resize",
+            NsResize => "ns-resize",
+            NeswResize => "nesw-resize",
+            NwseResize => "nwse-resize",
+            Pointer => "pointer",
+            Progress => "progress",
+            RowResize => "row-resize",
+            SResize => "s-resize",
+            SeResize => "se-resize",
+            Text => "text",
+            Unset => "unset",
+            VerticalText => "vertical-text",
+            WResize => "w-resize",
+            Wait => "wait",
+            ZoomIn => "zoom-in",
+            ZoomOut => "zoom-out",
+        })
     }
 }
 
 /// Represents a `display` attribute
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum LayoutDisplay {
     None,
+    #[default]
     Flex,
     Block,
     InlineBlock,
 }
 
-impl Default for LayoutDisplay {
-    fn default() -> Self {
-        LayoutDisplay::Flex
+impl fmt::Display for LayoutDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::LayoutDisplay::*;
+        write!(f, "{}", match self {
+            None => "none",
+            Flex => "flex",
+            Block => "block",
+            InlineBlock => "inline-block",
+        })
     }
 }
 
 /// Represents a `float` attribute
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum LayoutFloat {
+    #[default]
     Left,
     Right,
 }
 
-impl Default for LayoutFloat {
-    fn default() -> Self {
-        LayoutFloat::Left
+impl fmt::Display for LayoutFloat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::LayoutFloat::*;
+        write!(f, "{}", match self {
+            Left => "left",
+            Right => "right",
+        })
     }
 }
 
 /// Represents a `flex-direction` attribute - default: `Column`
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum LayoutBoxSizing {
+    #[default]
     ContentBox,
     BorderBox,
 }
 
-impl Default for LayoutBoxSizing {
-    fn default() -> Self {
-        LayoutBoxSizing::ContentBox
+impl fmt::Display for LayoutBoxSizing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::LayoutBoxSizing::*;
+        write!(f, "{}", match self {
+            ContentBox => "content-box",
+            BorderBox => "border-box",
+        })
     }
 }
 
@@ -1459,12 +2313,12 @@ pub struct LayoutMaxHeight(pub PixelValue);
 
 impl Default for LayoutMaxHeight {
     fn default() -> Self {
-        Self(PixelValue::px(core::f32::MAX))
+        Self(PixelValue::px(f32::MAX))
     }
 }
 impl Default for LayoutMaxWidth {
     fn default() -> Self {
-        Self(PixelValue::px(core::f32::MAX))
+        Self(PixelValue::px(f32::MAX))
     }
 }
 
@@ -1484,52 +2338,71 @@ pub struct LayoutBottom(pub PixelValue);
 /// Represents a `position` attribute - default: `Static`
 ///
 /// NOTE: No inline positioning is supported.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum LayoutPosition {
+    #[default]
     Static,
     Relative,
     Absolute,
     Fixed,
 }
 
-impl LayoutPosition {
-    pub fn is_positioned(&self) -> bool {
-        *self != LayoutPosition::Static
+impl fmt::Display for LayoutPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::LayoutPosition::*;
+        write!(f, "{}", match self {
+            Static => "static",
+            Relative => "relative",
+            Absolute => "absolute",
+            Fixed => "fixed",
+        })
     }
 }
 
-impl Default for LayoutPosition {
-    fn default() -> Self {
-        LayoutPosition::Static
+impl LayoutPosition {
+    pub fn is_positioned(&self) -> bool {
+        *self != LayoutPosition::Static
     }
 }
 
 /// Represents a `flex-wrap` attribute - default: `Wrap`
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum LayoutFlexWrap {
+    #[default]
     Wrap,
     NoWrap,
 }
 
-impl Default for LayoutFlexWrap {
-    fn default() -> Self {
-        LayoutFlexWrap::Wrap
+impl fmt::Display for LayoutFlexWrap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::LayoutFlexWrap::*;
+        write!(f, "{}", match self {
+            Wrap => "wrap",
+            NoWrap => "nowrap",
+        })
     }
 }
 
 /// Represents a `flex-direction` attribute - default: `Column`
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 #[repr(C)]
 pub enum LayoutFlexDirection {
     Row,
     RowReverse,
+    #[default]
     Column,
     ColumnReverse,
 }
 
-impl Default for LayoutFlexDirection {
-    fn default() -> Self {
-        LayoutFlexDirection::Column
+impl fmt::Display for LayoutFlexDirection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::LayoutFlexDirection::*;
+        write!(f, "{}", match self {
+            Row => "row",
+            RowReverse => "row-reverse",
+            Column => "column",
+            ColumnReverse => "column-reverse",
+        })
     }
 }
 
@@ -1578,9 +2451,10 @@ impl Default for LayoutFlexShrink {
 }
 
 /// Represents a `justify-content` attribute
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum LayoutJustifyContent {
     /// Default value. Items are positioned at the beginning of the container
+    #[default]
     Start,
     /// Items are positioned at the end of the container
     End,
@@ -1595,35 +2469,51 @@ pub enum LayoutJustifyContent {
     SpaceEvenly,
 }
 
-impl Default for LayoutJustifyContent {
-    fn default() -> Self {
-        LayoutJustifyContent::Start
+impl fmt::Display for LayoutJustifyContent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::LayoutJustifyContent::*;
+        write!(f, "{}", match self {
+            Start => "flex-start",
+            End => "flex-end",
+            Center => "center",
+            SpaceBetween => "space-between",
+            SpaceAround => "space-around",
+            SpaceEvenly => "space-evenly",
+        })
     }
 }
 
 /// Represents a `align-items` attribute
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum LayoutAlignItems {
     /// Items are stretched to fit the container
     Stretch,
     /// Items are positioned at the center of the container
     Center,
     /// Items are positioned at the beginning of the container
+    #[default]
     FlexStart,
     /// Items are positioned at the end of the container
     FlexEnd,
 }
 
-impl Default for LayoutAlignItems {
-    fn default() -> Self {
-        LayoutAlignItems::FlexStart
+impl fmt::Display for LayoutAlignItems {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::LayoutAlignItems::*;
+        write!(f, "{}", match self {
+            Stretch => "stretch",
+            Center => "center",
+            FlexStart => "flex-start",
+            FlexEnd => "flex-end",
+        })
     }
 }
 
 /// Represents a `align-content` attribute
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum LayoutAlignContent {
     /// Default value. Lines stretch to take up the remaining space
+    #[default]
     Stretch,
     /// Lines are packed toward the center of the flex container
     Center,
@@ -1637,9 +2527,17 @@ pub enum LayoutAlignContent {
     SpaceAround,
 }
 
-impl Default for LayoutAlignContent {
-    fn default() -> Self {
-        LayoutAlignContent::Stretch
+impl fmt::Display for LayoutAlignContent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::LayoutAlignContent::*;
+        write!(f, "{}", match self {
+            Stretch => "stretch",
+            Center => "center",
+            Start => "flex-start",
+            End => "flex-end",
+            SpaceBetween => "space-between",
+            SpaceAround => "space-around",
+        })
     }
 }
 
@@ -1744,6 +2642,15 @@ pub enum Direction {
     FromTo(DirectionCorner, DirectionCorner),
 }
 
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Direction::Angle(a) => write!(f, "{}", a),
+            Direction::FromTo(_, to) => write!(f, "to {}", to),
+        }
+    }
+}
+
 impl Default for Direction {
     fn default() -> Self {
         Direction::FromTo(DirectionCorner::Top, DirectionCorner::Bottom)
@@ -1793,20 +2700,20 @@ impl Direction {
                 };
 
                 // assuming deg = 36deg, then degree_diff_to_corner = 9deg
-                let degree_diff_to_corner = ending_point_degrees as f32 - deg;
+                let degree_diff_to_corner = ending_point_degrees - deg;
 
                 // Searched_len is the distance between the center of the rect and the
                 // ending point of the gradient
                 let searched_len = libm::fabsf(libm::cosf(
-                    hypotenuse_len * degree_diff_to_corner.to_radians() as f32,
+                    hypotenuse_len * degree_diff_to_corner.to_radians(),
                 ));
 
                 // TODO: This searched_len is incorrect...
 
                 // Once we have the length, we can simply rotate the length by the angle,
                 // then translate it to the center of the rect
-                let dx = libm::sinf(deg.to_radians() as f32) * searched_len;
-                let dy = libm::cosf(deg.to_radians() as f32) * searched_len;
+                let dx = libm::sinf(deg.to_radians()) * searched_len;
+                let dy = libm::cosf(deg.to_radians()) * searched_len;
 
                 let start_point_location = LayoutPoint {
                     x: libm::roundf(width_half + dx) as isize,
@@ -1825,18 +2732,13 @@ impl Direction {
 }
 
 /// Whether a `gradient` should be repeated or clamped to the edges.
-#[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash, Default)]
 pub enum ExtendMode {
+    #[default]
     Clamp,
     Repeat,
 }
 
-impl Default for ExtendMode {
-    fn default() -> Self {
-        ExtendMode::Clamp
-    }
-}
-
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct LinearColorStop {
     // this is set to None if there was no offset that could be parsed
@@ -1851,6 +2753,12 @@ pub struct NormalizedLinearColorStop {
     pub color: ColorU,
 }
 
+impl fmt::Display for NormalizedLinearColorStop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.color, self.offset)
+    }
+}
+
 impl LinearColorStop {
     pub fn to_normalized(stops: &[LinearColorStop]) -> Vec<NormalizedLinearColorStop> {
         const MIN_STOP_DEGREE: f32 = 0.0;
@@ -1882,11 +2790,11 @@ impl LinearColorStop {
             if let Some(s) = stop.offset {
                 let current_stop_val = s.get();
                 if stops_to_distribute != 0 {
-                    let last_stop_val = stops[(stop_id - stops_to_distribute)].offset.get();
+                    let last_stop_val = stops[stop_id - stops_to_distribute].offset.get();
                     let value_to_add_per_stop = (current_stop_val.max(last_stop_val)
                         - last_stop_val)
                         / (stops_to_distribute - 1) as f32;
-                    for (s_id, s) in stops[(stop_id - stops_to_distribute)..stop_id]
+                    for (s_id, s) in stops[stop_id - stops_to_distribute..stop_id]
                         .iter_mut()
                         .enumerate()
                     {
@@ -1908,7 +2816,7 @@ impl LinearColorStop {
                 .get();
             let value_to_add_per_stop = (MAX_STOP_DEGREE.max(last_stop_val) - last_stop_val)
                 / (stops_to_distribute - 1) as f32;
-            for (s_id, s) in stops[(stops_len - stops_to_distribute)..]
+            for (s_id, s) in stops[stops_len - stops_to_distribute..]
                 .iter_mut()
                 .enumerate()
             {
@@ -1934,6 +2842,12 @@ pub struct NormalizedRadialColorStop {
     pub color: ColorU,
 }
 
+impl fmt::Display for NormalizedRadialColorStop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.color, self.angle)
+    }
+}
+
 impl RadialColorStop {
     pub fn to_normalized(stops: &[RadialColorStop]) -> Vec<NormalizedRadialColorStop> {
         const MIN_STOP_DEGREE: f32 = 0.0;
@@ -1965,11 +2879,11 @@ impl RadialColorStop {
             if let Some(s) = stop.offset {
                 let current_stop_val = s.to_degrees();
                 if stops_to_distribute != 0 {
-                    let last_stop_val = stops[(stop_id - stops_to_distribute)].angle.to_degrees();
+                    let last_stop_val = stops[stop_id - stops_to_distribute].angle.to_degrees();
                     let value_to_add_per_stop = (current_stop_val.max(last_stop_val)
                         - last_stop_val)
                         / (stops_to_distribute - 1) as f32;
-                    for (s_id, s) in stops[(stop_id - stops_to_distribute)..stop_id]
+                    for (s_id, s) in stops[stop_id - stops_to_distribute..stop_id]
                         .iter_mut()
                         .enumerate()
                     {
@@ -1990,7 +2904,7 @@ impl RadialColorStop {
                 .to_degrees();
             let value_to_add_per_stop = (MAX_STOP_DEGREE.max(last_stop_val) - last_stop_val)
                 / (stops_to_distribute - 1) as f32;
-            for (s_id, s) in stops[(stops_len - stops_to_distribute)..]
+            for (s_id, s) in stops[stops_len - stops_to_distribute..]
                 .iter_mut()
                 .enumerate()
             {
@@ -2002,7 +2916,7 @@ impl RadialColorStop {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum RadialGradientSize {
     // The gradient's ending shape meets the side of the box closest to its center
     // (for circles) or meets both the vertical and horizontal sides closest to the
@@ -2016,12 +2930,19 @@ pub enum RadialGradientSize {
     FarthestSide,
     // The default value, the gradient's ending shape is sized so that it exactly
     // meets the farthest corner of the box from its center
+    #[default]
     FarthestCorner,
 }
 
-impl Default for RadialGradientSize {
-    fn default() -> Self {
-        RadialGradientSize::FarthestCorner
+impl fmt::Display for RadialGradientSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::RadialGradientSize::*;
+        write!(f, "{}", match self {
+            ClosestSide => "closest-side",
+            ClosestCorner => "closest-corner",
+            FarthestSide => "farthest-side",
+            FarthestCorner => "farthest-corner",
+        })
     }
 }
 
@@ -2036,15 +2957,20 @@ impl RadialGradientSize {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum Shape {
+    #[default]
     Ellipse,
     Circle,
 }
 
-impl Default for Shape {
-    fn default() -> Self {
-        Shape::Ellipse
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::Shape::*;
+        write!(f, "{}", match self {
+            Ellipse => "ellipse",
+            Circle => "circle",
+        })
     }
 }
 
@@ -2055,6 +2981,17 @@ pub struct LinearGradient {
     pub stops: Vec<NormalizedLinearColorStop>,
 }
 
+impl fmt::Display for LinearGradient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.extend_mode == ExtendMode::Repeat {
+            write!(f, "repeating-")?;
+        }
+        write!(f, "linear-gradient({}, ", self.direction)?;
+        write_list(f, &self.stops, ", ")?;
+        write!(f, ")")
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ConicGradient {
     pub extend_mode: ExtendMode,               // default = clamp (no-repeat)
@@ -2063,6 +3000,17 @@ pub struct ConicGradient {
     pub stops: Vec<NormalizedRadialColorStop>, // default = []
 }
 
+impl fmt::Display for ConicGradient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.extend_mode == ExtendMode::Repeat {
+            write!(f, "repeating-")?;
+        }
+        write!(f, "conic-gradient(from {} at {}, ", self.angle, self.center)?;
+        write_list(f, &self.stops, ", ")?;
+        write!(f, ")")
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct RadialGradient {
     pub shape: Shape,
@@ -2072,6 +3020,17 @@ pub struct RadialGradient {
     pub stops: Vec<NormalizedLinearColorStop>,
 }
 
+impl fmt::Display for RadialGradient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.extend_mode == ExtendMode::Repeat {
+            write!(f, "repeating-")?;
+        }
+        write!(f, "radial-gradient({} {} at {}, ", self.shape, self.size, self.position)?;
+        write_list(f, &self.stops, ", ")?;
+        write!(f, ")")
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum BackgroundPositionHorizontal {
     Left,
@@ -2080,6 +3039,18 @@ pub enum BackgroundPositionHorizontal {
     Exact(PixelValue),
 }
 
+impl fmt::Display for BackgroundPositionHorizontal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::BackgroundPositionHorizontal::*;
+        match self {
+            Left => write!(f, "left"),
+            Center => write!(f, "center"),
+            Right => write!(f, "right"),
+            Exact(v) => write!(f, "{}", v),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum BackgroundPositionVertical {
     Top,
@@ -2088,6 +3059,18 @@ pub enum BackgroundPositionVertical {
     Exact(PixelValue),
 }
 
+impl fmt::Display for BackgroundPositionVertical {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::BackgroundPositionVertical::*;
+        match self {
+            Top => write!(f, "top"),
+            Center => write!(f, "center"),
+            Bottom => write!(f, "bottom"),
+            Exact(v) => write!(f, "{}", v),
+        }
+    }
+}
+
 /// Represents a `background-position` attribute
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StyleBackgroundPosition {
@@ -2095,6 +3078,12 @@ pub struct StyleBackgroundPosition {
     pub vertical: BackgroundPositionVertical,
 }
 
+impl fmt::Display for StyleBackgroundPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.horizontal, self.vertical)
+    }
+}
+
 impl Default for StyleBackgroundPosition {
     fn default() -> Self {
         StyleBackgroundPosition {
@@ -2113,6 +3102,23 @@ pub enum StyleBackgroundContent {
     Color(ColorU),
 }
 
+impl fmt::Display for StyleBackgroundContent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::StyleBackgroundContent::*;
+        match self {
+            LinearGradient(g) => write!(f, "{}", g),
+            RadialGradient(g) => write!(f, "{}", g),
+            ConicGradient(g) => write!(f, "{}", g),
+            Image(id) => {
+                write!(f, "url(")?;
+                write_css_string(f, id)?;
+                write!(f, ")")
+            }
+            Color(c) => write!(f, "{}", c),
+        }
+    }
+}
+
 impl Default for StyleBackgroundContent {
     fn default() -> StyleBackgroundContent {
         StyleBackgroundContent::Color(ColorU::TRANSPARENT)
@@ -2126,41 +3132,55 @@ impl From<String> for StyleBackgroundContent {
 }
 
 /// Represents a `background-size` attribute
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum StyleBackgroundSize {
     ExactSize(PixelValue, PixelValue),
+    #[default]
     Contain,
     Cover,
 }
 
-impl Default for StyleBackgroundSize {
-    fn default() -> Self {
-        StyleBackgroundSize::Contain
+impl fmt::Display for StyleBackgroundSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::StyleBackgroundSize::*;
+        match self {
+            ExactSize(w, h) => write!(f, "{} {}", w, h),
+            Contain => write!(f, "contain"),
+            Cover => write!(f, "cover"),
+        }
     }
 }
 
 /// Represents a `background-repeat` attribute
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum StyleBackgroundRepeat {
     NoRepeat,
+    #[default]
     Repeat,
     RepeatX,
     RepeatY,
 }
 
-impl Default for StyleBackgroundRepeat {
-    fn default() -> Self {
-        StyleBackgroundRepeat::Repeat
+impl fmt::Display for StyleBackgroundRepeat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::StyleBackgroundRepeat::*;
+        write!(f, "{}", match self {
+            NoRepeat => "no-repeat",
+            Repeat => "repeat",
+            RepeatX => "repeat-x",
+            RepeatY => "repeat-y",
+        })
     }
 }
 
 /// Represents a `overflow-x` or `overflow-y` property, see
 /// [`TextOverflowBehaviour`](./struct.TextOverflowBehaviour.html) - default: `Auto`
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum LayoutOverflow {
     /// Always shows a scroll bar, overflows on scroll
     Scroll,
     /// Does not show a scroll bar by default, only when text is overflowing
+    #[default]
     Auto,
     /// Never shows a scroll bar, simply clips text
     Hidden,
@@ -2168,9 +3188,15 @@ pub enum LayoutOverflow {
     Visible,
 }
 
-impl Default for LayoutOverflow {
-    fn default() -> Self {
-        LayoutOverflow::Auto
+impl fmt::Display for LayoutOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::LayoutOverflow::*;
+        write!(f, "{}", match self {
+            Scroll => "scroll",
+            Auto => "auto",
+            Hidden => "hidden",
+            Visible => "visible",
+        })
     }
 }
 
@@ -2254,10 +3280,11 @@ pub struct StyleBorderRightColor(pub ColorU);
 pub struct StyleBorderBottomColor(pub ColorU);
 
 /// Style of a `border`: solid, double, dash, ridge, etc.
-#[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash, Default)]
 #[repr(C)]
 pub enum BorderStyle {
     None,
+    #[default]
     Solid,
     Double,
     Dotted,
@@ -2289,16 +3316,7 @@ impl fmt::Display for BorderStyle {
 
 impl BorderStyle {
     pub fn is_normalized(&self) -> bool {
-        match self {
-            BorderStyle::None => false,
-            _ => true,
-        }
-    }
-}
-
-impl Default for BorderStyle {
-    fn default() -> Self {
-        BorderStyle::Solid
+        !matches!(self, BorderStyle::None)
     }
 }
 
@@ -2356,6 +3374,19 @@ pub struct StyleBoxShadow {
     pub clip_mode: BoxShadowClipMode,
 }
 
+impl fmt::Display for StyleBoxShadow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.clip_mode == BoxShadowClipMode::Inset {
+            write!(f, "inset ")?;
+        }
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.offset.0, self.offset.1, self.blur_radius, self.spread_radius, self.color
+        )
+    }
+}
+
 /// Holds info necessary for layouting / styling scrollbars (-webkit-scrollbar)
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ScrollbarInfo {
@@ -2408,6 +3439,12 @@ pub struct ScrollbarStyle {
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StyleOpacity(pub PercentageValue);
 
+impl fmt::Display for StyleOpacity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.normalized())
+    }
+}
+
 impl Default for StyleOpacity {
     fn default() -> Self {
         StyleOpacity(PercentageValue::const_new(0))
@@ -2434,12 +3471,80 @@ pub enum StyleTransform {
     ScaleX(PercentageValue),
     ScaleY(PercentageValue),
     ScaleZ(PercentageValue),
+    /// Skew angles are stored in degrees, as the `normalized()` value of the percentage
     Skew(StyleTransformSkew2D),
     SkewX(PercentageValue),
     SkewY(PercentageValue),
     Perspective(PixelValue),
 }
 
+impl fmt::Display for StyleTransform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::StyleTransform::*;
+        // scale factors are stored as percentages, but written as plain numbers (`scale(1.5)`)
+        let n = |p: &PercentageValue| p.normalized();
+        match self {
+            Matrix(m) => {
+                write!(f, "matrix(")?;
+                write_matrix_values(f, &[m.a, m.b, m.c, m.d, m.tx, m.ty])?;
+                write!(f, ")")
+            }
+            Matrix3D(m) => {
+                write!(f, "matrix3d(")?;
+                write_matrix_values(f, &[
+                    m.m11, m.m12, m.m13, m.m14,
+                    m.m21, m.m22, m.m23, m.m24,
+                    m.m31, m.m32, m.m33, m.m34,
+                    m.m41, m.m42, m.m43, m.m44,
+                ])?;
+                write!(f, ")")
+            }
+            Translate(t) => write!(f, "translate({}, {})", t.x, t.y),
+            Translate3D(t) => write!(f, "translate3d({}, {}, {})", t.x, t.y, t.z),
+            TranslateX(x) => write!(f, "translateX({})", x),
+            TranslateY(y) => write!(f, "translateY({})", y),
+            TranslateZ(z) => write!(f, "translateZ({})", z),
+            Rotate(a) => write!(f, "rotate({})", a),
+            Rotate3D(r) => write!(f, "rotate3d({}, {}, {}, {})", n(&r.x), n(&r.y), n(&r.z), r.angle),
+            RotateX(a) => write!(f, "rotateX({})", a),
+            RotateY(a) => write!(f, "rotateY({})", a),
+            RotateZ(a) => write!(f, "rotateZ({})", a),
+            Scale(s) => write!(f, "scale({}, {})", n(&s.x), n(&s.y)),
+            Scale3D(s) => write!(f, "scale3d({}, {}, {})", n(&s.x), n(&s.y), n(&s.z)),
+            ScaleX(x) => write!(f, "scaleX({})", n(x)),
+            ScaleY(y) => write!(f, "scaleY({})", n(y)),
+            ScaleZ(z) => write!(f, "scaleZ({})", n(z)),
+            Skew(s) => write!(f, "skew({}deg, {}deg)", n(&s.x), n(&s.y)),
+            SkewX(x) => write!(f, "skewX({}deg)", n(x)),
+            SkewY(y) => write!(f, "skewY({}deg)", n(y)),
+            Perspective(p) if *p == PERSPECTIVE_NONE_DISTANCE => write!(f, "perspective(none)"),
+            Perspective(p) => write!(f, "perspective({})", p),
+        }
+    }
+}
+
+/// Distance of `perspective(none)`: the largest `PixelValue`, i.e. an infinitely far away viewer
+const PERSPECTIVE_NONE_DISTANCE: PixelValue = PixelValue { metric: SizeMetric::Px, number: FloatValue(isize::MAX) };
+
+impl StyleTransform {
+    /// `perspective(none)`, the identity function of `perspective()`
+    pub const PERSPECTIVE_NONE: StyleTransform = StyleTransform::Perspective(PERSPECTIVE_NONE_DISTANCE);
+}
+
+/// Writes the values of a `matrix()` / `matrix3d()`, pixel values are written as plain numbers
+fn write_matrix_values(f: &mut fmt::Formatter, values: &[PixelValue]) -> fmt::Result {
+    for (i, v) in values.iter().enumerate() {
+        if i != 0 {
+            write!(f, ", ")?;
+        }
+        match v.metric {
+            SizeMetric::Px => write!(f, "{}", v.number)?,
+            _ => write!(f, "{}", v)?,
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StyleTransformMatrix2D {
     pub a: PixelValue,
@@ -2518,6 +3623,12 @@ pub struct StyleTransformOrigin {
     pub y: PixelValue,
 }
 
+impl fmt::Display for StyleTransformOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.x, self.y)
+    }
+}
+
 impl StyleTransformOrigin {
     pub fn interpolate(&self, other: &Self, t: f32) -> Self {
         Self {
@@ -2543,6 +3654,12 @@ pub struct StylePerspectiveOrigin {
     pub y: PixelValue,
 }
 
+impl fmt::Display for StylePerspectiveOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.x, self.y)
+    }
+}
+
 impl StylePerspectiveOrigin {
     pub fn interpolate(&self, other: &Self, t: f32) -> Self {
         Self {
@@ -2559,18 +3676,26 @@ impl Default for StylePerspectiveOrigin {
 }
 
 /// Represents a `backface-visibility` attribute
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum StyleBackfaceVisibility {
     Hidden,
+    #[default]
     Visible,
 }
 
-impl Default for StyleBackfaceVisibility {
-    fn default() -> Self { StyleBackfaceVisibility::Visible }
+impl fmt::Display for StyleBackfaceVisibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::StyleBackfaceVisibility::*;
+        write!(f, "{}", match self {
+            Hidden => "hidden",
+            Visible => "visible",
+        })
+    }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum StyleMixBlendMode {
+    #[default]
     Normal,
     Multiply,
     Screen,
@@ -2589,12 +3714,6 @@ pub enum StyleMixBlendMode {
     Luminosity,
 }
 
-impl Default for StyleMixBlendMode {
-    fn default() -> StyleMixBlendMode {
-        StyleMixBlendMode::Normal
-    }
-}
-
 impl fmt::Display for StyleMixBlendMode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::StyleMixBlendMode::*;
@@ -2632,6 +3751,27 @@ pub enum StyleFilter {
     Composite(StyleCompositeFilter),
 }
 
+impl fmt::Display for StyleFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::StyleFilter::*;
+        match self {
+            Blend(m) => write!(f, "blend({})", m),
+            Flood(c) => write!(f, "flood({})", c),
+            Blur(b) => write!(f, "blur({}, {})", b.width, b.height),
+            Opacity(o) => write!(f, "opacity({})", o),
+            ColorMatrix(m) => {
+                write!(f, "color-matrix(")?;
+                write_list(f, &m.matrix, ", ")?;
+                write!(f, ")")
+            }
+            DropShadow(s) => write!(f, "drop-shadow({})", s),
+            ComponentTransfer => write!(f, "component-transfer()"),
+            Offset(o) => write!(f, "offset({}, {})", o.x, o.y),
+            Composite(c) => write!(f, "composite({})", c),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StyleBlur {
     pub width: PixelValue,
@@ -2659,3 +3799,429 @@ pub enum StyleCompositeFilter {
     Arithmetic([FloatValue; 4]),
 }
 
+impl fmt::Display for StyleCompositeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::StyleCompositeFilter::*;
+        match self {
+            Over => write!(f, "over"),
+            In => write!(f, "in"),
+            Atop => write!(f, "atop"),
+            Out => write!(f, "out"),
+            Xor => write!(f, "xor"),
+            Lighter => write!(f, "lighter"),
+            Arithmetic(k) => write!(f, "arithmetic, {}, {}, {}, {}", k[0], k[1], k[2], k[3]),
+        }
+    }
+}
+
+
+
+/// Name of a `@keyframes` rule in `animation-name`, i.e. `slide-in` or `"slide in"`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleAnimationName(pub String);
+
+impl fmt::Display for StyleAnimationName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let is_keyword = ["none", "initial", "inherit", "auto"].iter().any(|k| self.0.eq_ignore_ascii_case(k));
+        let is_ident = self.0.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '-')
+            && self.0.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if is_ident && !is_keyword {
+            write!(f, "{}", self.0)
+        } else {
+            write_css_string(f, &self.0)
+        }
+    }
+}
+
+/// A time such as `300ms` or `1.5s`, stored in milliseconds
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleDuration(pub FloatValue);
+
+impl StyleDuration {
+    pub fn ms(ms: f32) -> Self {
+        StyleDuration(FloatValue::new(ms))
+    }
+
+    pub fn get_ms(&self) -> f32 {
+        self.0.get()
+    }
+}
+
+impl fmt::Display for StyleDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ms = self.get_ms();
+        if ms != 0.0 && libm::fmodf(ms, 100.0) == 0.0 {
+            write!(f, "{}s", FloatValue::new(ms / 1000.0))
+        } else {
+            write!(f, "{}ms", self.0)
+        }
+    }
+}
+
+/// Easing function of an animation or transition, maps the input progress (0 to 1) to the
+/// output progress of the interpolation - default: `Ease`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum TimingFunction {
+    #[default]
+    Ease,
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// `cubic-bezier(x1, y1, x2, y2)`, `x1` and `x2` must be in `[0, 1]`
+    CubicBezier(FloatValue, FloatValue, FloatValue, FloatValue),
+    /// `steps(n, position)`: `n` equal steps, `step-start` is `steps(1, jump-start)` and
+    /// `step-end` is `steps(1, jump-end)`. `n` must be at least 2 for `jump-none`.
+    Steps(u32, StepPosition),
+    /// `linear(0, 0.25 75%, 1)`: a piecewise linear function through the points (at least two,
+    /// sorted by their input), inputs that were omitted are already filled in
+    LinearPoints(Vec<LinearStop>),
+}
+
+impl TimingFunction {
+    /// Returns the eased progress for the input `progress` (0 to 1). The output can be
+    /// outside of `[0, 1]`, i.e. for a `cubic-bezier()` that overshoots.
+    pub fn apply(&self, progress: f32) -> f32 {
+        match self {
+            TimingFunction::Linear => progress,
+            TimingFunction::Ease => cubic_bezier(0.25, 0.1, 0.25, 1.0, progress),
+            TimingFunction::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, progress),
+            TimingFunction::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, progress),
+            TimingFunction::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, progress),
+            TimingFunction::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1.get(), y1.get(), x2.get(), y2.get(), progress),
+            TimingFunction::Steps(steps, position) => apply_steps(*steps, *position, progress),
+            TimingFunction::LinearPoints(stops) => apply_linear_points(stops, progress),
+        }
+    }
+}
+
+/// Evaluates the cubic bézier curve from `(0, 0)` to `(1, 1)` with the control points
+/// `(x1, y1)` and `(x2, y2)` at `x`: solves `x(t) = x` for the curve parameter `t` with
+/// Newton's method (falling back to bisection if it doesn't converge) and returns `y(t)`.
+/// Outside of `[0, 1]`, the curve continues along the tangent of the nearest end point.
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, x: f32) -> f32 {
+    const EPSILON: f64 = 1e-7;
+    let (x1, y1, x2, y2, x) = (x1 as f64, y1 as f64, x2 as f64, y2 as f64, x as f64);
+
+    if x < 0.0 {
+        let slope = if x1 > 0.0 { y1 / x1 } else if x2 > 0.0 { y2 / x2 } else { 0.0 };
+        return (slope * x) as f32;
+    } else if x > 1.0 {
+        let slope = if x2 < 1.0 { (y2 - 1.0) / (x2 - 1.0) } else if x1 < 1.0 { (y1 - 1.0) / (x1 - 1.0) } else { 0.0 };
+        return (1.0 + slope * (x - 1.0)) as f32;
+    }
+
+    // coefficients of the polynomials x(t) = ((ax * t + bx) * t + cx) * t and y(t)
+    let cx = 3.0 * x1;
+    let bx = 3.0 * (x2 - x1) - cx;
+    let ax = 1.0 - cx - bx;
+    let cy = 3.0 * y1;
+    let by = 3.0 * (y2 - y1) - cy;
+    let ay = 1.0 - cy - by;
+    let sample_x = |t: f64| ((ax * t + bx) * t + cx) * t;
+    let sample_y = |t: f64| ((ay * t + by) * t + cy) * t;
+    let derivative_x = |t: f64| (3.0 * ax * t + 2.0 * bx) * t + cx;
+
+    let mut t = x;
+    for _ in 0..8 {
+        let error = sample_x(t) - x;
+        if libm::fabs(error) < EPSILON {
+            return sample_y(t) as f32;
+        }
+        let derivative = derivative_x(t);
+        if libm::fabs(derivative) < 1e-6 {
+            break;
+        }
+        t -= error / derivative;
+        if !(0.0..=1.0).contains(&t) {
+            break;
+        }
+    }
+
+    // x(t) is monotonic, since x1 and x2 are in [0, 1]
+    let (mut low, mut high) = (0.0, 1.0);
+    t = x;
+    for _ in 0..64 {
+        let value = sample_x(t);
+        if libm::fabs(value - x) < EPSILON {
+            break;
+        }
+        if value < x {
+            low = t;
+        } else {
+            high = t;
+        }
+        t = (low + high) / 2.0;
+    }
+    sample_y(t) as f32
+}
+
+/// Evaluates `steps(steps, position)` at `progress`
+fn apply_steps(steps: u32, position: StepPosition, progress: f32) -> f32 {
+    let steps = steps as f32;
+    let mut step = libm::floorf(progress * steps);
+    if matches!(position, StepPosition::JumpStart | StepPosition::JumpBoth) {
+        step += 1.0;
+    }
+    let jumps = match position {
+        StepPosition::JumpStart | StepPosition::JumpEnd => steps,
+        StepPosition::JumpNone => steps - 1.0,
+        StepPosition::JumpBoth => steps + 1.0,
+    }
+    .max(1.0);
+
+    if progress >= 0.0 && step < 0.0 {
+        step = 0.0;
+    }
+    if progress <= 1.0 && step > jumps {
+        step = jumps;
+    }
+    step / jumps
+}
+
+/// Evaluates `linear()` at `progress`: interpolates between the two points around the
+/// progress, before the first and after the last point the first / last segment is extended
+fn apply_linear_points(stops: &[LinearStop], progress: f32) -> f32 {
+    if stops.len() < 2 {
+        return stops.first().map(|s| s.output.get()).unwrap_or(progress);
+    }
+    let index = stops.iter().rposition(|s| s.input.get() / 100.0 <= progress).unwrap_or(0).min(stops.len() - 2);
+    let (a, b) = (&stops[index], &stops[index + 1]);
+    let (a_input, b_input) = (a.input.get() / 100.0, b.input.get() / 100.0);
+    if a_input == b_input {
+        return b.output.get();
+    }
+    let t = (progress - a_input) / (b_input - a_input);
+    a.output.get() + (b.output.get() - a.output.get()) * t
+}
+
+impl fmt::Display for TimingFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimingFunction::Ease => write!(f, "ease"),
+            TimingFunction::Linear => write!(f, "linear"),
+            TimingFunction::EaseIn => write!(f, "ease-in"),
+            TimingFunction::EaseOut => write!(f, "ease-out"),
+            TimingFunction::EaseInOut => write!(f, "ease-in-out"),
+            TimingFunction::CubicBezier(x1, y1, x2, y2) => write!(f, "cubic-bezier({}, {}, {}, {})", x1, y1, x2, y2),
+            TimingFunction::Steps(steps, StepPosition::JumpEnd) => write!(f, "steps({})", steps),
+            TimingFunction::Steps(steps, position) => write!(f, "steps({}, {})", steps, position),
+            TimingFunction::LinearPoints(stops) => {
+                write!(f, "linear(")?;
+                write_list(f, stops, ", ")?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// When the jumps of `steps()` happen - default: `JumpEnd`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum StepPosition {
+    /// The first jump happens at the start (`jump-start` or `start`)
+    JumpStart,
+    /// The last jump happens at the end (`jump-end` or `end`)
+    #[default]
+    JumpEnd,
+    /// No jump at the start or the end, the first and the last step are only half as long
+    JumpNone,
+    /// Jumps at the start and at the end
+    JumpBoth,
+}
+
+impl fmt::Display for StepPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StepPosition::JumpStart => write!(f, "jump-start"),
+            StepPosition::JumpEnd => write!(f, "jump-end"),
+            StepPosition::JumpNone => write!(f, "jump-none"),
+            StepPosition::JumpBoth => write!(f, "jump-both"),
+        }
+    }
+}
+
+/// One point of a `linear()` timing function, i.e. `0.25 75%`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LinearStop {
+    pub output: FloatValue,
+    pub input: PercentageValue,
+}
+
+impl fmt::Display for LinearStop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.output, self.input)
+    }
+}
+
+/// Represents an `animation-iteration-count` attribute - default: `Count(1)`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleIterationCount {
+    Infinite,
+    /// Can be fractional: `0.5` runs half of the animation
+    Count(FloatValue),
+}
+
+impl Default for StyleIterationCount {
+    fn default() -> Self {
+        StyleIterationCount::Count(FloatValue::const_new(1))
+    }
+}
+
+impl fmt::Display for StyleIterationCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StyleIterationCount::Infinite => write!(f, "infinite"),
+            StyleIterationCount::Count(count) => write!(f, "{}", count),
+        }
+    }
+}
+
+/// Represents an `animation-direction` attribute - default: `Normal`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum StyleAnimationDirection {
+    #[default]
+    Normal,
+    Reverse,
+    /// Every second iteration runs backwards
+    Alternate,
+    AlternateReverse,
+}
+
+impl fmt::Display for StyleAnimationDirection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StyleAnimationDirection::Normal => write!(f, "normal"),
+            StyleAnimationDirection::Reverse => write!(f, "reverse"),
+            StyleAnimationDirection::Alternate => write!(f, "alternate"),
+            StyleAnimationDirection::AlternateReverse => write!(f, "alternate-reverse"),
+        }
+    }
+}
+
+/// Represents an `animation-fill-mode` attribute: whether the animation applies
+/// before its delay (`Backwards`) and after it ended (`Forwards`) - default: `None`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum StyleAnimationFillMode {
+    #[default]
+    None,
+    Forwards,
+    Backwards,
+    Both,
+}
+
+impl fmt::Display for StyleAnimationFillMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StyleAnimationFillMode::None => write!(f, "none"),
+            StyleAnimationFillMode::Forwards => write!(f, "forwards"),
+            StyleAnimationFillMode::Backwards => write!(f, "backwards"),
+            StyleAnimationFillMode::Both => write!(f, "both"),
+        }
+    }
+}
+
+/// Represents an `animation-play-state` attribute - default: `Running`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum StyleAnimationPlayState {
+    #[default]
+    Running,
+    Paused,
+}
+
+impl fmt::Display for StyleAnimationPlayState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StyleAnimationPlayState::Running => write!(f, "running"),
+            StyleAnimationPlayState::Paused => write!(f, "paused"),
+        }
+    }
+}
+
+/// One item of a `transition-property` list - default: `All`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum StyleTransitionProperty {
+    /// `all`: every property that can be interpolated
+    #[default]
+    All,
+    Property(PType),
+    /// A shorthand such as `margin` transitions all of its longhands
+    Shorthand(CombinedPropertyType),
+}
+
+impl StyleTransitionProperty {
+    /// Returns whether a change of the given property is transitioned by this item
+    pub fn contains(&self, property_type: PType) -> bool {
+        match self {
+            StyleTransitionProperty::All => true,
+            StyleTransitionProperty::Property(p) => *p == property_type,
+            StyleTransitionProperty::Shorthand(shorthand) => shorthand.longhands().contains(&property_type),
+        }
+    }
+}
+
+impl fmt::Display for StyleTransitionProperty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StyleTransitionProperty::All => write!(f, "all"),
+            StyleTransitionProperty::Property(p) => write!(f, "{}", p.to_str()),
+            StyleTransitionProperty::Shorthand(shorthand) => write!(f, "{}", shorthand.to_str()),
+        }
+    }
+}
+
+#[test]
+fn test_property_type() {
+    for p in [PType::TextColor, PType::MinWidth, PType::BackgroundContent, PType::BorderTopLeftRadius, PType::BoxShadowTop, PType::TextShadow, PType::Content] {
+        assert_eq!(PType::from_str(p.to_str()), Some(p));
+    }
+    assert_eq!(PType::from_str("margin"), None);
+
+    let min_width = Property::MinWidth(Value::Exact(LayoutMaxWidth(PixelValue::px(5.0))));
+    assert_eq!(min_width.get_type(), PType::MinWidth);
+    assert_eq!(Property::Opacity(Value::Inherit).get_type().to_str(), "opacity");
+}
+
+#[test]
+fn test_angle_value() {
+    // `FloatValue` keeps three decimals, so `PI` rad is only close to 180deg
+    assert!((AngleValue::rad(core::f32::consts::PI).to_degrees() - 180.0).abs() < 0.1);
+    assert_eq!(AngleValue::grad(100.0).to_degrees(), 90.0);
+    assert_eq!(AngleValue::turn(1.5).to_degrees(), 180.0);
+    assert_eq!(AngleValue::deg(-90.0).to_degrees(), 270.0);
+    assert_eq!(AngleValue::deg(450.0).to_degrees_unclamped(), 450.0);
+    assert_eq!(AngleValue::grad(400.0).to_degrees_unclamped(), 360.0);
+}
+
+#[test]
+fn test_timing_function() {
+    let close = |a: f32, b: f32| (a - b).abs() < 1e-5;
+    let bezier = |x1: f32, y1: f32, x2: f32, y2: f32| {
+        TimingFunction::CubicBezier(FloatValue::new(x1), FloatValue::new(y1), FloatValue::new(x2), FloatValue::new(y2))
+    };
+
+    assert!(close(TimingFunction::Ease.apply(0.5), 0.802_403_4));
+    assert!(close(TimingFunction::EaseInOut.apply(0.5), 0.5));
+    assert!(close(TimingFunction::EaseIn.apply(0.0), 0.0) && close(TimingFunction::EaseIn.apply(1.0), 1.0));
+    assert!(close(bezier(0.0, 0.0, 1.0, 1.0).apply(0.3), 0.3));
+    // steep curves converge with the bisection fallback
+    assert!(close(bezier(1.0, 0.0, 0.0, 1.0).apply(0.5), 0.5));
+    assert!(bezier(0.5, -0.5, 0.5, 1.5).apply(0.1) < 0.0);
+    // extrapolated along the tangents outside of [0, 1]
+    assert!(close(bezier(0.5, 1.0, 0.5, 1.0).apply(-0.5), -1.0));
+    assert!(close(bezier(0.0, 0.0, 0.5, 0.0).apply(2.0), 3.0));
+
+    assert_eq!(TimingFunction::Steps(4, StepPosition::JumpEnd).apply(0.3), 0.25);
+    assert_eq!(TimingFunction::Steps(4, StepPosition::JumpStart).apply(0.3), 0.5);
+    assert_eq!(TimingFunction::Steps(5, StepPosition::JumpNone).apply(0.5), 0.5);
+    assert_eq!(TimingFunction::Steps(3, StepPosition::JumpBoth).apply(0.0), 0.25);
+    assert_eq!(TimingFunction::Steps(3, StepPosition::JumpBoth).apply(1.0), 1.0);
+
+    let stop = |output: f32, input: f32| LinearStop { output: FloatValue::new(output), input: PercentageValue::new(input) };
+    let linear = TimingFunction::LinearPoints(vec![stop(0.0, 0.0), stop(0.25, 75.0), stop(1.0, 100.0)]);
+    assert!(close(linear.apply(0.375), 0.125));
+    assert!(close(linear.apply(0.875), 0.625));
+    assert!(close(linear.apply(1.5), 2.5));
+    let jump = TimingFunction::LinearPoints(vec![stop(0.0, 0.0), stop(0.0, 50.0), stop(1.0, 50.0), stop(1.0, 100.0)]);
+    assert_eq!(jump.apply(0.49), 0.0);
+    assert_eq!(jump.apply(0.5), 1.0);
+}
@@ -0,0 +1,2274 @@
+//! Parsers for the values of the CSS properties, i.e. the `5px` in `width: 5px`
+
+use crate::property::*;
+use crate::tokenizer::{SpannedToken, Token};
+
+/// Cursor over a list of tokens (usually the value of one declaration),
+/// that skips whitespace by default
+#[derive(Debug, Clone)]
+pub(crate) struct ValueParser<'a> {
+    tokens: &'a [SpannedToken],
+    position: usize,
+}
+
+impl<'a> ValueParser<'a> {
+    pub fn new(tokens: &'a [SpannedToken]) -> Self {
+        Self { tokens, position: 0 }
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        while let Some(SpannedToken { token: Token::Whitespace, .. }) = self.tokens.get(self.position) {
+            self.position += 1;
+        }
+    }
+
+    /// Returns the next non-whitespace token without consuming it
+    pub fn peek(&self) -> Option<&'a Token> {
+        self.tokens[self.position..]
+            .iter()
+            .map(|t| &t.token)
+            .find(|t| **t != Token::Whitespace)
+    }
+
+    /// Returns the next non-whitespace token
+    pub fn next(&mut self) -> Option<&'a Token> {
+        self.skip_whitespace();
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        Some(&token.token)
+    }
+
+    /// Returns the next token, including whitespace
+    pub fn next_including_whitespace(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        Some(&token.token)
+    }
+
+    /// Returns the tokens that have not been consumed yet (including whitespace)
+    pub fn remaining_tokens(&self) -> &'a [SpannedToken] {
+        &self.tokens[self.position..]
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.peek().is_none()
+    }
+
+    pub fn expect_exhausted(&self) -> Result<(), ()> {
+        if self.is_exhausted() { Ok(()) } else { Err(()) }
+    }
+
+    /// Runs the parse function and resets the parser to the previous
+    /// position if the function fails
+    pub fn try_parse<T, F: FnOnce(&mut Self) -> Result<T, ()>>(&mut self, f: F) -> Result<T, ()> {
+        let position = self.position;
+        let result = f(self);
+        if result.is_err() {
+            self.position = position;
+        }
+        result
+    }
+
+    pub fn expect_ident(&mut self) -> Result<&'a str, ()> {
+        match self.next() {
+            Some(Token::Ident(i)) => Ok(i),
+            _ => Err(()),
+        }
+    }
+
+    pub fn expect_ident_matching(&mut self, expected: &str) -> Result<(), ()> {
+        match self.next() {
+            Some(Token::Ident(i)) if i.eq_ignore_ascii_case(expected) => Ok(()),
+            _ => Err(()),
+        }
+    }
+
+    pub fn expect_comma(&mut self) -> Result<(), ()> {
+        match self.next() {
+            Some(Token::Comma) => Ok(()),
+            _ => Err(()),
+        }
+    }
+
+    pub fn expect_number(&mut self) -> Result<f32, ()> {
+        match self.next() {
+            Some(Token::Number { value, .. }) => Ok(*value),
+            _ => Err(()),
+        }
+    }
+
+    /// Expects a `name(` function token and returns a parser over the arguments
+    /// of the function, advancing this parser past the closing parenthesis
+    pub fn expect_function(&mut self) -> Result<(&'a str, ValueParser<'a>), ()> {
+        match self.next() {
+            Some(Token::Function(name)) => Ok((name, self.block_contents())),
+            _ => Err(()),
+        }
+    }
+
+    /// Must be called right after a token that opens a block (`(`, `[`, `{` or a function):
+    /// Returns a parser over the tokens of the block and advances past the closing token
+    pub fn block_contents(&mut self) -> ValueParser<'a> {
+        let start = self.position;
+        let end = find_block_end(self.tokens, start);
+        self.position = (end + 1).min(self.tokens.len());
+        ValueParser::new(&self.tokens[start..end])
+    }
+
+    /// Skips one component value (a token or a whole block)
+    pub fn skip_component_value(&mut self) {
+        if let Some(token) = self.next_including_whitespace() {
+            if token.closing_token().is_some() {
+                self.block_contents();
+            }
+        }
+    }
+
+    /// Splits the remaining tokens at every top-level comma and consumes the parser
+    pub fn split_commas(&mut self) -> Vec<ValueParser<'a>> {
+        let mut parts = Vec::new();
+        let mut start = self.position;
+        while self.position < self.tokens.len() {
+            if self.tokens[self.position].token == Token::Comma {
+                parts.push(ValueParser::new(&self.tokens[start..self.position]));
+                self.position += 1;
+                start = self.position;
+            } else {
+                self.skip_component_value();
+            }
+        }
+        parts.push(ValueParser::new(&self.tokens[start..self.position]));
+        parts
+    }
+
+    /// Parses a comma-separated list of items, the list must not be empty
+    pub fn parse_comma_separated<T, F: Fn(&mut ValueParser<'a>) -> Result<T, ()>>(&mut self, f: F) -> Result<Vec<T>, ()> {
+        self.split_commas()
+            .into_iter()
+            .map(|mut part| {
+                let item = f(&mut part)?;
+                part.expect_exhausted()?;
+                Ok(item)
+            })
+            .collect()
+    }
+}
+
+/// Returns the index of the token closing the block that starts at `start`
+/// (or the length of the token list, if the block is never closed)
+pub(crate) fn find_block_end(tokens: &[SpannedToken], start: usize) -> usize {
+    let mut stack = Vec::new();
+    let mut i = start;
+    while i < tokens.len() {
+        let token = &tokens[i].token;
+        match token {
+            Token::CloseParen | Token::CloseSquare | Token::CloseCurly => {
+                match stack.pop() {
+                    Some(expected) if expected == *token => {}
+                    Some(_) => {}
+                    None => return i,
+                }
+            }
+            t => {
+                if let Some(closing) = t.closing_token() {
+                    stack.push(closing);
+                }
+            }
+        }
+        i += 1;
+    }
+    tokens.len()
+}
+
+/// Parses the value of the given CSS key, i.e. `"width"` + `"5px"`
+pub(crate) fn parse_property(key: PType, input: &mut ValueParser) -> Result<Property, ()> {
+    let keywords = value_keywords(key);
+    macro_rules! value {
+        ($variant:ident, $parse_fn:expr) => {
+            parse_value(input, keywords, $parse_fn).map(Property::$variant)
+        };
+    }
+    match key {
+        PType::TextColor => value!(TextColor, |i| parse_color(i).map(StyleTextColor)),
+        PType::FontSize => value!(FontSize, |i| parse_pixel_value(i).map(StyleFontSize)),
+        PType::FontFamily => value!(FontFamily, |i| i.parse_comma_separated(parse_font_family)),
+        PType::TextAlign => value!(TextAlign, parse_text_align),
+        PType::LetterSpacing => value!(LetterSpacing, |i| parse_pixel_value(i).map(StyleLetterSpacing)),
+        PType::LineHeight => value!(LineHeight, |i| parse_percentage_value(i).map(StyleLineHeight)),
+        PType::WordSpacing => value!(WordSpacing, |i| parse_pixel_value(i).map(StyleWordSpacing)),
+        PType::TabWidth => value!(TabWidth, |i| parse_percentage_value(i).map(StyleTabWidth)),
+        PType::Cursor => value!(Cursor, parse_cursor),
+        PType::Display => value!(Display, parse_display),
+        PType::Float => value!(Float, parse_float),
+        PType::BoxSizing => value!(BoxSizing, parse_box_sizing),
+        PType::Width => value!(Width, |i| parse_pixel_value(i).map(LayoutWidth)),
+        PType::Height => value!(Height, |i| parse_pixel_value(i).map(LayoutHeight)),
+        PType::MinWidth => value!(MinWidth, |i| parse_pixel_value(i).map(LayoutMaxWidth)),
+        PType::MinHeight => value!(MinHeight, |i| parse_pixel_value(i).map(LayoutMinHeight)),
+        PType::MaxWidth => value!(MaxWidth, |i| parse_pixel_value(i).map(LayoutMaxWidth)),
+        PType::MaxHeight => value!(MaxHeight, |i| parse_pixel_value(i).map(LayoutMaxHeight)),
+        PType::Position => value!(Position, parse_position),
+        PType::Top => value!(Top, |i| parse_pixel_value(i).map(LayoutTop)),
+        PType::Right => value!(Right, |i| parse_pixel_value(i).map(LayoutRight)),
+        PType::Left => value!(Left, |i| parse_pixel_value(i).map(LayoutLeft)),
+        PType::Bottom => value!(Bottom, |i| parse_pixel_value(i).map(LayoutBottom)),
+        PType::FlexWrap => value!(FlexWrap, parse_flex_wrap),
+        PType::FlexDirection => value!(FlexDirection, parse_flex_direction),
+        PType::FlexGrow => value!(FlexGrow, |i| parse_float_value(i).map(LayoutFlexGrow)),
+        PType::FlexShrink => value!(FlexShrink, |i| parse_float_value(i).map(LayoutFlexShrink)),
+        PType::JustifyContent => value!(JustifyContent, parse_justify_content),
+        PType::AlignItems => value!(AlignItems, parse_align_items),
+        PType::AlignContent => value!(AlignContent, parse_align_content),
+        PType::BackgroundContent => value!(BackgroundContent, |i| i.parse_comma_separated(parse_background_content)),
+        PType::BackgroundPosition => value!(BackgroundPosition, |i| i.parse_comma_separated(parse_background_position)),
+        PType::BackgroundSize => value!(BackgroundSize, |i| i.parse_comma_separated(parse_background_size)),
+        PType::BackgroundRepeat => value!(BackgroundRepeat, |i| i.parse_comma_separated(parse_background_repeat)),
+        PType::OverflowX => value!(OverflowX, parse_overflow),
+        PType::OverflowY => value!(OverflowY, parse_overflow),
+        PType::PaddingTop => value!(PaddingTop, |i| parse_pixel_value(i).map(LayoutPaddingTop)),
+        PType::PaddingLeft => value!(PaddingLeft, |i| parse_pixel_value(i).map(LayoutPaddingLeft)),
+        PType::PaddingRight => value!(PaddingRight, |i| parse_pixel_value(i).map(LayoutPaddingRight)),
+        PType::PaddingBottom => value!(PaddingBottom, |i| parse_pixel_value(i).map(LayoutPaddingBottom)),
+        PType::MarginTop => value!(MarginTop, |i| parse_pixel_value(i).map(LayoutMarginTop)),
+        PType::MarginLeft => value!(MarginLeft, |i| parse_pixel_value(i).map(LayoutMarginLeft)),
+        PType::MarginRight => value!(MarginRight, |i| parse_pixel_value(i).map(LayoutMarginRight)),
+        PType::MarginBottom => value!(MarginBottom, |i| parse_pixel_value(i).map(LayoutMarginBottom)),
+        PType::BorderTopLeftRadius => value!(BorderTopLeftRadius, |i| parse_pixel_value(i).map(StyleBorderTopLeftRadius)),
+        PType::BorderTopRightRadius => value!(BorderTopRightRadius, |i| parse_pixel_value(i).map(StyleBorderTopRightRadius)),
+        PType::BorderBottomLeftRadius => value!(BorderBottomLeftRadius, |i| parse_pixel_value(i).map(StyleBorderBottomLeftRadius)),
+        PType::BorderBottomRightRadius => value!(BorderBottomRightRadius, |i| parse_pixel_value(i).map(StyleBorderBottomRightRadius)),
+        PType::BorderTopColor => value!(BorderTopColor, |i| parse_color(i).map(StyleBorderTopColor)),
+        PType::BorderRightColor => value!(BorderRightColor, |i| parse_color(i).map(StyleBorderRightColor)),
+        PType::BorderLeftColor => value!(BorderLeftColor, |i| parse_color(i).map(StyleBorderLeftColor)),
+        PType::BorderBottomColor => value!(BorderBottomColor, |i| parse_color(i).map(StyleBorderBottomColor)),
+        PType::BorderTopStyle => value!(BorderTopStyle, |i| parse_border_style(i).map(StyleBorderTopStyle)),
+        PType::BorderRightStyle => value!(BorderRightStyle, |i| parse_border_style(i).map(StyleBorderRightStyle)),
+        PType::BorderLeftStyle => value!(BorderLeftStyle, |i| parse_border_style(i).map(StyleBorderLeftStyle)),
+        PType::BorderBottomStyle => value!(BorderBottomStyle, |i| parse_border_style(i).map(StyleBorderBottomStyle)),
+        PType::BorderTopWidth => value!(BorderTopWidth, |i| parse_border_width(i).map(LayoutBorderTopWidth)),
+        PType::BorderRightWidth => value!(BorderRightWidth, |i| parse_border_width(i).map(LayoutBorderRightWidth)),
+        PType::BorderLeftWidth => value!(BorderLeftWidth, |i| parse_border_width(i).map(LayoutBorderLeftWidth)),
+        PType::BorderBottomWidth => value!(BorderBottomWidth, |i| parse_border_width(i).map(LayoutBorderBottomWidth)),
+        PType::BoxShadowLeft => value!(BoxShadowLeft, parse_box_shadow),
+        PType::BoxShadowRight => value!(BoxShadowRight, parse_box_shadow),
+        PType::BoxShadowTop => value!(BoxShadowTop, parse_box_shadow),
+        PType::BoxShadowBottom => value!(BoxShadowBottom, parse_box_shadow),
+        // scrollbar styles can only be constructed from Rust code, only the keywords are parsed
+        PType::ScrollbarStyle => value!(ScrollbarStyle, |_| Err(())),
+        PType::Opacity => value!(Opacity, parse_opacity),
+        PType::Transform => value!(Transform, parse_transforms),
+        PType::TransformOrigin => value!(TransformOrigin, |i| parse_origin(i).map(|(x, y)| StyleTransformOrigin { x, y })),
+        PType::PerspectiveOrigin => value!(PerspectiveOrigin, |i| parse_origin(i).map(|(x, y)| StylePerspectiveOrigin { x, y })),
+        PType::BackfaceVisibility => value!(BackfaceVisibility, parse_backface_visibility),
+        PType::MixBlendMode => value!(MixBlendMode, parse_mix_blend_mode),
+        PType::Filter => value!(Filter, parse_filters),
+        PType::BackdropFilter => value!(BackdropFilter, parse_filters),
+        PType::TextShadow => value!(TextShadow, parse_box_shadow),
+        PType::Content => match input.try_parse(|i| i.expect_ident_matching("normal").and_then(|_| i.expect_exhausted())) {
+            // `normal` computes to `none` for `::before` and `::after`
+            Ok(()) => Ok(Property::Content(Value::None)),
+            Err(()) => value!(Content, parse_contents),
+        },
+        PType::AnimationName => value!(AnimationName, |i| i.parse_comma_separated(parse_animation_name)),
+        PType::AnimationDuration => value!(AnimationDuration, |i| i.parse_comma_separated(parse_duration)),
+        PType::AnimationTimingFunction => value!(AnimationTimingFunction, |i| i.parse_comma_separated(parse_timing_function)),
+        PType::AnimationDelay => value!(AnimationDelay, |i| i.parse_comma_separated(parse_time)),
+        PType::AnimationIterationCount => value!(AnimationIterationCount, |i| i.parse_comma_separated(parse_iteration_count)),
+        PType::AnimationDirection => value!(AnimationDirection, |i| i.parse_comma_separated(parse_animation_direction)),
+        PType::AnimationFillMode => value!(AnimationFillMode, |i| i.parse_comma_separated(parse_animation_fill_mode)),
+        PType::AnimationPlayState => value!(AnimationPlayState, |i| i.parse_comma_separated(parse_animation_play_state)),
+        PType::TransitionProperty => value!(TransitionProperty, |i| i.parse_comma_separated(parse_transition_property)),
+        PType::TransitionDuration => value!(TransitionDuration, |i| i.parse_comma_separated(parse_duration)),
+        PType::TransitionTimingFunction => value!(TransitionTimingFunction, |i| i.parse_comma_separated(parse_timing_function)),
+        PType::TransitionDelay => value!(TransitionDelay, |i| i.parse_comma_separated(parse_time)),
+    }
+}
+
+/// Which of the `auto` / `none` keywords the grammar of a property allows, if they
+/// are not parsed by the parse function of the property itself
+fn value_keywords(key: PType) -> ValueKeywords {
+    use self::PType::*;
+    match key {
+        Width | Height | MinWidth | MinHeight | Top | Right | Left | Bottom | MarginTop | MarginLeft | MarginRight
+        | MarginBottom | BackgroundSize => ValueKeywords::Auto,
+        Cursor => ValueKeywords::AutoOrNone,
+        Float | MaxWidth | MaxHeight | BackgroundContent | BoxShadowLeft | BoxShadowRight | BoxShadowTop | BoxShadowBottom
+        | Transform | Filter | BackdropFilter | TextShadow | Content | AnimationName | TransitionProperty => ValueKeywords::None,
+        _ => ValueKeywords::Neither,
+    }
+}
+
+/// The keywords besides `initial` / `inherit` that `parse_value` accepts
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum ValueKeywords {
+    Neither,
+    Auto,
+    None,
+    AutoOrNone,
+}
+
+impl ValueKeywords {
+    fn allows_auto(self) -> bool {
+        matches!(self, ValueKeywords::Auto | ValueKeywords::AutoOrNone)
+    }
+
+    fn allows_none(self) -> bool {
+        matches!(self, ValueKeywords::None | ValueKeywords::AutoOrNone)
+    }
+}
+
+/// Parses a value that can also be one of the CSS-wide keywords
+/// (`initial`, `inherit`) or, if the property allows them, `auto` / `none`
+pub(crate) fn parse_value<'a, T, F>(input: &mut ValueParser<'a>, keywords: ValueKeywords, parse_fn: F) -> Result<Value<T>, ()>
+where
+    F: FnOnce(&mut ValueParser<'a>) -> Result<T, ()>,
+{
+    let keyword = {
+        let mut lookahead = input.clone();
+        lookahead.expect_ident().and_then(|ident| lookahead.expect_exhausted().map(|_| ident))
+    };
+
+    if let Ok(k) = keyword {
+        if k.eq_ignore_ascii_case("initial") {
+            return Ok(Value::Initial);
+        } else if k.eq_ignore_ascii_case("inherit") {
+            return Ok(Value::Inherit);
+        }
+    }
+
+    match input.try_parse(|i| {
+        let value = parse_fn(i)?;
+        i.expect_exhausted()?;
+        Ok(value)
+    }) {
+        Ok(value) => Ok(Value::Exact(value)),
+        Err(()) => match keyword {
+            Ok(k) if keywords.allows_auto() && k.eq_ignore_ascii_case("auto") => Ok(Value::Auto),
+            Ok(k) if keywords.allows_none() && k.eq_ignore_ascii_case("none") => Ok(Value::None),
+            _ => Err(()),
+        },
+    }
+}
+
+/// Parses `initial` or `inherit`, if it is the only token of the input
+fn parse_css_wide_keyword<T>(input: &mut ValueParser) -> Option<Value<T>> {
+    input
+        .try_parse(|i| {
+            let keyword = i.expect_ident()?;
+            i.expect_exhausted()?;
+            if keyword.eq_ignore_ascii_case("initial") {
+                Ok(Value::Initial)
+            } else if keyword.eq_ignore_ascii_case("inherit") {
+                Ok(Value::Inherit)
+            } else {
+                Err(())
+            }
+        })
+        .ok()
+}
+
+/// Parses the value of a shorthand property into its longhand properties,
+/// i.e. `margin: 5px 10px` => `MarginTop(5px)`, `MarginRight(10px)`, `MarginBottom(5px)`, `MarginLeft(10px)`
+pub(crate) fn parse_shorthand(shorthand: CombinedPropertyType, input: &mut ValueParser) -> Result<Vec<Property>, ()> {
+    use self::CombinedPropertyType::*;
+
+    let properties = match shorthand {
+        Margin => {
+            let [top, right, bottom, left] = parse_sides(input, |i| {
+                match i.try_parse(|i| i.expect_ident_matching("auto")) {
+                    Ok(()) => Ok(Value::Auto),
+                    Err(()) => parse_pixel_value(i).map(Value::Exact),
+                }
+            })?;
+            vec![
+                Property::MarginTop(top.map_property(LayoutMarginTop)),
+                Property::MarginRight(right.map_property(LayoutMarginRight)),
+                Property::MarginBottom(bottom.map_property(LayoutMarginBottom)),
+                Property::MarginLeft(left.map_property(LayoutMarginLeft)),
+            ]
+        }
+        Padding => {
+            let [top, right, bottom, left] = parse_sides(input, |i| parse_pixel_value(i).map(Value::Exact))?;
+            vec![
+                Property::PaddingTop(top.map_property(LayoutPaddingTop)),
+                Property::PaddingRight(right.map_property(LayoutPaddingRight)),
+                Property::PaddingBottom(bottom.map_property(LayoutPaddingBottom)),
+                Property::PaddingLeft(left.map_property(LayoutPaddingLeft)),
+            ]
+        }
+        BorderRadius => {
+            // the corners are listed clockwise, starting at the top left corner
+            let [top_left, top_right, bottom_right, bottom_left] =
+                parse_sides(input, |i| parse_pixel_value(i).map(Value::Exact))?;
+            vec![
+                Property::BorderTopLeftRadius(top_left.map_property(StyleBorderTopLeftRadius)),
+                Property::BorderTopRightRadius(top_right.map_property(StyleBorderTopRightRadius)),
+                Property::BorderBottomRightRadius(bottom_right.map_property(StyleBorderBottomRightRadius)),
+                Property::BorderBottomLeftRadius(bottom_left.map_property(StyleBorderBottomLeftRadius)),
+            ]
+        }
+        Overflow => {
+            let (x, y) = match parse_css_wide_keyword(input) {
+                Some(keyword) => (keyword, keyword),
+                None => {
+                    let x = parse_overflow(input)?;
+                    let y = input.try_parse(parse_overflow).unwrap_or(x);
+                    (Value::Exact(x), Value::Exact(y))
+                }
+            };
+            vec![Property::OverflowX(x), Property::OverflowY(y)]
+        }
+        Border => {
+            let (width, style, color) = parse_border_side(input)?;
+            let mut properties = Vec::with_capacity(12);
+            properties.extend(border_top(width, style, color));
+            properties.extend(border_right(width, style, color));
+            properties.extend(border_bottom(width, style, color));
+            properties.extend(border_left(width, style, color));
+            properties
+        }
+        BorderTop => {
+            let (width, style, color) = parse_border_side(input)?;
+            border_top(width, style, color).to_vec()
+        }
+        BorderRight => {
+            let (width, style, color) = parse_border_side(input)?;
+            border_right(width, style, color).to_vec()
+        }
+        BorderBottom => {
+            let (width, style, color) = parse_border_side(input)?;
+            border_bottom(width, style, color).to_vec()
+        }
+        BorderLeft => {
+            let (width, style, color) = parse_border_side(input)?;
+            border_left(width, style, color).to_vec()
+        }
+        BoxShadow => {
+            let shadow = parse_value(input, ValueKeywords::None, parse_box_shadow)?;
+            vec![
+                Property::BoxShadowTop(shadow),
+                Property::BoxShadowRight(shadow),
+                Property::BoxShadowBottom(shadow),
+                Property::BoxShadowLeft(shadow),
+            ]
+        }
+        BackgroundColor => {
+            let color = parse_value(input, ValueKeywords::Neither, parse_color)?;
+            vec![Property::BackgroundContent(color.map_property(|c| vec![StyleBackgroundContent::Color(c)]))]
+        }
+        BackgroundImage => {
+            let images = parse_value(input, ValueKeywords::None, |i| {
+                i.parse_comma_separated(|i| match parse_background_content(i)? {
+                    StyleBackgroundContent::Color(_) => Err(()),
+                    image => Ok(image),
+                })
+            })?;
+            vec![Property::BackgroundContent(images)]
+        }
+        Animation => parse_animations(input)?,
+    };
+
+    input.expect_exhausted()?;
+    Ok(properties)
+}
+
+/// Parses the comma-separated list of the `animation` shorthand into the eight `animation-*` longhands
+fn parse_animations(input: &mut ValueParser) -> Result<Vec<Property>, ()> {
+    if let Some(keyword) = parse_css_wide_keyword::<()>(input) {
+        // `keyword` is `initial` or `inherit`, so the map function is never called
+        return Ok(vec![
+            Property::AnimationName(keyword.map_property(|_| unreachable!())),
+            Property::AnimationDuration(keyword.map_property(|_| unreachable!())),
+            Property::AnimationTimingFunction(keyword.map_property(|_| unreachable!())),
+            Property::AnimationDelay(keyword.map_property(|_| unreachable!())),
+            Property::AnimationIterationCount(keyword.map_property(|_| unreachable!())),
+            Property::AnimationDirection(keyword.map_property(|_| unreachable!())),
+            Property::AnimationFillMode(keyword.map_property(|_| unreachable!())),
+            Property::AnimationPlayState(keyword.map_property(|_| unreachable!())),
+        ]);
+    }
+
+    let animations = input.parse_comma_separated(parse_single_animation)?;
+    let names = animations.iter().map(|a| a.0.clone()).collect::<Option<Vec<_>>>();
+    let names = match names {
+        Some(names) => Value::Exact(names),
+        // `none` can only be used on its own
+        None if animations.len() == 1 => Value::None,
+        None => return Err(()),
+    };
+    Ok(vec![
+        Property::AnimationName(names),
+        Property::AnimationDuration(Value::Exact(animations.iter().map(|a| a.1).collect())),
+        Property::AnimationTimingFunction(Value::Exact(animations.iter().map(|a| a.2.clone()).collect())),
+        Property::AnimationDelay(Value::Exact(animations.iter().map(|a| a.3).collect())),
+        Property::AnimationIterationCount(Value::Exact(animations.iter().map(|a| a.4).collect())),
+        Property::AnimationDirection(Value::Exact(animations.iter().map(|a| a.5).collect())),
+        Property::AnimationFillMode(Value::Exact(animations.iter().map(|a| a.6).collect())),
+        Property::AnimationPlayState(Value::Exact(animations.iter().map(|a| a.7).collect())),
+    ])
+}
+
+/// Name, duration, timing function, delay, iteration count, direction, fill mode and play state
+/// of one animation in the `animation` shorthand, the name is `None` for `none`
+type SingleAnimation = (
+    Option<StyleAnimationName>,
+    StyleDuration,
+    TimingFunction,
+    StyleDuration,
+    StyleIterationCount,
+    StyleAnimationDirection,
+    StyleAnimationFillMode,
+    StyleAnimationPlayState,
+);
+
+/// Parses one animation of the `animation` shorthand, i.e. `slide-in 300ms ease-out 1s infinite`.
+/// The components can be in any order, but the first time is the duration and the second the delay.
+/// Omitted components are reset to their initial value.
+fn parse_single_animation(input: &mut ValueParser) -> Result<SingleAnimation, ()> {
+    let mut name = None;
+    let mut duration = None;
+    let mut timing_function = None;
+    let mut delay = None;
+    let mut iteration_count = None;
+    let mut direction = None;
+    let mut fill_mode = None;
+    let mut play_state = None;
+
+    /// Tries to parse the component if it hasn't been set yet
+    macro_rules! component {
+        ($value:ident, $parse_fn:expr) => {
+            if $value.is_none() {
+                if let Ok(v) = input.try_parse($parse_fn) {
+                    $value = Some(v);
+                    continue;
+                }
+            }
+        };
+    }
+
+    while !input.is_exhausted() {
+        if duration.is_none() {
+            if let Ok(d) = input.try_parse(parse_duration) {
+                duration = Some(d);
+                continue;
+            }
+        } else {
+            component!(delay, parse_time);
+        }
+        component!(timing_function, parse_timing_function);
+        component!(iteration_count, parse_iteration_count);
+        component!(direction, parse_animation_direction);
+        component!(fill_mode, parse_animation_fill_mode);
+        component!(play_state, parse_animation_play_state);
+        if name.is_none() {
+            // keywords of the other components are only names if that component is already set
+            if input.try_parse(|i| i.expect_ident_matching("none")).is_ok() {
+                name = Some(None);
+                continue;
+            }
+            if let Ok(n) = input.try_parse(parse_animation_name) {
+                name = Some(Some(n));
+                continue;
+            }
+        }
+        return Err(());
+    }
+
+    Ok((
+        name.unwrap_or(None),
+        duration.unwrap_or_default(),
+        timing_function.unwrap_or_default(),
+        delay.unwrap_or_default(),
+        iteration_count.unwrap_or_default(),
+        direction.unwrap_or_default(),
+        fill_mode.unwrap_or_default(),
+        play_state.unwrap_or_default(),
+    ))
+}
+
+/// Parses one to four values and assigns them to the four sides (or corners) following the
+/// CSS rules: one value applies to all sides, two values to top / bottom and left / right,
+/// three values to top, left / right and bottom. Returns the values in the order top, right, bottom, left.
+fn parse_sides<'a, T: Copy, F>(input: &mut ValueParser<'a>, parse_fn: F) -> Result<[Value<T>; 4], ()>
+where
+    F: Fn(&mut ValueParser<'a>) -> Result<Value<T>, ()>,
+{
+    if let Some(keyword) = parse_css_wide_keyword(input) {
+        return Ok([keyword; 4]);
+    }
+
+    let mut values = Vec::with_capacity(4);
+    while !input.is_exhausted() {
+        if values.len() == 4 {
+            return Err(());
+        }
+        values.push(parse_fn(input)?);
+    }
+
+    match values.as_slice() {
+        [all] => Ok([*all; 4]),
+        [vertical, horizontal] => Ok([*vertical, *horizontal, *vertical, *horizontal]),
+        [top, horizontal, bottom] => Ok([*top, *horizontal, *bottom, *horizontal]),
+        [top, right, bottom, left] => Ok([*top, *right, *bottom, *left]),
+        _ => Err(()),
+    }
+}
+
+/// Width, style and color of one side of a border
+type BorderSide = (Value<PixelValue>, Value<BorderStyle>, Value<ColorU>);
+
+/// Parses the `<width> || <style> || <color>` value of the `border` shorthands.
+/// Omitted components are reset to their initial value.
+fn parse_border_side(input: &mut ValueParser) -> Result<BorderSide, ()> {
+    if let Some(keyword) = parse_css_wide_keyword::<()>(input) {
+        return Ok(match keyword {
+            Value::Inherit => (Value::Inherit, Value::Inherit, Value::Inherit),
+            _ => (Value::Initial, Value::Initial, Value::Initial),
+        });
+    }
+
+    let mut width = None;
+    let mut style = None;
+    let mut color = None;
+    while !input.is_exhausted() {
+        if width.is_none() {
+            if let Ok(w) = input.try_parse(parse_border_width) {
+                width = Some(w);
+                continue;
+            }
+        }
+        if style.is_none() {
+            if let Ok(s) = input.try_parse(parse_border_style) {
+                style = Some(s);
+                continue;
+            }
+        }
+        if color.is_none() {
+            if let Ok(c) = input.try_parse(parse_color) {
+                color = Some(c);
+                continue;
+            }
+        }
+        return Err(());
+    }
+
+    if width.is_none() && style.is_none() && color.is_none() {
+        return Err(());
+    }
+
+    Ok((
+        width.map_or(Value::Initial, Value::Exact),
+        style.map_or(Value::Initial, Value::Exact),
+        color.map_or(Value::Initial, Value::Exact),
+    ))
+}
+
+macro_rules! border_side {
+    ($fn_name:ident, $width:ident($width_ty:ident), $style:ident($style_ty:ident), $color:ident($color_ty:ident)) => {
+        /// Returns the width, style and color longhands of one side of the border
+        fn $fn_name(width: Value<PixelValue>, style: Value<BorderStyle>, color: Value<ColorU>) -> [Property; 3] {
+            [
+                Property::$width(width.map_property($width_ty)),
+                Property::$style(style.map_property($style_ty)),
+                Property::$color(color.map_property($color_ty)),
+            ]
+        }
+    };
+}
+
+border_side!(border_top, BorderTopWidth(LayoutBorderTopWidth), BorderTopStyle(StyleBorderTopStyle), BorderTopColor(StyleBorderTopColor));
+border_side!(border_right, BorderRightWidth(LayoutBorderRightWidth), BorderRightStyle(StyleBorderRightStyle), BorderRightColor(StyleBorderRightColor));
+border_side!(border_bottom, BorderBottomWidth(LayoutBorderBottomWidth), BorderBottomStyle(StyleBorderBottomStyle), BorderBottomColor(StyleBorderBottomColor));
+border_side!(border_left, BorderLeftWidth(LayoutBorderLeftWidth), BorderLeftStyle(StyleBorderLeftStyle), BorderLeftColor(StyleBorderLeftColor));
+
+macro_rules! keyword_parser {
+    ($fn_name:ident, $ty:ident, { $($keyword:expr => $variant:ident,)+ }) => {
+        pub(crate) fn $fn_name(input: &mut ValueParser) -> Result<$ty, ()> {
+            let ident = input.expect_ident()?;
+            $(
+                if ident.eq_ignore_ascii_case($keyword) {
+                    return Ok($ty::$variant);
+                }
+            )+
+            Err(())
+        }
+    };
+}
+
+keyword_parser!(parse_text_align, StyleTextAlign, {
+    "left" => Left,
+    "center" => Center,
+    "right" => Right,
+});
+
+keyword_parser!(parse_cursor, StyleCursor, {
+    "alias" => Alias,
+    "all-scroll" => AllScroll,
+    "cell" => Cell,
+    "col-resize" => ColResize,
+    "context-menu" => ContextMenu,
+    "copy" => Copy,
+    "crosshair" => Crosshair,
+    "default" => Default,
+    "e-resize" => EResize,
+    "ew-resize" => EwResize,
+    "grab" => Grab,
+    "grabbing" => Grabbing,
+    "help" => Help,
+    "move" => Move,
+    "n-resize" => NResize,
+    "ns-resize" => NsResize,
+    "nesw-resize" => NeswResize,
+    "nwse-resize" => NwseResize,
+    "pointer" => Pointer,
+    "progress" => Progress,
+    "row-resize" => RowResize,
+    "s-resize" => SResize,
+    "se-resize" => SeResize,
+    "text" => Text,
+    "unset" => Unset,
+    "vertical-text" => VerticalText,
+    "w-resize" => WResize,
+    "wait" => Wait,
+    "zoom-in" => ZoomIn,
+    "zoom-out" => ZoomOut,
+});
+
+keyword_parser!(parse_display, LayoutDisplay, {
+    "none" => None,
+    "flex" => Flex,
+    "block" => Block,
+    "inline-block" => InlineBlock,
+});
+
+keyword_parser!(parse_float, LayoutFloat, {
+    "left" => Left,
+    "right" => Right,
+});
+
+keyword_parser!(parse_box_sizing, LayoutBoxSizing, {
+    "content-box" => ContentBox,
+    "border-box" => BorderBox,
+});
+
+keyword_parser!(parse_position, LayoutPosition, {
+    "static" => Static,
+    "relative" => Relative,
+    "absolute" => Absolute,
+    "fixed" => Fixed,
+});
+
+keyword_parser!(parse_flex_wrap, LayoutFlexWrap, {
+    "wrap" => Wrap,
+    "nowrap" => NoWrap,
+});
+
+keyword_parser!(parse_flex_direction, LayoutFlexDirection, {
+    "row" => Row,
+    "row-reverse" => RowReverse,
+    "column" => Column,
+    "column-reverse" => ColumnReverse,
+});
+
+keyword_parser!(parse_justify_content, LayoutJustifyContent, {
+    "flex-start" => Start,
+    "start" => Start,
+    "flex-end" => End,
+    "end" => End,
+    "center" => Center,
+    "space-between" => SpaceBetween,
+    "space-around" => SpaceAround,
+    "space-evenly" => SpaceEvenly,
+});
+
+keyword_parser!(parse_align_items, LayoutAlignItems, {
+    "stretch" => Stretch,
+    "center" => Center,
+    "flex-start" => FlexStart,
+    "start" => FlexStart,
+    "flex-end" => FlexEnd,
+    "end" => FlexEnd,
+});
+
+keyword_parser!(parse_align_content, LayoutAlignContent, {
+    "stretch" => Stretch,
+    "center" => Center,
+    "flex-start" => Start,
+    "start" => Start,
+    "flex-end" => End,
+    "end" => End,
+    "space-between" => SpaceBetween,
+    "space-around" => SpaceAround,
+});
+
+keyword_parser!(parse_background_repeat, StyleBackgroundRepeat, {
+    "no-repeat" => NoRepeat,
+    "repeat" => Repeat,
+    "repeat-x" => RepeatX,
+    "repeat-y" => RepeatY,
+});
+
+keyword_parser!(parse_overflow, LayoutOverflow, {
+    "scroll" => Scroll,
+    "auto" => Auto,
+    "hidden" => Hidden,
+    "visible" => Visible,
+});
+
+keyword_parser!(parse_border_style, BorderStyle, {
+    "none" => None,
+    "solid" => Solid,
+    "double" => Double,
+    "dotted" => Dotted,
+    "dashed" => Dashed,
+    "hidden" => Hidden,
+    "groove" => Groove,
+    "ridge" => Ridge,
+    "inset" => Inset,
+    "outset" => Outset,
+});
+
+keyword_parser!(parse_backface_visibility, StyleBackfaceVisibility, {
+    "hidden" => Hidden,
+    "visible" => Visible,
+});
+
+keyword_parser!(parse_mix_blend_mode, StyleMixBlendMode, {
+    "normal" => Normal,
+    "multiply" => Multiply,
+    "screen" => Screen,
+    "overlay" => Overlay,
+    "darken" => Darken,
+    "lighten" => Lighten,
+    "color-dodge" => ColorDodge,
+    "color-burn" => ColorBurn,
+    "hard-light" => HardLight,
+    "soft-light" => SoftLight,
+    "difference" => Difference,
+    "exclusion" => Exclusion,
+    "hue" => Hue,
+    "saturation" => Saturation,
+    "color" => Color,
+    "luminosity" => Luminosity,
+});
+
+keyword_parser!(parse_radial_gradient_size, RadialGradientSize, {
+    "closest-side" => ClosestSide,
+    "closest-corner" => ClosestCorner,
+    "farthest-side" => FarthestSide,
+    "farthest-corner" => FarthestCorner,
+});
+
+keyword_parser!(parse_shape, Shape, {
+    "ellipse" => Ellipse,
+    "circle" => Circle,
+});
+
+/// Parses a length such as `5px`, `1.5em`, `10pt` or `50%`
+/// (a unitless `0` is accepted as `0px`)
+pub(crate) fn parse_pixel_value(input: &mut ValueParser) -> Result<PixelValue, ()> {
+    match input.next() {
+        Some(Token::Dimension { value, unit, .. }) => {
+            let metric = match unit.to_ascii_lowercase().as_str() {
+                "px" => SizeMetric::Px,
+                "pt" => SizeMetric::Pt,
+                "em" | "rem" => SizeMetric::Em,
+                _ => return Err(()),
+            };
+            Ok(PixelValue::from_metric(metric, *value))
+        }
+        Some(Token::Percentage { value, .. }) => Ok(PixelValue::percent(*value)),
+        Some(Token::Number { value, .. }) if *value == 0.0 => Ok(PixelValue::zero()),
+        _ => Err(()),
+    }
+}
+
+/// Same as `parse_pixel_value`, but unitless numbers are interpreted as pixels
+/// (used for the values of transformation matrices)
+fn parse_pixel_value_or_number(input: &mut ValueParser) -> Result<PixelValue, ()> {
+    input
+        .try_parse(|i| i.expect_number().map(PixelValue::px))
+        .or_else(|_| parse_pixel_value(input))
+}
+
+/// Parses `border-width` values, including the `thin`, `medium` and `thick` keywords
+fn parse_border_width(input: &mut ValueParser) -> Result<PixelValue, ()> {
+    if let Ok(ident) = input.try_parse(|i| i.expect_ident()) {
+        return match ident.to_ascii_lowercase().as_str() {
+            "thin" => Ok(PixelValue::const_px(1)),
+            "medium" => Ok(PixelValue::const_px(3)),
+            "thick" => Ok(PixelValue::const_px(5)),
+            _ => Err(()),
+        };
+    }
+    parse_pixel_value(input)
+}
+
+/// Parses a percentage such as `50%`. Plain numbers are interpreted
+/// as fractions, i.e. `0.5` is parsed as `50%`.
+pub(crate) fn parse_percentage_value(input: &mut ValueParser) -> Result<PercentageValue, ()> {
+    match input.next() {
+        Some(Token::Percentage { value, .. }) => Ok(PercentageValue::new(*value)),
+        Some(Token::Number { value, .. }) => Ok(PercentageValue::new(*value * 100.0)),
+        _ => Err(()),
+    }
+}
+
+pub(crate) fn parse_float_value(input: &mut ValueParser) -> Result<FloatValue, ()> {
+    input.expect_number().map(FloatValue::new)
+}
+
+/// Parses an angle such as `45deg`, `1.5rad`, `100grad`, `0.25turn` or `50%`
+pub(crate) fn parse_angle_value(input: &mut ValueParser) -> Result<AngleValue, ()> {
+    match input.next() {
+        Some(Token::Dimension { value, unit, .. }) => {
+            let metric = match unit.to_ascii_lowercase().as_str() {
+                "deg" => AngleMetric::Degree,
+                "rad" => AngleMetric::Radians,
+                "grad" => AngleMetric::Grad,
+                "turn" => AngleMetric::Turn,
+                _ => return Err(()),
+            };
+            Ok(AngleValue::from_metric(metric, *value))
+        }
+        Some(Token::Percentage { value, .. }) => Ok(AngleValue::percent(*value)),
+        Some(Token::Number { value, .. }) if *value == 0.0 => Ok(AngleValue::zero()),
+        _ => Err(()),
+    }
+}
+
+fn parse_opacity(input: &mut ValueParser) -> Result<StyleOpacity, ()> {
+    let value = parse_percentage_value(input)?;
+    if value.normalized() < 0.0 || value.normalized() > 1.0 {
+        return Err(());
+    }
+    Ok(StyleOpacity(value))
+}
+
+fn parse_font_family(input: &mut ValueParser) -> Result<StyleFontFamily, ()> {
+    match input.next() {
+        Some(Token::QuotedString(s)) => Ok(StyleFontFamily::System(s.clone())),
+        Some(Token::Url(u)) => Ok(StyleFontFamily::File(u.clone())),
+        Some(Token::Function(f)) if f.eq_ignore_ascii_case("url") => {
+            let mut args = input.block_contents();
+            match args.next() {
+                Some(Token::QuotedString(s)) => {
+                    args.expect_exhausted()?;
+                    Ok(StyleFontFamily::File(s.clone()))
+                }
+                _ => Err(()),
+            }
+        }
+        Some(Token::Ident(first)) => {
+            // unquoted font names consist of multiple identifiers: `font-family: Times New Roman`
+            let mut name = first.clone();
+            while let Ok(ident) = input.try_parse(|i| i.expect_ident()) {
+                name.push(' ');
+                name.push_str(ident);
+            }
+            Ok(StyleFontFamily::System(name))
+        }
+        _ => Err(()),
+    }
+}
+
+/// Parses the space-separated items of the `content` property
+fn parse_contents(input: &mut ValueParser) -> Result<Vec<StyleContent>, ()> {
+    let mut contents = Vec::new();
+    while !input.is_exhausted() {
+        contents.push(parse_content(input)?);
+    }
+    if contents.is_empty() { Err(()) } else { Ok(contents) }
+}
+
+fn parse_content(input: &mut ValueParser) -> Result<StyleContent, ()> {
+    // parses the optional `, style` at the end of `counter()` and `counters()`
+    fn parse_counter_style(args: &mut ValueParser) -> Result<Option<String>, ()> {
+        match args.next() {
+            None => Ok(None),
+            Some(Token::Comma) => {
+                let style = args.expect_ident()?.to_string();
+                args.expect_exhausted()?;
+                Ok(Some(style))
+            }
+            Some(_) => Err(()),
+        }
+    }
+
+    match input.next() {
+        Some(Token::QuotedString(s)) => Ok(StyleContent::String(s.clone())),
+        Some(Token::Ident(i)) => match i.to_ascii_lowercase().as_str() {
+            "open-quote" => Ok(StyleContent::OpenQuote),
+            "close-quote" => Ok(StyleContent::CloseQuote),
+            "no-open-quote" => Ok(StyleContent::NoOpenQuote),
+            "no-close-quote" => Ok(StyleContent::NoCloseQuote),
+            _ => Err(()),
+        },
+        Some(Token::Function(f)) => {
+            let function = f.to_ascii_lowercase();
+            let mut args = input.block_contents();
+            let name = args.expect_ident()?.to_string();
+            match function.as_str() {
+                "counter" => Ok(StyleContent::Counter { name, style: parse_counter_style(&mut args)? }),
+                "counters" => {
+                    let separator = match (args.next(), args.next()) {
+                        (Some(Token::Comma), Some(Token::QuotedString(s))) => s.clone(),
+                        _ => return Err(()),
+                    };
+                    Ok(StyleContent::Counters { name, separator, style: parse_counter_style(&mut args)? })
+                }
+                "attr" => {
+                    args.expect_exhausted()?;
+                    Ok(StyleContent::Attr(name))
+                }
+                _ => Err(()),
+            }
+        }
+        _ => Err(()),
+    }
+}
+
+/// Parses the name of a `@keyframes` rule, an identifier (except `none`) or a string
+pub(crate) fn parse_animation_name(input: &mut ValueParser) -> Result<StyleAnimationName, ()> {
+    match input.next() {
+        Some(Token::Ident(name)) => match name.to_ascii_lowercase().as_str() {
+            "none" | "initial" | "inherit" | "auto" => Err(()),
+            _ => Ok(StyleAnimationName(name.to_string())),
+        },
+        Some(Token::QuotedString(name)) => Ok(StyleAnimationName(name.clone())),
+        _ => Err(()),
+    }
+}
+
+/// Parses a time such as `300ms` or `1.5s`, negative times are allowed
+fn parse_time(input: &mut ValueParser) -> Result<StyleDuration, ()> {
+    match input.next() {
+        Some(Token::Dimension { value, unit, .. }) => match unit.to_ascii_lowercase().as_str() {
+            "ms" => Ok(StyleDuration::ms(*value)),
+            "s" => Ok(StyleDuration::ms(*value * 1000.0)),
+            _ => Err(()),
+        },
+        _ => Err(()),
+    }
+}
+
+/// Same as `parse_time`, but negative times are not allowed
+fn parse_duration(input: &mut ValueParser) -> Result<StyleDuration, ()> {
+    match parse_time(input)? {
+        d if d.get_ms() < 0.0 => Err(()),
+        d => Ok(d),
+    }
+}
+
+/// Parses an easing function: `ease`, `linear`, `ease-in`, `ease-out`, `ease-in-out`, `step-start`,
+/// `step-end`, `cubic-bezier(x1, y1, x2, y2)`, `steps(n, position)` or `linear(stops)`
+pub(crate) fn parse_timing_function(input: &mut ValueParser) -> Result<TimingFunction, ()> {
+    if let Some(Token::Ident(keyword)) = input.peek() {
+        input.next();
+        return match keyword.to_ascii_lowercase().as_str() {
+            "ease" => Ok(TimingFunction::Ease),
+            "linear" => Ok(TimingFunction::Linear),
+            "ease-in" => Ok(TimingFunction::EaseIn),
+            "ease-out" => Ok(TimingFunction::EaseOut),
+            "ease-in-out" => Ok(TimingFunction::EaseInOut),
+            "step-start" => Ok(TimingFunction::Steps(1, StepPosition::JumpStart)),
+            "step-end" => Ok(TimingFunction::Steps(1, StepPosition::JumpEnd)),
+            _ => Err(()),
+        };
+    }
+
+    let (name, mut args) = input.expect_function()?;
+    match name.to_ascii_lowercase().as_str() {
+        "cubic-bezier" => match args.parse_comma_separated(|i| i.expect_number())?[..] {
+            [x1, y1, x2, y2] if (0.0..=1.0).contains(&x1) && (0.0..=1.0).contains(&x2) => Ok(TimingFunction::CubicBezier(
+                FloatValue::new(x1),
+                FloatValue::new(y1),
+                FloatValue::new(x2),
+                FloatValue::new(y2),
+            )),
+            _ => Err(()),
+        },
+        "steps" => {
+            let steps = match args.next() {
+                Some(Token::Number { value, is_integer: true, .. }) if *value >= 1.0 => *value as u32,
+                _ => return Err(()),
+            };
+            let position = if args.is_exhausted() {
+                StepPosition::JumpEnd
+            } else {
+                args.expect_comma()?;
+                parse_step_position(&mut args)?
+            };
+            args.expect_exhausted()?;
+            if position == StepPosition::JumpNone && steps < 2 {
+                return Err(());
+            }
+            Ok(TimingFunction::Steps(steps, position))
+        }
+        "linear" => parse_linear_points(&mut args).map(TimingFunction::LinearPoints),
+        _ => Err(()),
+    }
+}
+
+keyword_parser!(parse_step_position, StepPosition, {
+    "jump-start" => JumpStart,
+    "start" => JumpStart,
+    "jump-end" => JumpEnd,
+    "end" => JumpEnd,
+    "jump-none" => JumpNone,
+    "jump-both" => JumpBoth,
+});
+
+/// Parses the arguments of `linear()`: at least two stops of an output number and up to two
+/// input percentages (`0.5 25% 75%` is the same as `0.5 25%, 0.5 75%`). Omitted inputs are
+/// filled in: `0%` for the first stop, `100%` for the last one and evenly spaced in between.
+/// Inputs can't decrease, an input smaller than the one before is raised to it.
+fn parse_linear_points(input: &mut ValueParser) -> Result<Vec<LinearStop>, ()> {
+    let stops = input.parse_comma_separated(|i| {
+        let mut output = None;
+        let mut inputs = Vec::new();
+        while let Some(token) = i.next() {
+            match token {
+                Token::Number { value, .. } if output.is_none() => output = Some(*value),
+                Token::Percentage { value, .. } if inputs.len() < 2 => inputs.push(*value),
+                _ => return Err(()),
+            }
+        }
+        Ok((output.ok_or(())?, inputs))
+    })?;
+
+    let mut points = stops
+        .into_iter()
+        .flat_map(|(output, inputs)| match inputs[..] {
+            [] => vec![(output, None)],
+            _ => inputs.into_iter().map(|input| (output, Some(input))).collect(),
+        })
+        .collect::<Vec<_>>();
+    if points.len() < 2 {
+        return Err(());
+    }
+
+    let last = points.len() - 1;
+    points[0].1 = points[0].1.or(Some(0.0));
+    points[last].1 = points[last].1.or(Some(100.0));
+    let mut max_input = f32::MIN;
+    for point in points.iter_mut() {
+        if let Some(input) = point.1.as_mut() {
+            *input = input.max(max_input);
+            max_input = *input;
+        }
+    }
+
+    // runs of omitted inputs are spread evenly between the inputs around them
+    let mut previous = 0;
+    for i in 1..points.len() {
+        if let Some(end) = points[i].1 {
+            let start = points[previous].1.unwrap_or(end);
+            let count = (i - previous) as f32;
+            for (n, point) in points[previous + 1..i].iter_mut().enumerate() {
+                point.1 = Some(start + (end - start) * (n + 1) as f32 / count);
+            }
+            previous = i;
+        }
+    }
+
+    Ok(points
+        .into_iter()
+        .map(|(output, input)| LinearStop { output: FloatValue::new(output), input: PercentageValue::new(input.unwrap_or(0.0)) })
+        .collect())
+}
+
+/// Parses `infinite` or a non-negative number
+fn parse_iteration_count(input: &mut ValueParser) -> Result<StyleIterationCount, ()> {
+    match input.next() {
+        Some(Token::Ident(i)) if i.eq_ignore_ascii_case("infinite") => Ok(StyleIterationCount::Infinite),
+        Some(Token::Number { value, .. }) if *value >= 0.0 => Ok(StyleIterationCount::Count(FloatValue::new(*value))),
+        _ => Err(()),
+    }
+}
+
+keyword_parser!(parse_animation_direction, StyleAnimationDirection, {
+    "normal" => Normal,
+    "reverse" => Reverse,
+    "alternate" => Alternate,
+    "alternate-reverse" => AlternateReverse,
+});
+
+keyword_parser!(parse_animation_fill_mode, StyleAnimationFillMode, {
+    "none" => None,
+    "forwards" => Forwards,
+    "backwards" => Backwards,
+    "both" => Both,
+});
+
+keyword_parser!(parse_animation_play_state, StyleAnimationPlayState, {
+    "running" => Running,
+    "paused" => Paused,
+});
+
+/// Parses one item of `transition-property`: `all` or the name of a (shorthand) property.
+/// `none` is only valid on its own and is handled by `parse_value`.
+fn parse_transition_property(input: &mut ValueParser) -> Result<StyleTransitionProperty, ()> {
+    let name = input.expect_ident()?.to_ascii_lowercase();
+    if name == "all" {
+        Ok(StyleTransitionProperty::All)
+    } else if let Some(property_type) = PType::from_str(&name) {
+        Ok(StyleTransitionProperty::Property(property_type))
+    } else if let Some(shorthand) = CombinedPropertyType::from_str(&name) {
+        Ok(StyleTransitionProperty::Shorthand(shorthand))
+    } else {
+        Err(())
+    }
+}
+
+/// Parses a color, such as `red`, `#ff0000`, `rgb(255, 0, 0)` or `hsla(0, 100%, 50%, 1.0)`
+pub(crate) fn parse_color(input: &mut ValueParser) -> Result<ColorU, ()> {
+    match input.next() {
+        Some(Token::Hash { value, .. }) => parse_hex_color(value),
+        Some(Token::Ident(name)) => parse_color_keyword(name),
+        Some(Token::Function(name)) => {
+            let name = name.to_ascii_lowercase();
+            let mut args = input.block_contents();
+            let color = match name.as_str() {
+                "rgb" | "rgba" => parse_rgb_arguments(&mut args)?,
+                "hsl" | "hsla" => parse_hsl_arguments(&mut args)?,
+                _ => return Err(()),
+            };
+            args.expect_exhausted()?;
+            Ok(color)
+        }
+        _ => Err(()),
+    }
+}
+
+fn parse_hex_color(hex: &str) -> Result<ColorU, ()> {
+    let digit = |i: usize| -> Result<u8, ()> {
+        hex[i..i + 1].chars().next().and_then(|c| c.to_digit(16)).map(|d| d as u8).ok_or(())
+    };
+    if !hex.is_ascii() {
+        return Err(());
+    }
+    match hex.len() {
+        3 | 4 => {
+            let a = if hex.len() == 4 { digit(3)? * 17 } else { 255 };
+            Ok(ColorU { r: digit(0)? * 17, g: digit(1)? * 17, b: digit(2)? * 17, a })
+        }
+        6 | 8 => {
+            let byte = |i: usize| -> Result<u8, ()> { Ok(digit(i)? * 16 + digit(i + 1)?) };
+            let a = if hex.len() == 8 { byte(6)? } else { 255 };
+            Ok(ColorU { r: byte(0)?, g: byte(2)?, b: byte(4)?, a })
+        }
+        _ => Err(()),
+    }
+}
+
+/// Parses the alpha component of a color: `0.5` or `50%`
+fn parse_alpha(input: &mut ValueParser) -> Result<u8, ()> {
+    let alpha = match input.next() {
+        Some(Token::Number { value, .. }) => *value,
+        Some(Token::Percentage { value, .. }) => *value / 100.0,
+        _ => return Err(()),
+    };
+    Ok(libm::roundf(alpha.clamp(0.0, 1.0) * 255.0) as u8)
+}
+
+/// Parses the optional alpha component, either separated by a comma (legacy syntax)
+/// or by a `/` (space-separated syntax)
+fn parse_optional_alpha(input: &mut ValueParser, comma_separated: bool) -> Result<u8, ()> {
+    if input.is_exhausted() {
+        return Ok(ColorU::ALPHA_OPAQUE);
+    }
+    match input.next() {
+        Some(Token::Comma) if comma_separated => parse_alpha(input),
+        Some(Token::Delim('/')) if !comma_separated => parse_alpha(input),
+        _ => Err(()),
+    }
+}
+
+fn parse_rgb_arguments(input: &mut ValueParser) -> Result<ColorU, ()> {
+    let component = |input: &mut ValueParser| -> Result<u8, ()> {
+        let value = match input.next() {
+            Some(Token::Number { value, .. }) => *value,
+            Some(Token::Percentage { value, .. }) => *value / 100.0 * 255.0,
+            _ => return Err(()),
+        };
+        Ok(libm::roundf(value.clamp(0.0, 255.0)) as u8)
+    };
+
+    let r = component(input)?;
+    let comma_separated = input.try_parse(|i| i.expect_comma()).is_ok();
+    let g = component(input)?;
+    if comma_separated {
+        input.expect_comma()?;
+    }
+    let b = component(input)?;
+    let a = parse_optional_alpha(input, comma_separated)?;
+    Ok(ColorU { r, g, b, a })
+}
+
+fn parse_hsl_arguments(input: &mut ValueParser) -> Result<ColorU, ()> {
+    let hue = match input.try_parse(parse_angle_value) {
+        Ok(angle) => angle.to_degrees(),
+        Err(()) => input.expect_number()?,
+    };
+    let comma_separated = input.try_parse(|i| i.expect_comma()).is_ok();
+    let saturation = match input.next() {
+        Some(Token::Percentage { value, .. }) => *value / 100.0,
+        _ => return Err(()),
+    };
+    if comma_separated {
+        input.expect_comma()?;
+    }
+    let lightness = match input.next() {
+        Some(Token::Percentage { value, .. }) => *value / 100.0,
+        _ => return Err(()),
+    };
+    let a = parse_optional_alpha(input, comma_separated)?;
+    let (r, g, b) = hsl_to_rgb(hue, saturation.clamp(0.0, 1.0), lightness.clamp(0.0, 1.0));
+    Ok(ColorU { r, g, b, a })
+}
+
+/// See [the CSS color specification](https://www.w3.org/TR/css-color-3/#hsl-color)
+fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> (u8, u8, u8) {
+    let hue = (((hue % 360.0) + 360.0) % 360.0) / 360.0;
+    let m2 = if lightness <= 0.5 {
+        lightness * (saturation + 1.0)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let m1 = lightness * 2.0 - m2;
+    let hue_to_rgb = |h: f32| -> f32 {
+        let h = if h < 0.0 { h + 1.0 } else if h > 1.0 { h - 1.0 } else { h };
+        if h * 6.0 < 1.0 {
+            m1 + (m2 - m1) * h * 6.0
+        } else if h * 2.0 < 1.0 {
+            m2
+        } else if h * 3.0 < 2.0 {
+            m1 + (m2 - m1) * (2.0 / 3.0 - h) * 6.0
+        } else {
+            m1
+        }
+    };
+    let to_u8 = |v: f32| libm::roundf(v * 255.0) as u8;
+    (
+        to_u8(hue_to_rgb(hue + 1.0 / 3.0)),
+        to_u8(hue_to_rgb(hue)),
+        to_u8(hue_to_rgb(hue - 1.0 / 3.0)),
+    )
+}
+
+/// Parses one of the named CSS colors (`red`, `cornflowerblue`, `transparent`, ...)
+pub(crate) fn parse_color_keyword(name: &str) -> Result<ColorU, ()> {
+    let rgb = |r, g, b| Ok(ColorU::new_rgb(r, g, b));
+    match name.to_ascii_lowercase().as_str() {
+        "transparent" => Ok(ColorU::TRANSPARENT),
+        "aliceblue" => rgb(240, 248, 255),
+        "antiquewhite" => rgb(250, 235, 215),
+        "aqua" => rgb(0, 255, 255),
+        "aquamarine" => rgb(127, 255, 212),
+        "azure" => rgb(240, 255, 255),
+        "beige" => rgb(245, 245, 220),
+        "bisque" => rgb(255, 228, 196),
+        "black" => rgb(0, 0, 0),
+        "blanchedalmond" => rgb(255, 235, 205),
+        "blue" => rgb(0, 0, 255),
+        "blueviolet" => rgb(138, 43, 226),
+        "brown" => rgb(165, 42, 42),
+        "burlywood" => rgb(222, 184, 135),
+        "cadetblue" => rgb(95, 158, 160),
+        "chartreuse" => rgb(127, 255, 0),
+        "chocolate" => rgb(210, 105, 30),
+        "coral" => rgb(255, 127, 80),
+        "cornflowerblue" => rgb(100, 149, 237),
+        "cornsilk" => rgb(255, 248, 220),
+        "crimson" => rgb(220, 20, 60),
+        "cyan" => rgb(0, 255, 255),
+        "darkblue" => rgb(0, 0, 139),
+        "darkcyan" => rgb(0, 139, 139),
+        "darkgoldenrod" => rgb(184, 134, 11),
+        "darkgray" | "darkgrey" => rgb(169, 169, 169),
+        "darkgreen" => rgb(0, 100, 0),
+        "darkkhaki" => rgb(189, 183, 107),
+        "darkmagenta" => rgb(139, 0, 139),
+        "darkolivegreen" => rgb(85, 107, 47),
+        "darkorange" => rgb(255, 140, 0),
+        "darkorchid" => rgb(153, 50, 204),
+        "darkred" => rgb(139, 0, 0),
+        "darksalmon" => rgb(233, 150, 122),
+        "darkseagreen" => rgb(143, 188, 143),
+        "darkslateblue" => rgb(72, 61, 139),
+        "darkslategray" | "darkslategrey" => rgb(47, 79, 79),
+        "darkturquoise" => rgb(0, 206, 209),
+        "darkviolet" => rgb(148, 0, 211),
+        "deeppink" => rgb(255, 20, 147),
+        "deepskyblue" => rgb(0, 191, 255),
+        "dimgray" | "dimgrey" => rgb(105, 105, 105),
+        "dodgerblue" => rgb(30, 144, 255),
+        "firebrick" => rgb(178, 34, 34),
+        "floralwhite" => rgb(255, 250, 240),
+        "forestgreen" => rgb(34, 139, 34),
+        "fuchsia" => rgb(255, 0, 255),
+        "gainsboro" => rgb(220, 220, 220),
+        "ghostwhite" => rgb(248, 248, 255),
+        "gold" => rgb(255, 215, 0),
+        "goldenrod" => rgb(218, 165, 32),
+        "gray" | "grey" => rgb(128, 128, 128),
+        "green" => rgb(0, 128, 0),
+        "greenyellow" => rgb(173, 255, 47),
+        "honeydew" => rgb(240, 255, 240),
+        "hotpink" => rgb(255, 105, 180),
+        "indianred" => rgb(205, 92, 92),
+        "indigo" => rgb(75, 0, 130),
+        "ivory" => rgb(255, 255, 240),
+        "khaki" => rgb(240, 230, 140),
+        "lavender" => rgb(230, 230, 250),
+        "lavenderblush" => rgb(255, 240, 245),
+        "lawngreen" => rgb(124, 252, 0),
+        "lemonchiffon" => rgb(255, 250, 205),
+        "lightblue" => rgb(173, 216, 230),
+        "lightcoral" => rgb(240, 128, 128),
+        "lightcyan" => rgb(224, 255, 255),
+        "lightgoldenrodyellow" => rgb(250, 250, 210),
+        "lightgray" | "lightgrey" => rgb(211, 211, 211),
+        "lightgreen" => rgb(144, 238, 144),
+        "lightpink" => rgb(255, 182, 193),
+        "lightsalmon" => rgb(255, 160, 122),
+        "lightseagreen" => rgb(32, 178, 170),
+        "lightskyblue" => rgb(135, 206, 250),
+        "lightslategray" | "lightslategrey" => rgb(119, 136, 153),
+        "lightsteelblue" => rgb(176, 196, 222),
+        "lightyellow" => rgb(255, 255, 224),
+        "lime" => rgb(0, 255, 0),
+        "limegreen" => rgb(50, 205, 50),
+        "linen" => rgb(250, 240, 230),
+        "magenta" => rgb(255, 0, 255),
+        "maroon" => rgb(128, 0, 0),
+        "mediumaquamarine" => rgb(102, 205, 170),
+        "mediumblue" => rgb(0, 0, 205),
+        "mediumorchid" => rgb(186, 85, 211),
+        "mediumpurple" => rgb(147, 112, 219),
+        "mediumseagreen" => rgb(60, 179, 113),
+        "mediumslateblue" => rgb(123, 104, 238),
+        "mediumspringgreen" => rgb(0, 250, 154),
+        "mediumturquoise" => rgb(72, 209, 204),
+        "mediumvioletred" => rgb(199, 21, 133),
+        "midnightblue" => rgb(25, 25, 112),
+        "mintcream" => rgb(245, 255, 250),
+        "mistyrose" => rgb(255, 228, 225),
+        "moccasin" => rgb(255, 228, 181),
+        "navajowhite" => rgb(255, 222, 173),
+        "navy" => rgb(0, 0, 128),
+        "oldlace" => rgb(253, 245, 230),
+        "olive" => rgb(128, 128, 0),
+        "olivedrab" => rgb(107, 142, 35),
+        "orange" => rgb(255, 165, 0),
+        "orangered" => rgb(255, 69, 0),
+        "orchid" => rgb(218, 112, 214),
+        "palegoldenrod" => rgb(238, 232, 170),
+        "palegreen" => rgb(152, 251, 152),
+        "paleturquoise" => rgb(175, 238, 238),
+        "palevioletred" => rgb(219, 112, 147),
+        "papayawhip" => rgb(255, 239, 213),
+        "peachpuff" => rgb(255, 218, 185),
+        "peru" => rgb(205, 133, 63),
+        "pink" => rgb(255, 192, 203),
+        "plum" => rgb(221, 160, 221),
+        "powderblue" => rgb(176, 224, 230),
+        "purple" => rgb(128, 0, 128),
+        "rebeccapurple" => rgb(102, 51, 153),
+        "red" => rgb(255, 0, 0),
+        "rosybrown" => rgb(188, 143, 143),
+        "royalblue" => rgb(65, 105, 225),
+        "saddlebrown" => rgb(139, 69, 19),
+        "salmon" => rgb(250, 128, 114),
+        "sandybrown" => rgb(244, 164, 96),
+        "seagreen" => rgb(46, 139, 87),
+        "seashell" => rgb(255, 245, 238),
+        "sienna" => rgb(160, 82, 45),
+        "silver" => rgb(192, 192, 192),
+        "skyblue" => rgb(135, 206, 235),
+        "slateblue" => rgb(106, 90, 205),
+        "slategray" | "slategrey" => rgb(112, 128, 144),
+        "snow" => rgb(255, 250, 250),
+        "springgreen" => rgb(0, 255, 127),
+        "steelblue" => rgb(70, 130, 180),
+        "tan" => rgb(210, 180, 140),
+        "teal" => rgb(0, 128, 128),
+        "thistle" => rgb(216, 191, 216),
+        "tomato" => rgb(255, 99, 71),
+        "turquoise" => rgb(64, 224, 208),
+        "violet" => rgb(238, 130, 238),
+        "wheat" => rgb(245, 222, 179),
+        "white" => rgb(255, 255, 255),
+        "whitesmoke" => rgb(245, 245, 245),
+        "yellow" => rgb(255, 255, 0),
+        "yellowgreen" => rgb(154, 205, 50),
+        _ => Err(()),
+    }
+}
+
+/// Parses one layer of a `background`: a color, an image or a gradient
+pub(crate) fn parse_background_content(input: &mut ValueParser) -> Result<StyleBackgroundContent, ()> {
+    if let Ok(image) = input.try_parse(parse_image) {
+        return Ok(StyleBackgroundContent::Image(image));
+    }
+    if let Ok(color) = input.try_parse(parse_color) {
+        return Ok(StyleBackgroundContent::Color(color));
+    }
+
+    let (name, mut args) = input.expect_function()?;
+    let name = name.to_ascii_lowercase();
+    let (extend_mode, name) = match name.strip_prefix("repeating-") {
+        Some(n) => (ExtendMode::Repeat, n),
+        None => (ExtendMode::Clamp, name.as_str()),
+    };
+    let gradient = match name {
+        "linear-gradient" => StyleBackgroundContent::LinearGradient(parse_linear_gradient(&mut args, extend_mode)?),
+        "radial-gradient" => StyleBackgroundContent::RadialGradient(parse_radial_gradient(&mut args, extend_mode)?),
+        "conic-gradient" => StyleBackgroundContent::ConicGradient(parse_conic_gradient(&mut args, extend_mode)?),
+        _ => return Err(()),
+    };
+    Ok(gradient)
+}
+
+/// Parses `url(image.png)`, `url("image.png")` or `image("image_id")`
+fn parse_image(input: &mut ValueParser) -> Result<String, ()> {
+    match input.next() {
+        Some(Token::Url(u)) => Ok(u.clone()),
+        Some(Token::Function(f)) if f.eq_ignore_ascii_case("url") || f.eq_ignore_ascii_case("image") => {
+            let mut args = input.block_contents();
+            match args.next() {
+                Some(Token::QuotedString(s)) => {
+                    args.expect_exhausted()?;
+                    Ok(s.clone())
+                }
+                _ => Err(()),
+            }
+        }
+        _ => Err(()),
+    }
+}
+
+fn parse_direction_corner(input: &mut ValueParser) -> Result<DirectionCorner, ()> {
+    match input.expect_ident()?.to_ascii_lowercase().as_str() {
+        "right" => Ok(DirectionCorner::Right),
+        "left" => Ok(DirectionCorner::Left),
+        "top" => Ok(DirectionCorner::Top),
+        "bottom" => Ok(DirectionCorner::Bottom),
+        _ => Err(()),
+    }
+}
+
+/// Parses the direction of a linear gradient: `45deg`, `to right` or `to top left`
+fn parse_direction(input: &mut ValueParser) -> Result<Direction, ()> {
+    if let Ok(angle) = input.try_parse(parse_angle_value) {
+        return Ok(Direction::Angle(angle));
+    }
+    input.expect_ident_matching("to")?;
+    let first = parse_direction_corner(input)?;
+    let to = match input.try_parse(parse_direction_corner) {
+        Ok(second) => first.combine(&second).ok_or(())?,
+        Err(()) => first,
+    };
+    Ok(Direction::FromTo(to.opposite(), to))
+}
+
+fn parse_linear_color_stop(input: &mut ValueParser) -> Result<LinearColorStop, ()> {
+    let color = parse_color(input)?;
+    let offset = input.try_parse(parse_color_stop_percentage).ok();
+    Ok(LinearColorStop { offset, color })
+}
+
+fn parse_color_stop_percentage(input: &mut ValueParser) -> Result<PercentageValue, ()> {
+    match input.next() {
+        Some(Token::Percentage { value, .. }) => Ok(PercentageValue::new(*value)),
+        Some(Token::Number { value, .. }) if *value == 0.0 => Ok(PercentageValue::const_new(0)),
+        _ => Err(()),
+    }
+}
+
+fn parse_radial_color_stop(input: &mut ValueParser) -> Result<RadialColorStop, ()> {
+    let color = parse_color(input)?;
+    let offset = input.try_parse(parse_angle_value).ok();
+    Ok(RadialColorStop { offset, color })
+}
+
+fn parse_linear_gradient(input: &mut ValueParser, extend_mode: ExtendMode) -> Result<LinearGradient, ()> {
+    let mut args = input.split_commas().into_iter().peekable();
+    let mut direction = Direction::default();
+    if let Some(first) = args.peek_mut() {
+        if let Ok(d) = first.try_parse(|i| {
+            let d = parse_direction(i)?;
+            i.expect_exhausted()?;
+            Ok(d)
+        }) {
+            direction = d;
+            args.next();
+        }
+    }
+    let stops = args
+        .map(|mut arg| {
+            let stop = parse_linear_color_stop(&mut arg)?;
+            arg.expect_exhausted()?;
+            Ok(stop)
+        })
+        .collect::<Result<Vec<_>, ()>>()?;
+    if stops.is_empty() {
+        return Err(());
+    }
+    Ok(LinearGradient {
+        direction,
+        extend_mode,
+        stops: LinearColorStop::to_normalized(&stops),
+    })
+}
+
+fn parse_radial_gradient(input: &mut ValueParser, extend_mode: ExtendMode) -> Result<RadialGradient, ()> {
+    let mut args = input.split_commas().into_iter().peekable();
+    let mut shape = Shape::default();
+    let mut size = RadialGradientSize::default();
+    let mut position = StyleBackgroundPosition::default();
+
+    if let Some(first) = args.peek_mut() {
+        let config = first.try_parse(|i| {
+            let mut shape = None;
+            let mut size = None;
+            let mut position = None;
+            while !i.is_exhausted() {
+                if shape.is_none() {
+                    if let Ok(s) = i.try_parse(parse_shape) {
+                        shape = Some(s);
+                        continue;
+                    }
+                }
+                if size.is_none() {
+                    if let Ok(s) = i.try_parse(parse_radial_gradient_size) {
+                        size = Some(s);
+                        continue;
+                    }
+                }
+                if position.is_none() {
+                    i.expect_ident_matching("at")?;
+                    position = Some(parse_background_position(i)?);
+                    continue;
+                }
+                return Err(());
+            }
+            if shape.is_none() && size.is_none() && position.is_none() {
+                return Err(());
+            }
+            Ok((shape, size, position))
+        });
+        if let Ok((s, sz, p)) = config {
+            shape = s.unwrap_or_default();
+            size = sz.unwrap_or_default();
+            position = p.unwrap_or_default();
+            args.next();
+        }
+    }
+
+    let stops = args
+        .map(|mut arg| {
+            let stop = parse_linear_color_stop(&mut arg)?;
+            arg.expect_exhausted()?;
+            Ok(stop)
+        })
+        .collect::<Result<Vec<_>, ()>>()?;
+    if stops.is_empty() {
+        return Err(());
+    }
+    Ok(RadialGradient {
+        shape,
+        size,
+        position,
+        extend_mode,
+        stops: LinearColorStop::to_normalized(&stops),
+    })
+}
+
+fn parse_conic_gradient(input: &mut ValueParser, extend_mode: ExtendMode) -> Result<ConicGradient, ()> {
+    let mut args = input.split_commas().into_iter().peekable();
+    let mut angle = AngleValue::default();
+    let mut center = StyleBackgroundPosition {
+        horizontal: BackgroundPositionHorizontal::Center,
+        vertical: BackgroundPositionVertical::Center,
+    };
+
+    if let Some(first) = args.peek_mut() {
+        let config = first.try_parse(|i| {
+            let mut angle = None;
+            let mut center = None;
+            if i.try_parse(|i| i.expect_ident_matching("from")).is_ok() {
+                angle = Some(parse_angle_value(i)?);
+            }
+            if i.try_parse(|i| i.expect_ident_matching("at")).is_ok() {
+                center = Some(parse_background_position(i)?);
+            }
+            i.expect_exhausted()?;
+            if angle.is_none() && center.is_none() {
+                return Err(());
+            }
+            Ok((angle, center))
+        });
+        if let Ok((a, c)) = config {
+            angle = a.unwrap_or(angle);
+            center = c.unwrap_or(center);
+            args.next();
+        }
+    }
+
+    let stops = args
+        .map(|mut arg| {
+            let stop = parse_radial_color_stop(&mut arg)?;
+            arg.expect_exhausted()?;
+            Ok(stop)
+        })
+        .collect::<Result<Vec<_>, ()>>()?;
+    if stops.is_empty() {
+        return Err(());
+    }
+    Ok(ConicGradient {
+        extend_mode,
+        center,
+        angle,
+        stops: RadialColorStop::to_normalized(&stops),
+    })
+}
+
+/// Parses a position such as `left top`, `center`, `10px 20px` or `bottom right`
+pub(crate) fn parse_background_position(input: &mut ValueParser) -> Result<StyleBackgroundPosition, ()> {
+    #[derive(Copy, Clone)]
+    enum Component {
+        Horizontal(BackgroundPositionHorizontal),
+        Vertical(BackgroundPositionVertical),
+        Center,
+        Exact(PixelValue),
+    }
+
+    let component = |input: &mut ValueParser| -> Result<Component, ()> {
+        if let Ok(value) = input.try_parse(parse_pixel_value) {
+            return Ok(Component::Exact(value));
+        }
+        match input.expect_ident()?.to_ascii_lowercase().as_str() {
+            "left" => Ok(Component::Horizontal(BackgroundPositionHorizontal::Left)),
+            "right" => Ok(Component::Horizontal(BackgroundPositionHorizontal::Right)),
+            "top" => Ok(Component::Vertical(BackgroundPositionVertical::Top)),
+            "bottom" => Ok(Component::Vertical(BackgroundPositionVertical::Bottom)),
+            "center" => Ok(Component::Center),
+            _ => Err(()),
+        }
+    };
+
+    let first = component(input)?;
+    let second = input.try_parse(component).ok();
+
+    let horizontal = |c: Component| match c {
+        Component::Horizontal(h) => Ok(h),
+        Component::Center => Ok(BackgroundPositionHorizontal::Center),
+        Component::Exact(v) => Ok(BackgroundPositionHorizontal::Exact(v)),
+        Component::Vertical(_) => Err(()),
+    };
+    let vertical = |c: Component| match c {
+        Component::Vertical(v) => Ok(v),
+        Component::Center => Ok(BackgroundPositionVertical::Center),
+        Component::Exact(v) => Ok(BackgroundPositionVertical::Exact(v)),
+        Component::Horizontal(_) => Err(()),
+    };
+
+    let (horizontal, vertical) = match (first, second) {
+        (Component::Vertical(v), None) => (BackgroundPositionHorizontal::Center, v),
+        (h, None) => (horizontal(h)?, BackgroundPositionVertical::Center),
+        // `top left` is the same as `left top`
+        (Component::Vertical(v), Some(h)) => (horizontal(h)?, v),
+        (Component::Center, Some(Component::Horizontal(h))) => (h, BackgroundPositionVertical::Center),
+        (h, Some(v)) => (horizontal(h)?, vertical(v)?),
+    };
+
+    Ok(StyleBackgroundPosition { horizontal, vertical })
+}
+
+fn parse_background_size(input: &mut ValueParser) -> Result<StyleBackgroundSize, ()> {
+    if let Ok(ident) = input.try_parse(|i| i.expect_ident()) {
+        return match ident.to_ascii_lowercase().as_str() {
+            "contain" => Ok(StyleBackgroundSize::Contain),
+            "cover" => Ok(StyleBackgroundSize::Cover),
+            _ => Err(()),
+        };
+    }
+    let width = parse_pixel_value(input)?;
+    let height = input.try_parse(parse_pixel_value).unwrap_or(width);
+    Ok(StyleBackgroundSize::ExactSize(width, height))
+}
+
+/// Parses a shadow: `[inset] <offset-x> <offset-y> [<blur>] [<spread>] [<color>]`
+pub(crate) fn parse_box_shadow(input: &mut ValueParser) -> Result<StyleBoxShadow, ()> {
+    let mut clip_mode = None;
+    let mut color = None;
+    let mut lengths = Vec::new();
+
+    while !input.is_exhausted() {
+        if clip_mode.is_none() && input.try_parse(|i| i.expect_ident_matching("inset")).is_ok() {
+            clip_mode = Some(BoxShadowClipMode::Inset);
+            continue;
+        }
+        if color.is_none() {
+            if let Ok(c) = input.try_parse(parse_color) {
+                color = Some(c);
+                continue;
+            }
+        }
+        if lengths.len() < 4 {
+            if let Ok(l) = input.try_parse(parse_pixel_value) {
+                lengths.push(l);
+                continue;
+            }
+        }
+        return Err(());
+    }
+
+    if lengths.len() < 2 {
+        return Err(());
+    }
+
+    Ok(StyleBoxShadow {
+        offset: (lengths[0], lengths[1]),
+        color: color.unwrap_or(ColorU::BLACK),
+        blur_radius: lengths.get(2).copied().unwrap_or_else(PixelValue::zero),
+        spread_radius: lengths.get(3).copied().unwrap_or_else(PixelValue::zero),
+        clip_mode: clip_mode.unwrap_or(BoxShadowClipMode::Outset),
+    })
+}
+
+/// Parses a `transform-origin` or `perspective-origin`: `50% 50%`, `left top`, `10px`
+fn parse_origin(input: &mut ValueParser) -> Result<(PixelValue, PixelValue), ()> {
+    let position = parse_background_position(input)?;
+    let x = match position.horizontal {
+        BackgroundPositionHorizontal::Left => PixelValue::const_percent(0),
+        BackgroundPositionHorizontal::Center => PixelValue::const_percent(50),
+        BackgroundPositionHorizontal::Right => PixelValue::const_percent(100),
+        BackgroundPositionHorizontal::Exact(v) => v,
+    };
+    let y = match position.vertical {
+        BackgroundPositionVertical::Top => PixelValue::const_percent(0),
+        BackgroundPositionVertical::Center => PixelValue::const_percent(50),
+        BackgroundPositionVertical::Bottom => PixelValue::const_percent(100),
+        BackgroundPositionVertical::Exact(v) => v,
+    };
+    Ok((x, y))
+}
+
+/// Parses the arguments of a function, separated by commas
+fn parse_arguments<'a, T, F>(input: &mut ValueParser<'a>, f: F) -> Result<Vec<T>, ()>
+where
+    F: Fn(&mut ValueParser<'a>) -> Result<T, ()>,
+{
+    input.parse_comma_separated(f)
+}
+
+/// Parses a list of transformations: `translate(10px, 20px) rotate(45deg)`
+pub(crate) fn parse_transforms(input: &mut ValueParser) -> Result<Vec<StyleTransform>, ()> {
+    let mut transforms = Vec::new();
+    while !input.is_exhausted() {
+        transforms.push(parse_transform(input)?);
+    }
+    Ok(transforms)
+}
+
+fn parse_transform(input: &mut ValueParser) -> Result<StyleTransform, ()> {
+    let (name, mut args) = input.expect_function()?;
+    let args = &mut args;
+    let name = name.to_ascii_lowercase();
+
+    let transform = match name.as_str() {
+        "matrix" => {
+            let m = parse_arguments(args, parse_pixel_value_or_number)?;
+            if m.len() != 6 {
+                return Err(());
+            }
+            StyleTransform::Matrix(StyleTransformMatrix2D { a: m[0], b: m[1], c: m[2], d: m[3], tx: m[4], ty: m[5] })
+        }
+        "matrix3d" => {
+            let m = parse_arguments(args, parse_pixel_value_or_number)?;
+            if m.len() != 16 {
+                return Err(());
+            }
+            StyleTransform::Matrix3D(StyleTransformMatrix3D {
+                m11: m[0], m12: m[1], m13: m[2], m14: m[3],
+                m21: m[4], m22: m[5], m23: m[6], m24: m[7],
+                m31: m[8], m32: m[9], m33: m[10], m34: m[11],
+                m41: m[12], m42: m[13], m43: m[14], m44: m[15],
+            })
+        }
+        "translate" => {
+            let v = parse_arguments(args, parse_pixel_value)?;
+            match v.as_slice() {
+                [x] => StyleTransform::Translate(StyleTransformTranslate2D { x: *x, y: PixelValue::zero() }),
+                [x, y] => StyleTransform::Translate(StyleTransformTranslate2D { x: *x, y: *y }),
+                _ => return Err(()),
+            }
+        }
+        "translate3d" => {
+            let v = parse_arguments(args, parse_pixel_value)?;
+            match v.as_slice() {
+                [x, y, z] => StyleTransform::Translate3D(StyleTransformTranslate3D { x: *x, y: *y, z: *z }),
+                _ => return Err(()),
+            }
+        }
+        "translatex" => StyleTransform::TranslateX(parse_single_argument(args, parse_pixel_value)?),
+        "translatey" => StyleTransform::TranslateY(parse_single_argument(args, parse_pixel_value)?),
+        "translatez" => StyleTransform::TranslateZ(parse_single_argument(args, parse_pixel_value)?),
+        "rotate" => StyleTransform::Rotate(parse_single_argument(args, parse_angle_value)?),
+        "rotate3d" => {
+            let mut parts = args.split_commas();
+            if parts.len() != 4 {
+                return Err(());
+            }
+            let mut component = |i: usize| -> Result<PercentageValue, ()> {
+                let v = parse_percentage_value(&mut parts[i])?;
+                parts[i].expect_exhausted()?;
+                Ok(v)
+            };
+            let (x, y, z) = (component(0)?, component(1)?, component(2)?);
+            let angle = parse_angle_value(&mut parts[3])?;
+            parts[3].expect_exhausted()?;
+            StyleTransform::Rotate3D(StyleTransformRotate3D { x, y, z, angle })
+        }
+        "rotatex" => StyleTransform::RotateX(parse_single_argument(args, parse_angle_value)?),
+        "rotatey" => StyleTransform::RotateY(parse_single_argument(args, parse_angle_value)?),
+        "rotatez" => StyleTransform::RotateZ(parse_single_argument(args, parse_angle_value)?),
+        "scale" => {
+            let v = parse_arguments(args, parse_percentage_value)?;
+            match v.as_slice() {
+                [x] => StyleTransform::Scale(StyleTransformScale2D { x: *x, y: *x }),
+                [x, y] => StyleTransform::Scale(StyleTransformScale2D { x: *x, y: *y }),
+                _ => return Err(()),
+            }
+        }
+        "scale3d" => {
+            let v = parse_arguments(args, parse_percentage_value)?;
+            match v.as_slice() {
+                [x, y, z] => StyleTransform::Scale3D(StyleTransformScale3D { x: *x, y: *y, z: *z }),
+                _ => return Err(()),
+            }
+        }
+        "scalex" => StyleTransform::ScaleX(parse_single_argument(args, parse_percentage_value)?),
+        "scaley" => StyleTransform::ScaleY(parse_single_argument(args, parse_percentage_value)?),
+        "scalez" => StyleTransform::ScaleZ(parse_single_argument(args, parse_percentage_value)?),
+        "skew" => {
+            let v = parse_arguments(args, parse_skew_angle)?;
+            match v.as_slice() {
+                [x] => StyleTransform::Skew(StyleTransformSkew2D { x: *x, y: PercentageValue::const_new(0) }),
+                [x, y] => StyleTransform::Skew(StyleTransformSkew2D { x: *x, y: *y }),
+                _ => return Err(()),
+            }
+        }
+        "skewx" => StyleTransform::SkewX(parse_single_argument(args, parse_skew_angle)?),
+        "skewy" => StyleTransform::SkewY(parse_single_argument(args, parse_skew_angle)?),
+        "perspective" => match args.try_parse(|i| parse_single_argument(i, |i| i.expect_ident_matching("none"))) {
+            Ok(()) => StyleTransform::PERSPECTIVE_NONE,
+            Err(()) => StyleTransform::Perspective(parse_single_argument(args, parse_pixel_value)?),
+        },
+        _ => return Err(()),
+    };
+
+    Ok(transform)
+}
+
+/// Parses the `<angle>` of a `skew()`: stored in degrees, as the `normalized()` value of a percentage
+fn parse_skew_angle(input: &mut ValueParser) -> Result<PercentageValue, ()> {
+    if let Some(Token::Percentage { .. }) = input.peek() {
+        return Err(());
+    }
+    parse_angle_value(input).map(|angle| PercentageValue::new(angle.to_degrees_unclamped() * 100.0))
+}
+
+fn parse_single_argument<'a, T, F>(input: &mut ValueParser<'a>, f: F) -> Result<T, ()>
+where
+    F: FnOnce(&mut ValueParser<'a>) -> Result<T, ()>,
+{
+    let value = f(input)?;
+    input.expect_exhausted()?;
+    Ok(value)
+}
+
+/// Parses a list of filters: `blur(5px) opacity(50%)`
+pub(crate) fn parse_filters(input: &mut ValueParser) -> Result<Vec<StyleFilter>, ()> {
+    let mut filters = Vec::new();
+    while !input.is_exhausted() {
+        filters.push(parse_filter(input)?);
+    }
+    Ok(filters)
+}
+
+fn parse_filter(input: &mut ValueParser) -> Result<StyleFilter, ()> {
+    let (name, mut args) = input.expect_function()?;
+    let args = &mut args;
+    let filter = match name.to_ascii_lowercase().as_str() {
+        "blend" => StyleFilter::Blend(parse_single_argument(args, parse_mix_blend_mode)?),
+        "flood" => StyleFilter::Flood(parse_single_argument(args, parse_color)?),
+        "blur" => {
+            let v = parse_arguments(args, parse_pixel_value)?;
+            match v.as_slice() {
+                [r] => StyleFilter::Blur(StyleBlur { width: *r, height: *r }),
+                [width, height] => StyleFilter::Blur(StyleBlur { width: *width, height: *height }),
+                _ => return Err(()),
+            }
+        }
+        "opacity" => StyleFilter::Opacity(parse_single_argument(args, parse_percentage_value)?),
+        "color-matrix" => {
+            let v = parse_arguments(args, parse_float_value)?;
+            let mut matrix = [FloatValue::default(); 20];
+            if v.len() != matrix.len() {
+                return Err(());
+            }
+            matrix.copy_from_slice(&v);
+            StyleFilter::ColorMatrix(StyleColorMatrix { matrix })
+        }
+        "drop-shadow" => StyleFilter::DropShadow(parse_single_argument(args, parse_box_shadow)?),
+        "component-transfer" => {
+            args.expect_exhausted()?;
+            StyleFilter::ComponentTransfer
+        }
+        "offset" => {
+            let v = parse_arguments(args, parse_pixel_value)?;
+            match v.as_slice() {
+                [x, y] => StyleFilter::Offset(StyleFilterOffset { x: *x, y: *y }),
+                _ => return Err(()),
+            }
+        }
+        "composite" => StyleFilter::Composite(parse_composite_filter(args)?),
+        _ => return Err(()),
+    };
+    Ok(filter)
+}
+
+fn parse_composite_filter(input: &mut ValueParser) -> Result<StyleCompositeFilter, ()> {
+    let mut parts = input.split_commas();
+    let operator = parts[0].expect_ident()?.to_ascii_lowercase();
+    parts[0].expect_exhausted()?;
+    let filter = match operator.as_str() {
+        "over" => StyleCompositeFilter::Over,
+        "in" => StyleCompositeFilter::In,
+        "atop" => StyleCompositeFilter::Atop,
+        "out" => StyleCompositeFilter::Out,
+        "xor" => StyleCompositeFilter::Xor,
+        "lighter" => StyleCompositeFilter::Lighter,
+        "arithmetic" => {
+            let mut k = [FloatValue::default(); 4];
+            if parts.len() != 5 {
+                return Err(());
+            }
+            for (k, part) in k.iter_mut().zip(parts[1..].iter_mut()) {
+                *k = parse_single_argument(part, parse_float_value)?;
+            }
+            return Ok(StyleCompositeFilter::Arithmetic(k));
+        }
+        _ => return Err(()),
+    };
+    if parts.len() != 1 {
+        return Err(());
+    }
+    Ok(filter)
+}
+
+
+#[test]
+fn test_parse_color() {
+    use crate::tokenizer::Tokenizer;
+
+    let parse = |s: &str| {
+        let tokens = Tokenizer::tokenize(s);
+        let mut input = ValueParser::new(&tokens);
+        parse_color(&mut input).and_then(|c| input.expect_exhausted().map(|_| c))
+    };
+
+    assert_eq!(parse("red"), Ok(ColorU::RED));
+    assert_eq!(parse("#f00"), Ok(ColorU::RED));
+    assert_eq!(parse("#ff000080"), Ok(ColorU { r: 255, g: 0, b: 0, a: 128 }));
+    assert_eq!(parse("rgba(255, 0, 0, 0.5)"), Ok(ColorU { r: 255, g: 0, b: 0, a: 128 }));
+    assert_eq!(parse("rgb(100% 0% 0% / 50%)"), Ok(ColorU { r: 255, g: 0, b: 0, a: 128 }));
+    assert_eq!(parse("hsl(0, 100%, 50%)"), Ok(ColorU::RED));
+    assert_eq!(parse("hsl(240deg 100% 50%)"), Ok(ColorU::BLUE));
+    assert_eq!(parse("rgb(255, 0 0)"), Err(()));
+    assert_eq!(parse("notacolor"), Err(()));
+}
+
+#[test]
+fn test_parse_shorthand() {
+    let expand = |shorthand: CombinedPropertyType, value: &str| shorthand.expand(value).map_err(|_| ());
+    let margins = |top: f32, right: f32, bottom: f32, left: f32| {
+        Ok(vec![
+            Property::MarginTop(Value::Exact(LayoutMarginTop(PixelValue::px(top)))),
+            Property::MarginRight(Value::Exact(LayoutMarginRight(PixelValue::px(right)))),
+            Property::MarginBottom(Value::Exact(LayoutMarginBottom(PixelValue::px(bottom)))),
+            Property::MarginLeft(Value::Exact(LayoutMarginLeft(PixelValue::px(left)))),
+        ])
+    };
+
+    assert_eq!(expand(CombinedPropertyType::Margin, "1px"), margins(1.0, 1.0, 1.0, 1.0));
+    assert_eq!(expand(CombinedPropertyType::Margin, "1px 2px"), margins(1.0, 2.0, 1.0, 2.0));
+    assert_eq!(expand(CombinedPropertyType::Margin, "1px 2px 3px"), margins(1.0, 2.0, 3.0, 2.0));
+    assert_eq!(expand(CombinedPropertyType::Margin, "1px 2px 3px 4px"), margins(1.0, 2.0, 3.0, 4.0));
+    assert_eq!(expand(CombinedPropertyType::Margin, "1px 2px 3px 4px 5px"), Err(()));
+    assert_eq!(expand(CombinedPropertyType::Padding, "auto"), Err(()));
+
+    let border = expand(CombinedPropertyType::Border, "1px solid red").unwrap();
+    assert_eq!(border.len(), 12);
+    assert!(border.contains(&Property::BorderLeftWidth(Value::Exact(LayoutBorderLeftWidth(PixelValue::px(1.0))))));
+    assert!(border.contains(&Property::BorderTopStyle(Value::Exact(StyleBorderTopStyle(BorderStyle::Solid)))));
+    assert!(border.contains(&Property::BorderBottomColor(Value::Exact(StyleBorderBottomColor(ColorU::RED)))));
+    assert_eq!(expand(CombinedPropertyType::BorderTop, "red dashed"), Ok(vec![
+        Property::BorderTopWidth(Value::Initial),
+        Property::BorderTopStyle(Value::Exact(StyleBorderTopStyle(BorderStyle::Dashed))),
+        Property::BorderTopColor(Value::Exact(StyleBorderTopColor(ColorU::RED))),
+    ]));
+    assert_eq!(expand(CombinedPropertyType::Border, "solid dashed"), Err(()));
+
+    assert_eq!(expand(CombinedPropertyType::Overflow, "hidden scroll"), Ok(vec![
+        Property::OverflowX(Value::Exact(LayoutOverflow::Hidden)),
+        Property::OverflowY(Value::Exact(LayoutOverflow::Scroll)),
+    ]));
+    assert_eq!(expand(CombinedPropertyType::Overflow, "inherit"), Ok(vec![
+        Property::OverflowX(Value::Inherit),
+        Property::OverflowY(Value::Inherit),
+    ]));
+}
+
+#[test]
+fn test_parse_content() {
+    use crate::tokenizer::Tokenizer;
+
+    let parse = |s: &str| {
+        let tokens = Tokenizer::tokenize(s);
+        parse_property(PType::Content, &mut ValueParser::new(&tokens))
+    };
+    let content = |items: Vec<StyleContent>| Ok(Property::Content(Value::Exact(items)));
+
+    assert_eq!(parse("none"), Ok(Property::Content(Value::None)));
+    assert_eq!(parse("normal"), Ok(Property::Content(Value::None)));
+    assert_eq!(parse("\"a\" attr(title) open-quote"), content(vec![
+        StyleContent::String("a".to_string()),
+        StyleContent::Attr("title".to_string()),
+        StyleContent::OpenQuote,
+    ]));
+    assert_eq!(parse("counter(item, upper-roman) counters(section, \".\")"), content(vec![
+        StyleContent::Counter { name: "item".to_string(), style: Some("upper-roman".to_string()) },
+        StyleContent::Counters { name: "section".to_string(), separator: ".".to_string(), style: None },
+    ]));
+    for invalid in ["", "attr()", "counter(a,)", "counters(a)", "counters(a, b)", "open-quote none", "5px"] {
+        assert_eq!(parse(invalid), Err(()), "{}", invalid);
+    }
+
+    let property = parse("\"\\\"\" counters(a, \"-\", lower-alpha) no-close-quote").unwrap();
+    assert_eq!(property.to_string(), "content: \"\\\"\" counters(a, \"-\", lower-alpha) no-close-quote");
+}
+
+#[test]
+fn test_parse_animation() {
+    use crate::tokenizer::Tokenizer;
+
+    let parse = |ptype: PType, s: &str| {
+        let tokens = Tokenizer::tokenize(s);
+        parse_property(ptype, &mut ValueParser::new(&tokens))
+    };
+    assert_eq!(
+        parse(PType::AnimationDuration, "300ms, 2s"),
+        Ok(Property::AnimationDuration(Value::Exact(vec![StyleDuration::ms(300.0), StyleDuration::ms(2000.0)])))
+    );
+    assert_eq!(parse(PType::AnimationDuration, "-1s"), Err(()));
+    assert_eq!(parse(PType::AnimationDelay, "-1s"), Ok(Property::AnimationDelay(Value::Exact(vec![StyleDuration::ms(-1000.0)]))));
+    assert_eq!(parse(PType::AnimationName, "none"), Ok(Property::AnimationName(Value::None)));
+    assert_eq!(parse(PType::AnimationName, "slide, none"), Err(()));
+    assert_eq!(
+        parse(PType::AnimationIterationCount, "infinite, 2.5"),
+        Ok(Property::AnimationIterationCount(Value::Exact(vec![
+            StyleIterationCount::Infinite,
+            StyleIterationCount::Count(FloatValue::new(2.5)),
+        ])))
+    );
+
+    let longhands = CombinedPropertyType::Animation.expand("slide 300ms ease-in 1s infinite alternate both, 'fade' 2s").unwrap();
+    assert_eq!(longhands.len(), 8);
+    assert_eq!(
+        longhands.iter().map(|p| p.to_string()).collect::<Vec<_>>(),
+        vec![
+            "animation-name: slide, fade",
+            "animation-duration: 0.3s, 2s",
+            "animation-timing-function: ease-in, ease",
+            "animation-delay: 1s, 0ms",
+            "animation-iteration-count: infinite, 1",
+            "animation-direction: alternate, normal",
+            "animation-fill-mode: both, none",
+            "animation-play-state: running, running",
+        ]
+    );
+    assert_eq!(CombinedPropertyType::Animation.collapse(&longhands).unwrap(), "0.3s ease-in 1s infinite alternate both slide, 2s fade");
+    assert!(CombinedPropertyType::Animation.expand("1s 2s 3s").is_err());
+    assert!(CombinedPropertyType::Animation.expand("none, slide").is_err());
+}
+
+#[test]
+fn test_parse_transition_property() {
+    use crate::tokenizer::Tokenizer;
+
+    let parse = |s: &str| {
+        let tokens = Tokenizer::tokenize(s);
+        parse_property(PType::TransitionProperty, &mut ValueParser::new(&tokens)).map(|p| p.to_string())
+    };
+    assert_eq!(parse("width, MARGIN, all").as_deref(), Ok("transition-property: width, margin, all"));
+    assert_eq!(parse("none").as_deref(), Ok("transition-property: none"));
+    assert_eq!(parse("none, width"), Err(()));
+    assert_eq!(parse("colour"), Err(()));
+    assert!(StyleTransitionProperty::Shorthand(CombinedPropertyType::Margin).contains(PType::MarginLeft));
+}
+
+#[test]
+fn test_parse_transform() {
+    use crate::tokenizer::Tokenizer;
+
+    let parse = |s: &str| {
+        let tokens = Tokenizer::tokenize(s);
+        parse_property(PType::Transform, &mut ValueParser::new(&tokens)).map(|p| p.to_string())
+    };
+    // skew angles are stored in degrees
+    assert_eq!(parse("skewX(10deg)").as_deref(), Ok("transform: skewX(10deg)"));
+    assert_eq!(parse("skew(0.5turn, -0.25TURN)").as_deref(), Ok("transform: skew(180deg, -90deg)"));
+    assert_eq!(parse("skewY(1rad)").as_deref(), Ok("transform: skewY(57.29578deg)"));
+    assert_eq!(parse("skew(0)").as_deref(), Ok("transform: skew(0deg, 0deg)"));
+    assert_eq!(parse("skewX(10)"), Err(()));
+    assert_eq!(parse("skewX(10%)"), Err(()));
+    assert_eq!(parse("skew(10deg, 5deg, 1deg)"), Err(()));
+}
+
+#[test]
+fn test_parse_timing_function() {
+    use crate::tokenizer::Tokenizer;
+
+    let parse = |s: &str| {
+        let tokens = Tokenizer::tokenize(s);
+        let mut input = ValueParser::new(&tokens);
+        parse_timing_function(&mut input).and_then(|f| input.expect_exhausted().map(|()| f.to_string()))
+    };
+    for (css, expected) in [
+        ("EASE-IN", "ease-in"),
+        ("cubic-bezier(0.1, -0.6, 0.2, 1.5)", "cubic-bezier(0.1, -0.6, 0.2, 1.5)"),
+        ("step-start", "steps(1, jump-start)"),
+        ("steps(3, end)", "steps(3)"),
+        ("steps(2, jump-none)", "steps(2, jump-none)"),
+        ("linear(0, 0.5 25% 75%, 1)", "linear(0 0%, 0.5 25%, 0.5 75%, 1 100%)"),
+        ("linear(0, 0.2, 0.3, 40% 0.6, 1)", "linear(0 0%, 0.2 13.333%, 0.3 26.667%, 0.6 40%, 1 100%)"),
+        ("linear(0 50%, 1 20%)", "linear(0 50%, 1 50%)"),
+    ] {
+        assert_eq!(parse(css).as_deref(), Ok(expected), "{}", css);
+        assert_eq!(parse(expected).as_deref(), Ok(expected), "{}", expected);
+    }
+    for invalid in ["cubic-bezier(1.1, 0, 1, 1)", "cubic-bezier(0, 0, 1)", "steps(0)", "steps(2.5)", "steps(1, jump-none)", "linear(1)", "linear(0 1, 1)", "bounce"] {
+        assert_eq!(parse(invalid), Err(()), "{}", invalid);
+    }
+
+    let animation = CombinedPropertyType::Animation.expand("slide 1s cubic-bezier(0.1, 0.7, 1, 0.1), fade 2s steps(4, start)").unwrap();
+    assert_eq!(
+        CombinedPropertyType::Animation.collapse(&animation).as_deref(),
+        Some("1s cubic-bezier(0.1, 0.7, 1, 0.1) slide, 2s steps(4, jump-start) fade")
+    );
+}
@@ -1,17 +1,45 @@
-use std::fmt;
+use std::{fmt, str::FromStr};
 
-use crate::property::Property;
+use crate::animation::KeyframesRule;
+use crate::matching::{self, Element, ElementState};
+use crate::media::{MediaEnvironment, MediaQueryList};
+use crate::parser::{self, CssParseError};
+use crate::property::{write_css_string, CombinedPropertyType, PType, Property};
 
 /// Css stylesheet - contains a parsed CSS stylesheet in "rule blocks",
 /// i.e. blocks of key-value pairs associated with a selector path.
 ///
 /// One CSS stylesheet can hold more than one sub-stylesheet:
 /// For example, when overriding native styles, the `.sort_by_specificy()` function
-/// should not mix the two stylesheets during sorting.
+/// should not mix the two stylesheets during sorting. Native styles should instead be
+/// loaded as a separate stylesheet with a different `origin`, or be put into an `@layer`.
 #[derive(Debug, Default, PartialEq, PartialOrd, Clone)]
 pub struct Stylesheet {
     /// The style rules making up the document - for example, de-duplicated CSS rules
     pub rules: Vec<RuleBlock>,
+    /// Where the stylesheet comes from, `Origin::Author` for parsed stylesheets
+    pub origin: Origin,
+    /// Names of the cascade layers, in the order they were declared (i.e. `@layer base, theme;`).
+    /// Sub-layers use their full name (`theme.dark`) and are declared after their parent layer.
+    pub layers: Vec<String>,
+    /// The `@keyframes` rules, in the order they appear in the stylesheet
+    pub keyframes: Vec<KeyframesRule>,
+}
+
+/// Origin of a stylesheet, which decides (before the specificity) which declarations win.
+///
+/// Normal declarations of a later origin override the ones of an earlier origin, for
+/// `!important` declarations the order is reversed: `UserAgent < User < Author < Author !important
+/// < User !important < UserAgent !important`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum Origin {
+    /// Built-in default styles, i.e. the native widget theme
+    UserAgent,
+    /// Styles configured by the user, i.e. accessibility overrides
+    User,
+    /// Styles of the application
+    #[default]
+    Author,
 }
 
 impl Stylesheet {
@@ -20,23 +48,342 @@ impl Stylesheet {
         Default::default()
     }
 
+    /// Parses a stylesheet from CSS source text, i.e. `div { width: 100px; }`
+    pub fn parse(css: &str) -> Result<Self, CssParseError> {
+        parser::parse_stylesheet(css)
+    }
+
+    /// Parses a stylesheet from CSS source text, skipping invalid declarations and rules
+    /// instead of failing. Returns the (partial) stylesheet and the skipped errors.
+    pub fn parse_with_warnings(css: &str) -> (Self, Vec<CssParseError>) {
+        parser::parse_stylesheet_with_warnings(css)
+    }
+
     /// Sort the style rules by their weight, so that the rules are applied in the correct order.
     /// Should always be called when a new style is loaded from an external source.
+    ///
+    /// Rules are sorted by their cascade layer first (layered rules before unlayered ones),
+    /// then by the specificity of their path. Rules with the same specificity keep the
+    /// order of their `source_index`.
+    ///
+    /// This is not the full cascade order: the origin of the stylesheet and `!important`
+    /// declarations are only taken into account by `ComputedStyle::compute`.
     pub fn sort_by_specificity(&mut self) {
+        let layers = sort_layers(self.layers.iter().map(|l| l.as_str()));
+        let layer_rank = |rule: &RuleBlock| layer_rank(&layers, rule.layer.as_deref());
         self.rules.sort_by(|a, b| {
-            get_specificity(&a.path)
-            .cmp(&get_specificity(&b.path))
+            layer_rank(a).cmp(&layer_rank(b))
+            .then_with(|| get_specificity(&a.path).cmp(&get_specificity(&b.path)))
+            .then_with(|| a.source_index.cmp(&b.source_index))
         });
     }
+
+    /// Returns the rules whose path depends on one of the `changed` states, i.e. the rules
+    /// that have to be re-matched after the state of an element changed from `old` to `new`
+    /// (`changed = old ^ new`). Since paths can contain combinators, the rules have to be
+    /// re-matched against the element, its descendants and its following siblings.
+    ///
+    /// `:focus-within` and `:has()` depend on the state of descendants (and `:has()` on the
+    /// state of siblings), `:nth-child(An+B of S)` on the state of all siblings: if a returned
+    /// rule contains one of these, the ancestors of the element and their siblings (including
+    /// the preceding ones) have to be re-matched as well.
+    pub fn rules_depending_on(&self, changed: ElementState) -> impl Iterator<Item = &RuleBlock> {
+        self.rules.iter().filter(move |rule| rule.path.state_dependencies().intersects(changed))
+    }
+
+    /// Returns a copy of the stylesheet with only the rules whose `@media` queries match the
+    /// environment, with their media queries removed. `ComputedStyle::compute` ignores media
+    /// queries, so this should be called again whenever the environment changes.
+    pub fn evaluate_media(&self, env: &MediaEnvironment) -> Stylesheet {
+        let rules = self
+            .rules
+            .iter()
+            .filter(|rule| rule.matches_media(env))
+            .map(|rule| RuleBlock { media: Vec::new(), ..rule.clone() })
+            .collect();
+        let keyframes = self
+            .keyframes
+            .iter()
+            .filter(|rule| rule.matches_media(env))
+            .map(|rule| KeyframesRule { media: Vec::new(), ..rule.clone() })
+            .collect();
+        Stylesheet { rules, origin: self.origin, layers: self.layers.clone(), keyframes }
+    }
+
+    /// Returns the `@keyframes` rule with the given name: if there are multiple, the one in the
+    /// cascade layer with the highest precedence, then the last one wins. Ignores `@media` queries.
+    pub fn find_keyframes(&self, name: &str) -> Option<&KeyframesRule> {
+        let layers = sort_layers(self.layers.iter().map(|l| l.as_str()));
+        self.keyframes
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| rule.name == name)
+            .max_by_key(|(i, rule)| (layer_rank(&layers, rule.layer.as_deref()), *i))
+            .map(|(_, rule)| rule)
+    }
+
+    /// Adds a cascade layer (and its parent layers) to `layers`, if it hasn't been declared yet
+    pub fn declare_layer(&mut self, name: &str) {
+        let mut end = 0;
+        for part in name.split('.') {
+            end += part.len();
+            if !self.layers.iter().any(|l| *l == name[..end]) {
+                self.layers.push(name[..end].to_string());
+            }
+            end += 1;
+        }
+    }
+
+    /// Returns a value that writes the stylesheet like `Display` does, but with the longhands
+    /// collapsed into shorthands where possible, i.e. `padding: 5px` instead of four `padding-*` declarations
+    pub fn collapse_shorthands(&self) -> CollapseShorthands<'_, Self> {
+        CollapseShorthands(self)
+    }
+}
+
+/// Writes the stylesheet as CSS text: `{}` writes minified CSS, `{:#}` writes pretty-printed CSS
+/// with one declaration per line.
+impl fmt::Display for Stylesheet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rules = self.rules.iter().map(|rule| (rule.layer.as_deref(), rule.media.as_slice(), rule as &dyn fmt::Display));
+        let keyframes = self.keyframes.iter().map(|rule| (rule.layer.as_deref(), rule.media.as_slice(), rule as &dyn fmt::Display));
+        write_rules(f, &self.layers, rules.chain(keyframes))
+    }
 }
 
-/// Returns specificity of the given css path. Further information can be found on
-/// [the w3 website](http://www.w3.org/TR/selectors/#specificity).
-fn get_specificity(path: &Path) -> (usize, usize, usize, usize) {
-    let id_count = path.selectors.iter().filter(|x|     if let PathSelector::Id(_) = x {     true } else { false }).count();
-    let class_count = path.selectors.iter().filter(|x|  if let PathSelector::Class(_) = x {  true } else { false }).count();
-    let div_count = path.selectors.iter().filter(|x|    if let PathSelector::Type(_) = x {   true } else { false }).count();
-    (id_count, class_count, div_count, path.selectors.len())
+/// Returns the precedence of a cascade layer (higher wins), `layers` are the layers sorted
+/// by `sort_layers()`. Unlayered rules (`None`) win over all layers.
+fn layer_rank(layers: &[&str], layer: Option<&str>) -> usize {
+    match layer {
+        Some(layer) => layers.iter().position(|l| *l == layer).unwrap_or(layers.len()),
+        None => layers.len(),
+    }
+}
+
+/// Writes the `@layer` statement declaring the layer order, then the rules. Consecutive
+/// rules of the same layer are written into one `@layer name { }` block, rules with media
+/// queries are written into nested `@media queries { }` blocks inside of it.
+fn write_rules<'a, R: fmt::Display>(
+    f: &mut fmt::Formatter,
+    layers: &[String],
+    rules: impl Iterator<Item = (Option<&'a str>, &'a [MediaQueryList], R)>,
+) -> fmt::Result {
+    let pretty = f.alternate();
+    let mut is_first = true;
+    if !layers.is_empty() {
+        write!(f, "@layer {};", layers.join(if pretty { ", " } else { "," }))?;
+        if pretty {
+            writeln!(f)?;
+        }
+        is_first = false;
+    }
+
+    // headers of the currently open blocks, outermost first
+    let mut open_blocks: Vec<String> = Vec::new();
+    for (layer, media, rule) in rules {
+        let blocks = layer
+            .map(|name| format!("@layer {}", name))
+            .into_iter()
+            .chain(media.iter().map(|queries| format!("@media {}", queries)))
+            .collect::<Vec<_>>();
+        let common = open_blocks.iter().zip(blocks.iter()).take_while(|(a, b)| a == b).count();
+
+        while open_blocks.len() > common {
+            open_blocks.pop();
+            if pretty {
+                writeln!(f, "{}}}", "    ".repeat(open_blocks.len()))?;
+            } else {
+                write!(f, "}}")?;
+            }
+        }
+        for header in &blocks[common..] {
+            if pretty {
+                if !is_first {
+                    writeln!(f)?;
+                }
+                writeln!(f, "{}{} {{", "    ".repeat(open_blocks.len()), header)?;
+                is_first = true;
+            } else {
+                write!(f, "{}{{", header)?;
+            }
+            open_blocks.push(header.clone());
+        }
+
+        if pretty {
+            if !is_first {
+                writeln!(f)?;
+            }
+            // rules inside of a block are indented by one more level
+            let indent = "    ".repeat(open_blocks.len());
+            for line in format!("{:#}", rule).lines() {
+                if line.is_empty() {
+                    writeln!(f)?;
+                } else {
+                    writeln!(f, "{}{}", indent, line)?;
+                }
+            }
+        } else {
+            write!(f, "{}", rule)?;
+        }
+        is_first = false;
+    }
+
+    while open_blocks.pop().is_some() {
+        if pretty {
+            writeln!(f, "{}}}", "    ".repeat(open_blocks.len()))?;
+        } else {
+            write!(f, "}}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Sorts the names of cascade layers (in the order they were declared) by their precedence,
+/// lowest first: sub-layers come before their parent layer, i.e. `[a, a.b, c]` => `[a.b, a, c]`
+pub(crate) fn sort_layers<'a>(declared: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    fn push_layer<'a>(name: &'a str, declared: &[&'a str], sorted: &mut Vec<&'a str>) {
+        let children = declared.iter().filter(|l| l.rsplit_once('.').map(|(parent, _)| parent) == Some(name));
+        for child in children {
+            push_layer(child, declared, sorted);
+        }
+        sorted.push(name);
+    }
+
+    let mut unique = Vec::new();
+    for name in declared {
+        if !unique.contains(&name) {
+            unique.push(name);
+        }
+    }
+    let mut sorted = Vec::with_capacity(unique.len());
+    for name in unique.iter().filter(|l| !l.contains('.')) {
+        push_layer(name, &unique, &mut sorted);
+    }
+    sorted
+}
+
+/// Writes a `Stylesheet` or `RuleBlock` with its longhands collapsed into shorthands,
+/// created by `Stylesheet::collapse_shorthands()` and `RuleBlock::collapse_shorthands()`
+#[derive(Debug, Copy, Clone)]
+pub struct CollapseShorthands<'a, T>(&'a T);
+
+impl<'a> fmt::Display for CollapseShorthands<'a, Stylesheet> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rules = self.0.rules.iter().map(|rule| (rule.layer.as_deref(), rule.media.as_slice(), Box::new(rule.collapse_shorthands()) as Box<dyn fmt::Display>));
+        let keyframes = self.0.keyframes.iter().map(|rule| (rule.layer.as_deref(), rule.media.as_slice(), Box::new(rule) as Box<dyn fmt::Display>));
+        write_rules(f, &self.0.layers, rules.chain(keyframes))
+    }
+}
+
+impl<'a> fmt::Display for CollapseShorthands<'a, RuleBlock> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_rule(f, &self.0.path, self.0.collapsed_declarations().iter())
+    }
+}
+
+/// Shorthands that the serializer collapses longhands into, in order of preference
+/// (a `border` replaces the four `border-top` / `border-right` / ... shorthands)
+const COLLAPSIBLE_SHORTHANDS: [CombinedPropertyType; 10] = [
+    CombinedPropertyType::Border,
+    CombinedPropertyType::BorderTop,
+    CombinedPropertyType::BorderRight,
+    CombinedPropertyType::BorderBottom,
+    CombinedPropertyType::BorderLeft,
+    CombinedPropertyType::BorderRadius,
+    CombinedPropertyType::Margin,
+    CombinedPropertyType::Padding,
+    CombinedPropertyType::Overflow,
+    CombinedPropertyType::Animation,
+];
+
+/// Declaration of a rule when serializing
+enum RuleDeclaration<'a> {
+    Declaration(&'a Declaration),
+    /// Written with a trailing `!important`
+    Important(&'a Declaration),
+    /// Shorthand and its value, i.e. `margin` and `5px 10px`
+    Shorthand(CombinedPropertyType, String),
+}
+
+impl<'a> fmt::Display for RuleDeclaration<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleDeclaration::Declaration(d) => write!(f, "{}", d),
+            RuleDeclaration::Important(d) => write!(f, "{} !important", d),
+            RuleDeclaration::Shorthand(shorthand, value) => write!(f, "{}: {}", shorthand.to_str(), value),
+        }
+    }
+}
+
+impl FromStr for Stylesheet {
+    type Err = CssParseError;
+
+    fn from_str(css: &str) -> Result<Self, Self::Err> {
+        Self::parse(css)
+    }
+}
+
+/// Specificity of a selector, see [the w3 website](http://www.w3.org/TR/selectors/#specificity).
+///
+/// Compares `ids` first, then `classes`, then `types`: more specific selectors are greater.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Specificity {
+    /// Number of ID selectors
+    pub ids: usize,
+    /// Number of class, attribute and pseudo-class selectors
+    pub classes: usize,
+    /// Number of type selectors
+    pub types: usize,
+}
+
+impl Specificity {
+    pub const fn new(ids: usize, classes: usize, types: usize) -> Self {
+        Specificity { ids, classes, types }
+    }
+}
+
+impl ::std::ops::Add for Specificity {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Specificity::new(self.ids + other.ids, self.classes + other.classes, self.types + other.types)
+    }
+}
+
+/// Returns specificity of the given css path. `*` and combinators don't count,
+/// pseudo-elements count like type selectors.
+///
+/// `:is()`, `:not()` and `:has()` count as their most specific argument, `:where()` counts as zero.
+pub(crate) fn get_specificity(path: &Path) -> Specificity {
+    let pseudo_element = match path.pseudo_element {
+        Some(_) => Specificity::new(0, 0, 1),
+        None => Specificity::default(),
+    };
+    path.selectors.iter().fold(pseudo_element, |specificity, selector| {
+        specificity + match selector {
+            PathSelector::Id(_) => Specificity::new(1, 0, 0),
+            PathSelector::Class(_) | PathSelector::Attribute(_) => Specificity::new(0, 1, 0),
+            PathSelector::Type(_) => Specificity::new(0, 0, 1),
+            PathSelector::PseudoSelector(pseudo) => get_pseudo_specificity(pseudo),
+            PathSelector::Global | PathSelector::DirectChildren | PathSelector::Children |
+            PathSelector::AdjacentSibling | PathSelector::GeneralSibling => Specificity::default(),
+        }
+    })
+}
+
+fn get_pseudo_specificity(selector: &PathPseudoSelector) -> Specificity {
+    use self::PathPseudoSelector::*;
+    match selector {
+        Is(list) | Not(list) | Has(list) => list.iter().map(get_specificity).max().unwrap_or_default(),
+        Where(_) => Specificity::default(),
+        // `:nth-child(An+B of S)` counts as a pseudo-class plus the most specific selector of `S`
+        NthChild(_, of) | NthLastChild(_, of) => {
+            Specificity::new(0, 1, 0) + of.iter().map(get_specificity).max().unwrap_or_default()
+        }
+        FirstChild | LastChild | OnlyChild | FirstOfType | LastOfType | OnlyOfType |
+        NthOfType(_) | NthLastOfType(_) | Empty | Root | Hover | Active | Focus | FocusVisible | FocusWithin |
+        Enabled | Disabled | Checked | Indeterminate | ReadOnly | PlaceholderShown | Visited | Target => Specificity::new(0, 1, 0),
+    }
 }
 
 /// One block of rules that applies a bunch of rules to a "path" in the style, i.e.
@@ -48,6 +395,101 @@ pub struct RuleBlock {
     /// `"justify-content: center"` =>
     /// `CssDeclaration::Static(CssProperty::JustifyContent(LayoutJustifyContent::Center))`
     pub declarations: Vec<Declaration>,
+    /// The `!important` declarations of the rule, i.e. `width: 5px !important`: they win over
+    /// all normal declarations of the same origin (see `Origin`)
+    pub important_declarations: Vec<Declaration>,
+    /// Full name of the cascade layer (`@layer`) the rule is in, `None` for unlayered rules
+    pub layer: Option<String>,
+    /// Query lists of the (nested) `@media` blocks the rule is in: the rule only applies
+    /// if all of them match. Empty for rules that always apply.
+    pub media: Vec<MediaQueryList>,
+    /// Position of the rule in the source stylesheet: if two rules have the same specificity,
+    /// the rule with the higher index wins. Stays the same when the rules are sorted.
+    pub source_index: usize,
+}
+
+/// Writes the rule as CSS text, `{:#}` writes one declaration per line
+impl fmt::Display for RuleBlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let declarations = self.declarations.iter().map(RuleDeclaration::Declaration).collect();
+        write_rule(f, &self.path, self.with_important_declarations(declarations).iter())
+    }
+}
+
+fn write_rule<D: fmt::Display>(f: &mut fmt::Formatter, path: &Path, declarations: impl Iterator<Item = D>) -> fmt::Result {
+    if f.alternate() {
+        writeln!(f, "{} {{", path)?;
+        for declaration in declarations {
+            writeln!(f, "    {};", declaration)?;
+        }
+        write!(f, "}}")
+    } else {
+        write!(f, "{}{{", path)?;
+        for (i, declaration) in declarations.enumerate() {
+            if i != 0 {
+                write!(f, ";")?;
+            }
+            write!(f, "{}", declaration)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl RuleBlock {
+    /// Returns whether all the `@media` query lists of the rule match the environment
+    pub fn matches_media(&self, env: &MediaEnvironment) -> bool {
+        self.media.iter().all(|queries| queries.matches(env))
+    }
+
+    /// Returns a value that writes the rule like `Display` does, but with the longhands
+    /// collapsed into shorthands where possible, see `Stylesheet::collapse_shorthands()`
+    pub fn collapse_shorthands(&self) -> CollapseShorthands<'_, Self> {
+        CollapseShorthands(self)
+    }
+
+    /// Replaces the longhands of every shorthand in `COLLAPSIBLE_SHORTHANDS` with the shorthand,
+    /// if each longhand is set exactly once (and not dynamically) in this rule. The shorthand takes
+    /// the place of its first longhand.
+    fn collapsed_declarations(&self) -> Vec<RuleDeclaration<'_>> {
+        let mut declarations = self.declarations.iter().map(|d| Some(RuleDeclaration::Declaration(d))).collect::<Vec<_>>();
+
+        for shorthand in COLLAPSIBLE_SHORTHANDS.iter() {
+            let mut indices = Vec::new();
+            let mut properties = Vec::new();
+            for longhand in shorthand.longhands() {
+                let mut matching = declarations.iter().enumerate().filter_map(|(i, d)| match d {
+                    Some(RuleDeclaration::Declaration(d)) if d.get_type() == *longhand => Some((i, *d)),
+                    _ => None,
+                });
+                match (matching.next(), matching.next()) {
+                    (Some((i, Declaration::Static(p))), None) => {
+                        indices.push(i);
+                        properties.push(p.clone());
+                    }
+                    _ => break,
+                }
+            }
+            if indices.len() != shorthand.longhands().len() {
+                continue;
+            }
+
+            if let Some(value) = shorthand.collapse(&properties) {
+                let first = indices.iter().copied().min().unwrap_or(0);
+                for &i in indices.iter() {
+                    declarations[i] = None;
+                }
+                declarations[first] = Some(RuleDeclaration::Shorthand(*shorthand, value));
+            }
+        }
+
+        self.with_important_declarations(declarations.into_iter().flatten().collect())
+    }
+
+    /// Appends the `!important` declarations (which are never collapsed) to the normal declarations
+    fn with_important_declarations<'b>(&'b self, mut declarations: Vec<RuleDeclaration<'b>>) -> Vec<RuleDeclaration<'b>> {
+        declarations.extend(self.important_declarations.iter().map(RuleDeclaration::Important));
+        declarations
+    }
 }
 
 /// Represents a full CSS path (i.e. the "div#id.class" selector belonging to
@@ -64,6 +506,45 @@ pub struct RuleBlock {
 #[derive(Debug, Clone, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Path {
     pub selectors: Vec<PathSelector>,
+    /// `::before` in `div.a::before`: the rule styles the pseudo-element of the selected element
+    pub pseudo_element: Option<PseudoElement>,
+}
+
+impl Path {
+    /// Parses a comma-separated list of selectors, i.e. `div > p, .a + .b`
+    pub fn parse_list(selectors: &str) -> Result<Vec<Self>, CssParseError> {
+        parser::parse_selectors(selectors)
+    }
+
+    /// Returns whether the path selects the given element. The selectors are matched
+    /// right-to-left, starting at the element and walking up (or back) the tree.
+    ///
+    /// The `pseudo_element` is ignored: `p::before` matches the `p` elements.
+    pub fn matches<E: Element>(&self, element: &E) -> bool {
+        matching::matches_path(self, element)
+    }
+
+    /// Returns the element states (`:hover`, `:checked`, etc.) the path depends on, including
+    /// the states of other elements in the path (i.e. `.menu:hover > .item`). If the state of an
+    /// element changes, only the paths that depend on one of the changed states have to be re-matched.
+    pub fn state_dependencies(&self) -> ElementState {
+        matching::state_dependencies(self)
+    }
+
+    /// Returns the specificity of the path, which decides which rule wins if multiple rules
+    /// set the same property on an element
+    pub fn specificity(&self) -> Specificity {
+        get_specificity(self)
+    }
+}
+
+/// Parses a single selector, i.e. `div#id.class > p:hover`
+impl FromStr for Path {
+    type Err = CssParseError;
+
+    fn from_str(selector: &str) -> Result<Self, Self::Err> {
+        parser::parse_selector(selector)
+    }
 }
 
 impl fmt::Display for Path {
@@ -71,13 +552,63 @@ impl fmt::Display for Path {
         for selector in self.selectors.iter() {
             write!(f, "{}", selector)?;
         }
+        if let Some(pseudo_element) = self.pseudo_element {
+            write!(f, "{}", pseudo_element)?;
+        }
         Ok(())
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// A part of an element that can be styled separately, i.e. the `::before` in `p::before`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PseudoElement {
+    /// `::before` - generated content before the content of the element, see `Property::Content`
+    Before,
+    /// `::after` - generated content after the content of the element
+    After,
+    /// `::placeholder` - the placeholder text of a text input
+    Placeholder,
+    /// `::selection` - the selected text of the element
+    Selection,
+}
+
+impl PseudoElement {
+    /// Parses the name of the pseudo-element (without the `::`), i.e. `"before"` => `PseudoElement::Before`
+    #[allow(clippy::should_implement_trait, reason = "returns an `Option`, there is no error to report")]
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "before" => Some(PseudoElement::Before),
+            "after" => Some(PseudoElement::After),
+            "placeholder" => Some(PseudoElement::Placeholder),
+            "selection" => Some(PseudoElement::Selection),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the pseudo-element can also be written with one colon (`:before`), for compatibility with CSS 2
+    pub const fn allows_single_colon(&self) -> bool {
+        match self {
+            PseudoElement::Before | PseudoElement::After => true,
+            PseudoElement::Placeholder | PseudoElement::Selection => false,
+        }
+    }
+}
+
+impl fmt::Display for PseudoElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PseudoElement::Before => write!(f, "::before"),
+            PseudoElement::After => write!(f, "::after"),
+            PseudoElement::Placeholder => write!(f, "::placeholder"),
+            PseudoElement::Selection => write!(f, "::selection"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
 pub enum PathSelector {
     /// Represents the `*` selector
+    #[default]
     Global,
     /// `div`, `p`, etc.
     Type(NodeTypeTag),
@@ -85,18 +616,18 @@ pub enum PathSelector {
     Class(String),
     /// `#something`
     Id(String),
+    /// `[something]`, `[something="value"]`, etc.
+    Attribute(AttributeSelector),
     /// `:something`
     PseudoSelector(PathPseudoSelector),
     /// Represents the `>` selector
     DirectChildren,
     /// Represents the ` ` selector
     Children,
-}
-
-impl Default for PathSelector {
-    fn default() -> Self {
-        PathSelector::Global
-    }
+    /// Represents the `+` selector: the next sibling of the preceding element
+    AdjacentSibling,
+    /// Represents the `~` selector: any following sibling of the preceding element
+    GeneralSibling,
 }
 
 impl fmt::Display for PathSelector {
@@ -107,51 +638,247 @@ impl fmt::Display for PathSelector {
             Type(n) => write!(f, "{}", n),
             Class(c) => write!(f, ".{}", c),
             Id(i) => write!(f, "#{}", i),
+            Attribute(a) => write!(f, "{}", a),
             PseudoSelector(p) => write!(f, ":{}", p),
             DirectChildren => write!(f, ">"),
             Children => write!(f, " "),
+            AdjacentSibling => write!(f, "+"),
+            GeneralSibling => write!(f, "~"),
         }
     }
 }
 
+/// Selects elements by an attribute, i.e. `[data-state^="open" i]`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AttributeSelector {
+    /// Name of the attribute, i.e. `data-state`
+    pub name: String,
+    pub operator: AttributeOperator,
+    /// Value to compare the attribute against, empty for `AttributeOperator::Exists`
+    pub value: String,
+    pub case: AttributeCase,
+}
+
+impl fmt::Display for AttributeSelector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}", self.name)?;
+        if self.operator != AttributeOperator::Exists {
+            write!(f, "{}", self.operator)?;
+            write_css_string(f, &self.value)?;
+            match self.case {
+                AttributeCase::Default => {}
+                AttributeCase::Insensitive => write!(f, " i")?,
+                AttributeCase::Sensitive => write!(f, " s")?,
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+/// How the attribute value of an `AttributeSelector` is compared
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AttributeOperator {
+    /// `[attr]` - the attribute is present, regardless of its value
+    Exists,
+    /// `[attr=value]` - exactly `value`
+    Equals,
+    /// `[attr~=value]` - a whitespace-separated list of words, one of which is `value`
+    Includes,
+    /// `[attr|=value]` - exactly `value` or starting with `value-`
+    DashMatch,
+    /// `[attr^=value]` - starts with `value`
+    Prefix,
+    /// `[attr$=value]` - ends with `value`
+    Suffix,
+    /// `[attr*=value]` - contains `value`
+    Substring,
+}
+
+impl fmt::Display for AttributeOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::AttributeOperator::*;
+        match self {
+            Exists => Ok(()),
+            Equals => write!(f, "="),
+            Includes => write!(f, "~="),
+            DashMatch => write!(f, "|="),
+            Prefix => write!(f, "^="),
+            Suffix => write!(f, "$="),
+            Substring => write!(f, "*="),
+        }
+    }
+}
+
+/// The `i` / `s` flag of an attribute selector, i.e. `[type="a" i]`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AttributeCase {
+    /// No flag: the value is compared case-sensitively
+    Default,
+    /// `i` - the value is compared ASCII case-insensitively
+    Insensitive,
+    /// `s` - the value is compared case-sensitively
+    Sensitive,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum PathPseudoSelector {
-    /// `:first`
-    First,
-    /// `:last`
-    Last,
-    /// `:nth-child`
-    NthChild(NthChildSelector),
+    /// `:first-child`
+    FirstChild,
+    /// `:last-child`
+    LastChild,
+    /// `:only-child`
+    OnlyChild,
+    /// `:first-of-type`
+    FirstOfType,
+    /// `:last-of-type`
+    LastOfType,
+    /// `:only-of-type`
+    OnlyOfType,
+    /// `:nth-child(2n + 1)` or `:nth-child(2n + 1 of .a, .b)`: if the `of` selector list is not
+    /// empty, only the siblings that match one of the selectors are counted
+    NthChild(NthChildSelector, Vec<Path>),
+    /// `:nth-last-child(2n + 1)` or `:nth-last-child(2n + 1 of .a)`, counting from the last sibling
+    NthLastChild(NthChildSelector, Vec<Path>),
+    /// `:nth-of-type(2n + 1)` - only counts the siblings with the same node type
+    NthOfType(NthChildSelector),
+    /// `:nth-last-of-type(2n + 1)`
+    NthLastOfType(NthChildSelector),
+    /// `:empty` - element has no children
+    Empty,
+    /// `:root` - element has no parent
+    Root,
     /// `:hover` - mouse is over element
     Hover,
     /// `:active` - mouse is pressed and over element
     Active,
     /// `:focus` - element has received focus
     Focus,
+    /// `:focus-visible` - element has received focus and the focus should be drawn
+    FocusVisible,
+    /// `:focus-within` - element or one of its descendants has received focus
+    FocusWithin,
+    /// `:enabled` - element is not disabled
+    Enabled,
+    /// `:disabled` - element can't be interacted with
+    Disabled,
+    /// `:checked` - checkbox, radio button or toggle is on
+    Checked,
+    /// `:indeterminate` - checkbox or progress bar is neither on nor off
+    Indeterminate,
+    /// `:read-only` - content of the element can't be edited
+    ReadOnly,
+    /// `:placeholder-shown` - text input shows its placeholder text
+    PlaceholderShown,
+    /// `:visited` - link has been visited
+    Visited,
+    /// `:target` - element is the target of the current navigation
+    Target,
+    /// `:is(a, b)` - element matches any of the selectors
+    Is(Vec<Path>),
+    /// `:not(a, b)` - element matches none of the selectors
+    Not(Vec<Path>),
+    /// `:where(a, b)` - like `:is()`, but doesn't add to the specificity
+    Where(Vec<Path>),
+    /// `:has(> a, b)` - any of the relative selectors matches, starting at the element.
+    /// Selectors without a leading combinator (`b`) select the descendants of the element.
+    Has(Vec<Path>),
 }
 
 impl fmt::Display for PathPseudoSelector {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::PathPseudoSelector::*;
         match &self {
-            First => write!(f, "first"),
-            Last => write!(f, "last"),
-            NthChild(u) => write!(f, "nth-child({})", u),
+            FirstChild => write!(f, "first-child"),
+            LastChild => write!(f, "last-child"),
+            OnlyChild => write!(f, "only-child"),
+            FirstOfType => write!(f, "first-of-type"),
+            LastOfType => write!(f, "last-of-type"),
+            OnlyOfType => write!(f, "only-of-type"),
+            NthChild(nth, of) => write_nth_function(f, "nth-child", nth, of),
+            NthLastChild(nth, of) => write_nth_function(f, "nth-last-child", nth, of),
+            NthOfType(nth) => write!(f, "nth-of-type({})", nth),
+            NthLastOfType(nth) => write!(f, "nth-last-of-type({})", nth),
+            Empty => write!(f, "empty"),
+            Root => write!(f, "root"),
             Hover => write!(f, "hover"),
             Active => write!(f, "active"),
             Focus => write!(f, "focus"),
+            FocusVisible => write!(f, "focus-visible"),
+            FocusWithin => write!(f, "focus-within"),
+            Enabled => write!(f, "enabled"),
+            Disabled => write!(f, "disabled"),
+            Checked => write!(f, "checked"),
+            Indeterminate => write!(f, "indeterminate"),
+            ReadOnly => write!(f, "read-only"),
+            PlaceholderShown => write!(f, "placeholder-shown"),
+            Visited => write!(f, "visited"),
+            Target => write!(f, "target"),
+            Is(list) => write_selector_function(f, "is", list),
+            Not(list) => write_selector_function(f, "not", list),
+            Where(list) => write_selector_function(f, "where", list),
+            Has(list) => write_selector_function(f, "has", list),
         }
     }
 }
 
+fn write_selector_function(f: &mut fmt::Formatter, name: &str, list: &[Path]) -> fmt::Result {
+    write!(f, "{}(", name)?;
+    write_selector_list(f, list)?;
+    write!(f, ")")
+}
+
+fn write_nth_function(f: &mut fmt::Formatter, name: &str, nth: &NthChildSelector, of: &[Path]) -> fmt::Result {
+    write!(f, "{}({}", name, nth)?;
+    if !of.is_empty() {
+        write!(f, " of ")?;
+        write_selector_list(f, of)?;
+    }
+    write!(f, ")")
+}
+
+fn write_selector_list(f: &mut fmt::Formatter, list: &[Path]) -> fmt::Result {
+    for (i, path) in list.iter().enumerate() {
+        if i != 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", path)?;
+    }
+    Ok(())
+}
+
+/// The `An+B` argument of the `:nth-*` pseudo-classes: selects the elements whose
+/// (1-based) index among their siblings is `A * n + B` for any `n >= 0`
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum NthChildSelector {
-    Number(u32),
+    /// `3` - only `B`
+    Number(i32),
+    /// `even` - same as `2n`
     Even,
+    /// `odd` - same as `2n + 1`
     Odd,
+    /// `2n + 1`, `-n + 3`, `n`, etc.
     Pattern(NthChildPattern),
 }
 
+impl NthChildSelector {
+    /// Returns whether the 1-based index matches
+    pub fn matches(&self, index: i32) -> bool {
+        let (repeat, offset) = match *self {
+            NthChildSelector::Number(offset) => (0, offset),
+            NthChildSelector::Even => (2, 0),
+            NthChildSelector::Odd => (2, 1),
+            NthChildSelector::Pattern(NthChildPattern { repeat, offset }) => (repeat, offset),
+        };
+        // index = repeat * n + offset, for any n >= 0
+        // (in i64: the parser saturates huge values to i32::MIN / i32::MAX)
+        let (index, repeat, offset) = (index as i64, repeat as i64, offset as i64);
+        match repeat {
+            0 => index == offset,
+            _ => (index - offset) % repeat == 0 && (index - offset) / repeat >= 0,
+        }
+    }
+}
+
 impl fmt::Display for NthChildSelector {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::NthChildSelector::*;
@@ -159,20 +886,31 @@ impl fmt::Display for NthChildSelector {
             Number(u) => write!(f, "{}", u),
             Even => write!(f, "even"),
             Odd => write!(f, "odd"),
-            Pattern(p) => write!(f, "{}n + {}", p.repeat, p.offset),
+            Pattern(p) => {
+                write!(f, "{}n", p.repeat)?;
+                match p.offset {
+                    0 => Ok(()),
+                    o if o < 0 => write!(f, " - {}", -o),
+                    o => write!(f, " + {}", o),
+                }
+            }
         }
     }
 }
 
+/// `A` (`repeat`) and `B` (`offset`) of `An+B`
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct NthChildPattern {
-    pub repeat: u32,
-    pub offset: u32,
+    pub repeat: i32,
+    pub offset: i32,
 }
 
 /// Signifies the type (i.e. the discriminant value) of a DOM node
 /// without carrying any of its associated data
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///
+/// Tag names are case-insensitive: the common tags have their own variant (fast path),
+/// all other tags are stored as `Custom`, in ASCII lowercase (see `NodeTypeTag::new`).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum NodeTypeTag {
     Body,
     Div,
@@ -180,31 +918,61 @@ pub enum NodeTypeTag {
     P,
     Img,
     IFrame,
+    /// Any other element, i.e. `button` or `tab-bar`
+    Custom(String),
 }
 
-/// Parses the node type from a CSS string such as `"div"` => `NodeTypeTag::Div`
 impl NodeTypeTag {
+    /// Returns the tag with the given name, i.e. `"DIV"` => `NodeTypeTag::Div`,
+    /// `"Tab-Bar"` => `NodeTypeTag::Custom("tab-bar")`
+    pub fn new(name: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        match name.as_str() {
+            "body" => NodeTypeTag::Body,
+            "div" => NodeTypeTag::Div,
+            "br" => NodeTypeTag::Br,
+            "p" => NodeTypeTag::P,
+            "img" => NodeTypeTag::Img,
+            "iframe" => NodeTypeTag::IFrame,
+            _ => NodeTypeTag::Custom(name),
+        }
+    }
+
+    /// Parses the node type from a CSS string such as `"div"` => `NodeTypeTag::Div`.
+    /// Returns `None` if the string is not a valid tag name.
+    #[allow(clippy::should_implement_trait, reason = "returns an `Option`, there is no error to report")]
     pub fn from_str(css_key: &str) -> Option<Self> {
-        match css_key {
-            "body" => Some(NodeTypeTag::Body),
-            "div" => Some(NodeTypeTag::Div),
-            "p" => Some(NodeTypeTag::P),
-            "img" => Some(NodeTypeTag::Img),
-            _ => None,
+        let mut chars = css_key.chars();
+        let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || !c.is_ascii())
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii());
+        if valid { Some(NodeTypeTag::new(css_key)) } else { None }
+    }
+
+    /// Returns the name of the tag, i.e. `"div"`
+    pub fn as_str(&self) -> &str {
+        match self {
+            NodeTypeTag::Body => "body",
+            NodeTypeTag::Div => "div",
+            NodeTypeTag::Br => "br",
+            NodeTypeTag::P => "p",
+            NodeTypeTag::Img => "img",
+            NodeTypeTag::IFrame => "iframe",
+            NodeTypeTag::Custom(name) => name,
+        }
+    }
+
+    /// Compares the tag names ASCII case-insensitively, so that i.e. `Custom("DIV")` equals `Div`
+    pub fn eq_ignore_ascii_case(&self, other: &Self) -> bool {
+        match (self, other) {
+            (NodeTypeTag::Custom(name), tag) | (tag, NodeTypeTag::Custom(name)) => name.eq_ignore_ascii_case(tag.as_str()),
+            (a, b) => a == b,
         }
     }
 }
 
 impl fmt::Display for NodeTypeTag {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            NodeTypeTag::Body => write!(f, "body"),
-            NodeTypeTag::Div => write!(f, "div"),
-            NodeTypeTag::Br => write!(f, "br"),
-            NodeTypeTag::P => write!(f, "p"),
-            NodeTypeTag::Img => write!(f, "img"),
-            NodeTypeTag::IFrame => write!(f, "iframe"),
-        }
+        write!(f, "{}", self.as_str())
     }
 }
 
@@ -214,7 +982,7 @@ impl fmt::Display for NodeTypeTag {
 pub enum Declaration {
     /// Static key-value pair, such as `width: 500px`
     Static(Property),
-    /// Dynamic key-value pair with default value, such as `width: [[ my_id | 500px ]]`
+    /// Dynamic key-value pair with default value, such as `width: var(--my_id, 500px)`
     Dynamic(DynamicProperty),
 }
 
@@ -228,6 +996,19 @@ impl Declaration {
         Declaration::Dynamic(prop)
     }
 
+    /// Returns the property that is set by this declaration (the default value for dynamic properties)
+    pub const fn property(&self) -> &Property {
+        match self {
+            Declaration::Static(s) => s,
+            Declaration::Dynamic(d) => &d.default_value,
+        }
+    }
+
+    /// Returns the type of the property this declaration sets
+    pub const fn get_type(&self) -> PType {
+        self.property().get_type()
+    }
+
     /// Determines if the property will be inherited (applied to the children)
     /// during the recursive application of the style on the DOM tree
     pub fn is_inheritable(&self) -> bool {
@@ -248,11 +1029,22 @@ impl Declaration {
         }
     }
 
+    /// Returns the declaration as CSS, i.e. `width: var(--my_id, 500px)`
     pub fn to_str(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Declaration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::Declaration::*;
         match self {
-            Static(s) => format!("{:?}", s),
-            Dynamic(d) => format!("var(--{}, {:?})", d.dynamic_id, d.default_value),
+            Static(s) => write!(f, "{}", s),
+            Dynamic(d) => {
+                write!(f, "{}: var(--{}, ", d.default_value.get_type().to_str(), d.dynamic_id)?;
+                d.default_value.fmt_value(f)?;
+                write!(f, ")")
+            }
         }
     }
 }
@@ -303,10 +1095,22 @@ impl DynamicProperty {
 fn test_specificity() {
     use self::PathSelector::*;
     use std::string::ToString;
-    assert_eq!(get_specificity(&Path { selectors: vec![Id("hello".to_string())] }), (1, 0, 0, 1));
-    assert_eq!(get_specificity(&Path { selectors: vec![Class("hello".to_string())] }), (0, 1, 0, 1));
-    assert_eq!(get_specificity(&Path { selectors: vec![Type(NodeTypeTag::Div)] }), (0, 0, 1, 1));
-    assert_eq!(get_specificity(&Path { selectors: vec![Id("hello".to_string()), Type(NodeTypeTag::Div)] }), (1, 0, 1, 2));
+    assert_eq!(get_specificity(&Path { selectors: vec![Id("hello".to_string())], pseudo_element: None }), Specificity::new(1, 0, 0));
+    assert_eq!(get_specificity(&Path { selectors: vec![Class("hello".to_string())], pseudo_element: None }), Specificity::new(0, 1, 0));
+    assert_eq!(get_specificity(&Path { selectors: vec![Type(NodeTypeTag::Div)], pseudo_element: None }), Specificity::new(0, 0, 1));
+    assert_eq!(get_specificity(&Path { selectors: vec![Id("hello".to_string()), Type(NodeTypeTag::Div)], pseudo_element: None }), Specificity::new(1, 0, 1));
+    assert_eq!(get_specificity(&"div.a[data-state]".parse().unwrap()), Specificity::new(0, 2, 1));
+
+    let specificity = |selector: &str| selector.parse::<Path>().unwrap().specificity();
+    assert_eq!(specificity("p:hover:nth-child(2)"), Specificity::new(0, 2, 1));
+    assert_eq!(specificity(":is(#a, .b) p"), Specificity::new(1, 0, 1));
+    assert_eq!(specificity(":not(.a.b, div)"), Specificity::new(0, 2, 0));
+    assert_eq!(specificity("div:where(#a .b)"), Specificity::new(0, 0, 1));
+    assert_eq!(specificity(".x:has(> #a, p)"), Specificity::new(1, 1, 0));
+    assert_eq!(specificity("p:nth-child(2n of #a, .b)"), Specificity::new(1, 1, 1));
+    assert_eq!(specificity("p:nth-of-type(2n):first-child"), Specificity::new(0, 2, 1));
+    assert_eq!(specificity("p.a::before"), Specificity::new(0, 1, 2));
+    assert!(Specificity::new(1, 0, 0) > Specificity::new(0, 10, 10));
 }
 
 // Assert that order of the style items is correct
@@ -320,26 +1124,157 @@ fn test_specificity_sort() {
     let mut input_style = Stylesheet {
         rules: vec![
             // Rules are sorted from lowest-specificity to highest specificity
-            RuleBlock { path: Path { selectors: vec![Global] }, declarations: Vec::new() },
-            RuleBlock { path: Path { selectors: vec![Global, Type(Div), Class("my_class".to_string()), Id("my_id".to_string())] }, declarations: Vec::new() },
-            RuleBlock { path: Path { selectors: vec![Global, Type(Div), Id("my_id".to_string())] }, declarations: Vec::new() },
-            RuleBlock { path: Path { selectors: vec![Global, Id("my_id".to_string())] }, declarations: Vec::new() },
-            RuleBlock { path: Path { selectors: vec![Type(Div), Class("my_class".to_string()), Class("specific".to_string()), Id("my_id".to_string())] }, declarations: Vec::new() },
+            RuleBlock { source_index: 0, layer: None, media: Vec::new(), path: Path { selectors: vec![Global], pseudo_element: None }, declarations: Vec::new(), important_declarations: Vec::new() },
+            RuleBlock { source_index: 1, layer: None, media: Vec::new(), path: Path { selectors: vec![Global, Type(Div), Class("my_class".to_string()), Id("my_id".to_string())], pseudo_element: None }, declarations: Vec::new(), important_declarations: Vec::new() },
+            RuleBlock { source_index: 2, layer: None, media: Vec::new(), path: Path { selectors: vec![Global, Type(Div), Id("my_id".to_string())], pseudo_element: None }, declarations: Vec::new(), important_declarations: Vec::new() },
+            RuleBlock { source_index: 3, layer: None, media: Vec::new(), path: Path { selectors: vec![Global, Id("my_id".to_string())], pseudo_element: None }, declarations: Vec::new(), important_declarations: Vec::new() },
+            RuleBlock { source_index: 4, layer: None, media: Vec::new(), path: Path { selectors: vec![Type(Div), Class("my_class".to_string()), Class("specific".to_string()), Id("my_id".to_string())], pseudo_element: None }, declarations: Vec::new(), important_declarations: Vec::new() },
         ],
+        ..Default::default()
     };
     input_style.sort_by_specificity();
 
     let expected_style = Stylesheet {
         rules: vec![
             // Rules are sorted from lowest-specificity to highest specificity
-            RuleBlock { path: Path { selectors: vec![Global] }, declarations: Vec::new() },
-            RuleBlock { path: Path { selectors: vec![Global, Id("my_id".to_string())] }, declarations: Vec::new() },
-            RuleBlock { path: Path { selectors: vec![Global, Type(Div), Id("my_id".to_string())] }, declarations: Vec::new() },
-            RuleBlock { path: Path { selectors: vec![Global, Type(Div), Class("my_class".to_string()), Id("my_id".to_string())] }, declarations: Vec::new() },
-            RuleBlock { path: Path { selectors: vec![Type(Div), Class("my_class".to_string()), Class("specific".to_string()), Id("my_id".to_string())] }, declarations: Vec::new() },
+            RuleBlock { source_index: 0, layer: None, media: Vec::new(), path: Path { selectors: vec![Global], pseudo_element: None }, declarations: Vec::new(), important_declarations: Vec::new() },
+            RuleBlock { source_index: 3, layer: None, media: Vec::new(), path: Path { selectors: vec![Global, Id("my_id".to_string())], pseudo_element: None }, declarations: Vec::new(), important_declarations: Vec::new() },
+            RuleBlock { source_index: 2, layer: None, media: Vec::new(), path: Path { selectors: vec![Global, Type(Div), Id("my_id".to_string())], pseudo_element: None }, declarations: Vec::new(), important_declarations: Vec::new() },
+            RuleBlock { source_index: 1, layer: None, media: Vec::new(), path: Path { selectors: vec![Global, Type(Div), Class("my_class".to_string()), Id("my_id".to_string())], pseudo_element: None }, declarations: Vec::new(), important_declarations: Vec::new() },
+            RuleBlock { source_index: 4, layer: None, media: Vec::new(), path: Path { selectors: vec![Type(Div), Class("my_class".to_string()), Class("specific".to_string()), Id("my_id".to_string())], pseudo_element: None }, declarations: Vec::new(), important_declarations: Vec::new() },
         ],
+        ..Default::default()
     };
 
     assert_eq!(input_style, expected_style);
 }
 
+#[test]
+fn test_node_type_tag() {
+    assert_eq!(NodeTypeTag::from_str("br"), Some(NodeTypeTag::Br));
+    assert_eq!(NodeTypeTag::from_str("IFrame"), Some(NodeTypeTag::IFrame));
+    assert_eq!(NodeTypeTag::from_str("Tab-Bar"), Some(NodeTypeTag::Custom("tab-bar".to_string())));
+    assert_eq!(NodeTypeTag::from_str("2d"), None);
+    assert_eq!(NodeTypeTag::from_str(""), None);
+    assert!(NodeTypeTag::Custom("IMG".to_string()).eq_ignore_ascii_case(&NodeTypeTag::Img));
+    assert!(!NodeTypeTag::Custom("slider".to_string()).eq_ignore_ascii_case(&NodeTypeTag::Div));
+    assert_eq!("Tab-Bar > BR".parse::<Path>().unwrap().to_string(), "tab-bar>br");
+}
+
+// Rules with the same specificity must stay in source order, regardless of their selector count
+#[test]
+fn test_specificity_sort_equal_weight() {
+    let mut stylesheet = Stylesheet::parse("
+        div > * > .a { width: 1px; }
+        #x { width: 2px; }
+        p.c { width: 3px; }
+        .e { width: 4px; }
+        * .d p { width: 5px; }
+        p:hover { width: 6px; }
+    ").unwrap();
+    stylesheet.sort_by_specificity();
+
+    let sorted = stylesheet.rules.iter().map(|r| (r.path.to_string(), r.path.specificity(), r.source_index)).collect::<Vec<_>>();
+    assert_eq!(sorted, vec![
+        (".e".to_string(), Specificity::new(0, 1, 0), 3),
+        ("div>*>.a".to_string(), Specificity::new(0, 1, 1), 0),
+        ("p.c".to_string(), Specificity::new(0, 1, 1), 2),
+        ("* .d p".to_string(), Specificity::new(0, 1, 1), 4),
+        ("p:hover".to_string(), Specificity::new(0, 1, 1), 5),
+        ("#x".to_string(), Specificity::new(1, 0, 0), 1),
+    ]);
+
+    // sorting again does not change the order
+    let before = stylesheet.clone();
+    stylesheet.sort_by_specificity();
+    assert_eq!(stylesheet, before);
+}
+
+#[test]
+fn test_serialize_stylesheet() {
+    let css = r#"
+        div#my_id .my_class > p:hover, * {
+            color: rgba(255, 0, 0, 0.5);
+            font-family: "Times New Roman", sans-serif, url("fonts/my \"font\".ttf");
+            width: 33.333%;
+            line-height: 1.2;
+            max-width: none;
+            margin-top: 1.5em;
+            justify-content: flex-end;
+            background: repeating-linear-gradient(45deg, red, blue 30%, green), radial-gradient(circle at top left, red, blue), conic-gradient(from 10deg at 10px 20px, red, blue 90deg), url("image.png"), #123456;
+            background-position: left top, 10px center;
+            -box-shadow-left: inset 1px 2px 3px 4px red;
+            opacity: 0.5;
+            transform: matrix(1, 0, 0, 1, 10, 20) translate(10px, 20%) rotate(45deg) scale(1.5) rotate3d(1, 0, 0, 1rad) skew(10deg, 0.1turn);
+            transform-origin: left top;
+            filter: blur(5px) drop-shadow(1px 1px red) composite(arithmetic, 1, 2, 3, 4);
+            padding-top: var(--my_id, 5px);
+        }
+        div:nth-child(2n+1) { width: 5px }
+    "#;
+    let stylesheet = Stylesheet::parse(css).unwrap();
+
+    // minified and pretty-printed output must parse back to the same stylesheet
+    assert_eq!(Stylesheet::parse(&stylesheet.to_string()).unwrap(), stylesheet);
+    assert_eq!(Stylesheet::parse(&format!("{:#}", stylesheet)).unwrap(), stylesheet);
+
+    let stylesheet = Stylesheet::parse("p { width: 5px; font-family: sans-serif; } div > p { height: var(--h, 1em) }").unwrap();
+    assert_eq!(stylesheet.to_string(), "p{width: 5px;font-family: sans-serif}div>p{height: var(--h, 1em)}");
+    assert_eq!(
+        format!("{:#}", stylesheet),
+        "p {\n    width: 5px;\n    font-family: sans-serif;\n}\n\ndiv>p {\n    height: var(--h, 1em);\n}\n"
+    );
+
+    // values without a CSS syntax are written, but don't round-trip
+    use crate::property::{FontData, ScrollbarStyle, StyleFontFamily, Value};
+    let reparse = |property: &Property| Stylesheet::parse(&format!("p {{ {} }}", property)).unwrap().rules[0].declarations[0].property().clone();
+    let font = Property::FontFamily(Value::Exact(vec![StyleFontFamily::Ref(std::sync::Arc::new(FontData(vec![0; 4])))]));
+    assert_eq!(font.to_string(), "font-family: \"<font data: 4 bytes>\"");
+    assert_eq!(reparse(&font), Property::FontFamily(Value::Exact(vec![StyleFontFamily::System("<font data: 4 bytes>".to_string())])));
+    let scrollbar = Property::ScrollbarStyle(Value::Exact(ScrollbarStyle::default()));
+    assert_eq!(scrollbar.to_string(), "-scrollbar-style: initial");
+    assert_eq!(reparse(&scrollbar), Property::ScrollbarStyle(Value::Initial));
+    let scrollbar = Property::ScrollbarStyle(Value::Inherit);
+    assert_eq!(reparse(&scrollbar), scrollbar);
+}
+
+#[test]
+fn test_collapse_shorthands() {
+    let css = "div { \
+        padding: 5px 10px; \
+        width: 3px; \
+        margin: 1px 2px 3px; \
+        border: 1px solid red; \
+        border-left-width: 2px; \
+        border-radius: 4px; \
+        overflow: hidden scroll; \
+    } \
+    p { \
+        padding: 1px; \
+        padding-left: var(--left, 2px); \
+        margin-top: auto; margin-right: 0px; margin-bottom: auto; margin-left: 0px; \
+        border-top: inherit; \
+    }";
+    let stylesheet = Stylesheet::parse(css).unwrap();
+    let collapsed = stylesheet.collapse_shorthands().to_string();
+    assert_eq!(
+        collapsed,
+        "div{padding: 5px 10px;width: 3px;margin: 1px 2px 3px;\
+        border-top: 1px solid rgba(255, 0, 0, 1);\
+        border-right: 1px solid rgba(255, 0, 0, 1);\
+        border-bottom: 1px solid rgba(255, 0, 0, 1);\
+        border-left-width: 1px;border-left-style: solid;border-left-color: rgba(255, 0, 0, 1);border-left-width: 2px;\
+        border-radius: 4px;overflow: hidden scroll}\
+        p{padding-top: 1px;padding-right: 1px;padding-bottom: 1px;padding-left: 1px;padding-left: var(--left, 2px);\
+        margin: auto 0px;border-top: inherit}"
+    );
+    // collapsing does not change the meaning of the stylesheet
+    assert_eq!(Stylesheet::parse(&collapsed).unwrap(), stylesheet);
+    let border = Stylesheet::parse("div { border: 1px solid red; }").unwrap();
+    assert_eq!(border.collapse_shorthands().to_string(), "div{border: 1px solid rgba(255, 0, 0, 1)}");
+    assert_eq!(
+        format!("{:#}", stylesheet.rules[1].collapse_shorthands()),
+        "p {\n    padding-top: 1px;\n    padding-right: 1px;\n    padding-bottom: 1px;\n    padding-left: 1px;\n    \
+        padding-left: var(--left, 2px);\n    margin: auto 0px;\n    border-top: inherit;\n}"
+    );
+}
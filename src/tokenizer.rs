@@ -0,0 +1,547 @@
+//! CSS tokenizer, following the "Tokenization" chapter of
+//! [CSS Syntax Module Level 3](https://www.w3.org/TR/css-syntax-3/#tokenization).
+
+use std::fmt;
+
+/// One CSS token, as produced by the `Tokenizer`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    /// `width`, `-webkit-scrollbar`, `--my-var`
+    Ident(String),
+    /// `rgba(` - the name of the function, without the opening parenthesis
+    Function(String),
+    /// `@media` - the name of the at-rule, without the `@`
+    AtKeyword(String),
+    /// `#ff0000`, `#my_id` - `is_id` is set if the hash would be a valid identifier
+    Hash { value: String, is_id: bool },
+    /// `"Helvetica"` or `'Helvetica'`, without the quotes
+    QuotedString(String),
+    /// A string that was interrupted by a newline
+    BadString,
+    /// `url(image.png)` - unquoted url, quoted urls are parsed as a `Function`
+    Url(String),
+    /// Malformed unquoted url
+    BadUrl,
+    /// Any other single code point, such as `>`, `.` or `!`
+    Delim(char),
+    /// `5`, `-1.5`, `+3`
+    Number { value: f32, is_integer: bool, has_sign: bool },
+    /// `50%`
+    Percentage { value: f32, has_sign: bool },
+    /// `5px`, `-1.5em`, `2n`
+    Dimension { value: f32, is_integer: bool, has_sign: bool, unit: String },
+    /// One or more whitespace characters
+    Whitespace,
+    /// `<!--`
+    Cdo,
+    /// `-->`
+    Cdc,
+    Colon,
+    Semicolon,
+    Comma,
+    OpenSquare,
+    CloseSquare,
+    OpenParen,
+    CloseParen,
+    OpenCurly,
+    CloseCurly,
+}
+
+impl Token {
+    /// Returns the token that closes the block opened by this token, if any.
+    ///
+    /// Note that `Function` tokens are closed by `)`.
+    pub fn closing_token(&self) -> Option<Token> {
+        match self {
+            Token::Function(_) | Token::OpenParen => Some(Token::CloseParen),
+            Token::OpenSquare => Some(Token::CloseSquare),
+            Token::OpenCurly => Some(Token::CloseCurly),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::Token::*;
+        match self {
+            Ident(i) => write!(f, "{}", i),
+            Function(name) => write!(f, "{}(", name),
+            AtKeyword(a) => write!(f, "@{}", a),
+            Hash { value, .. } => write!(f, "#{}", value),
+            QuotedString(s) => write!(f, "\"{}\"", s),
+            BadString => write!(f, "<bad-string>"),
+            Url(u) => write!(f, "url({})", u),
+            BadUrl => write!(f, "<bad-url>"),
+            Delim(c) => write!(f, "{}", c),
+            Number { value, has_sign, .. } => {
+                if *has_sign && *value >= 0.0 {
+                    write!(f, "+")?;
+                }
+                write!(f, "{}", value)
+            }
+            Percentage { value, has_sign } => {
+                if *has_sign && *value >= 0.0 {
+                    write!(f, "+")?;
+                }
+                write!(f, "{}%", value)
+            }
+            Dimension { value, has_sign, unit, .. } => {
+                if *has_sign && *value >= 0.0 {
+                    write!(f, "+")?;
+                }
+                write!(f, "{}{}", value, unit)
+            }
+            Whitespace => write!(f, " "),
+            Cdo => write!(f, "<!--"),
+            Cdc => write!(f, "-->"),
+            Colon => write!(f, ":"),
+            Semicolon => write!(f, ";"),
+            Comma => write!(f, ","),
+            OpenSquare => write!(f, "["),
+            CloseSquare => write!(f, "]"),
+            OpenParen => write!(f, "("),
+            CloseParen => write!(f, ")"),
+            OpenCurly => write!(f, "{{"),
+            CloseCurly => write!(f, "}}"),
+        }
+    }
+}
+
+/// A token together with the byte range it was parsed from
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedToken {
+    pub token: Token,
+    /// Byte offset of the first character of the token
+    pub start: usize,
+    /// Byte offset one past the last character of the token
+    pub end: usize,
+}
+
+/// Splits a CSS source string into `Token`s. Comments are dropped.
+pub struct Tokenizer<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    /// Tokenizes the entire input
+    pub fn tokenize(input: &'a str) -> Vec<SpannedToken> {
+        Tokenizer::new(input).collect()
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn peek_char_at(&self, n: usize) -> Option<char> {
+        self.input[self.position..].chars().nth(n)
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.peek_char()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        self.input[self.position..].starts_with(s)
+    }
+
+    fn consume_comments(&mut self) {
+        while self.starts_with("/*") {
+            match self.input[self.position + 2..].find("*/") {
+                Some(end) => self.position += 2 + end + 2,
+                None => self.position = self.input.len(),
+            }
+        }
+    }
+
+    fn consume_token(&mut self) -> Option<Token> {
+        self.consume_comments();
+        let c = self.peek_char()?;
+
+        if is_whitespace(c) {
+            while self.peek_char().map(is_whitespace).unwrap_or(false) {
+                self.next_char();
+            }
+            return Some(Token::Whitespace);
+        }
+
+        match c {
+            '"' | '\'' => {
+                self.next_char();
+                Some(self.consume_string(c))
+            }
+            '#' => {
+                self.next_char();
+                if self.peek_char().map(is_name_char).unwrap_or(false) || self.starts_valid_escape(0) {
+                    let is_id = self.starts_identifier(0);
+                    let value = self.consume_name();
+                    Some(Token::Hash { value, is_id })
+                } else {
+                    Some(Token::Delim('#'))
+                }
+            }
+            '(' => { self.next_char(); Some(Token::OpenParen) }
+            ')' => { self.next_char(); Some(Token::CloseParen) }
+            '[' => { self.next_char(); Some(Token::OpenSquare) }
+            ']' => { self.next_char(); Some(Token::CloseSquare) }
+            '{' => { self.next_char(); Some(Token::OpenCurly) }
+            '}' => { self.next_char(); Some(Token::CloseCurly) }
+            ',' => { self.next_char(); Some(Token::Comma) }
+            ':' => { self.next_char(); Some(Token::Colon) }
+            ';' => { self.next_char(); Some(Token::Semicolon) }
+            '+' => {
+                if self.starts_number(0) {
+                    Some(self.consume_numeric())
+                } else {
+                    self.next_char();
+                    Some(Token::Delim('+'))
+                }
+            }
+            '-' => {
+                if self.starts_number(0) {
+                    Some(self.consume_numeric())
+                } else if self.starts_with("-->") {
+                    self.position += 3;
+                    Some(Token::Cdc)
+                } else if self.starts_identifier(0) {
+                    Some(self.consume_ident_like())
+                } else {
+                    self.next_char();
+                    Some(Token::Delim('-'))
+                }
+            }
+            '.' => {
+                if self.starts_number(0) {
+                    Some(self.consume_numeric())
+                } else {
+                    self.next_char();
+                    Some(Token::Delim('.'))
+                }
+            }
+            '<' => {
+                if self.starts_with("<!--") {
+                    self.position += 4;
+                    Some(Token::Cdo)
+                } else {
+                    self.next_char();
+                    Some(Token::Delim('<'))
+                }
+            }
+            '@' => {
+                self.next_char();
+                if self.starts_identifier(0) {
+                    Some(Token::AtKeyword(self.consume_name()))
+                } else {
+                    Some(Token::Delim('@'))
+                }
+            }
+            '\\' => {
+                if self.starts_valid_escape(0) {
+                    Some(self.consume_ident_like())
+                } else {
+                    self.next_char();
+                    Some(Token::Delim('\\'))
+                }
+            }
+            c if c.is_ascii_digit() => Some(self.consume_numeric()),
+            c if is_name_start_char(c) => Some(self.consume_ident_like()),
+            c => {
+                self.next_char();
+                Some(Token::Delim(c))
+            }
+        }
+    }
+
+    /// Checks if the two characters starting at offset `n` are a valid escape
+    fn starts_valid_escape(&self, n: usize) -> bool {
+        self.peek_char_at(n) == Some('\\') && !matches!(self.peek_char_at(n + 1), Some('\n') | None)
+    }
+
+    /// Checks if the three characters starting at offset `n` would start an identifier
+    fn starts_identifier(&self, n: usize) -> bool {
+        match self.peek_char_at(n) {
+            Some('-') => match self.peek_char_at(n + 1) {
+                Some(c) if is_name_start_char(c) || c == '-' => true,
+                _ => self.starts_valid_escape(n + 1),
+            },
+            Some('\\') => self.starts_valid_escape(n),
+            Some(c) => is_name_start_char(c),
+            None => false,
+        }
+    }
+
+    /// Checks if the three characters starting at offset `n` would start a number
+    fn starts_number(&self, n: usize) -> bool {
+        let is_digit = |c: Option<char>| c.map(|c| c.is_ascii_digit()).unwrap_or(false);
+        match self.peek_char_at(n) {
+            Some('+') | Some('-') => match self.peek_char_at(n + 1) {
+                Some('.') => is_digit(self.peek_char_at(n + 2)),
+                c => is_digit(c),
+            },
+            Some('.') => is_digit(self.peek_char_at(n + 1)),
+            c => is_digit(c),
+        }
+    }
+
+    fn consume_escape(&mut self) -> char {
+        // the backslash has already been consumed
+        match self.next_char() {
+            Some(c) if c.is_ascii_hexdigit() => {
+                let mut hex = c.to_string();
+                while hex.len() < 6 && self.peek_char().map(|c| c.is_ascii_hexdigit()).unwrap_or(false) {
+                    hex.push(self.next_char().unwrap());
+                }
+                if self.peek_char().map(is_whitespace).unwrap_or(false) {
+                    self.next_char();
+                }
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(|c| if c == 0 { None } else { char::from_u32(c) })
+                    .unwrap_or('\u{FFFD}')
+            }
+            Some(c) => c,
+            None => '\u{FFFD}',
+        }
+    }
+
+    fn consume_name(&mut self) -> String {
+        let mut name = String::new();
+        loop {
+            match self.peek_char() {
+                Some(c) if is_name_char(c) => {
+                    name.push(c);
+                    self.next_char();
+                }
+                Some('\\') if self.starts_valid_escape(0) => {
+                    self.next_char();
+                    name.push(self.consume_escape());
+                }
+                _ => return name,
+            }
+        }
+    }
+
+    fn consume_number(&mut self) -> (f32, bool, bool) {
+        let start = self.position;
+        let mut is_integer = true;
+        let mut has_sign = false;
+        if let Some('+') | Some('-') = self.peek_char() {
+            has_sign = true;
+            self.next_char();
+        }
+        while self.peek_char().map(|c| c.is_ascii_digit()).unwrap_or(false) {
+            self.next_char();
+        }
+        if self.peek_char() == Some('.') && self.peek_char_at(1).map(|c| c.is_ascii_digit()).unwrap_or(false) {
+            is_integer = false;
+            self.next_char();
+            while self.peek_char().map(|c| c.is_ascii_digit()).unwrap_or(false) {
+                self.next_char();
+            }
+        }
+        if let Some('e') | Some('E') = self.peek_char() {
+            let exponent_follows = match self.peek_char_at(1) {
+                Some('+') | Some('-') => self.peek_char_at(2).map(|c| c.is_ascii_digit()).unwrap_or(false),
+                Some(c) => c.is_ascii_digit(),
+                None => false,
+            };
+            if exponent_follows {
+                is_integer = false;
+                self.next_char();
+                if let Some('+') | Some('-') = self.peek_char() {
+                    self.next_char();
+                }
+                while self.peek_char().map(|c| c.is_ascii_digit()).unwrap_or(false) {
+                    self.next_char();
+                }
+            }
+        }
+        let value = self.input[start..self.position].parse::<f32>().unwrap_or(0.0);
+        (value, is_integer, has_sign)
+    }
+
+    fn consume_numeric(&mut self) -> Token {
+        let (value, is_integer, has_sign) = self.consume_number();
+        if self.starts_identifier(0) {
+            let unit = self.consume_name();
+            Token::Dimension { value, is_integer, has_sign, unit }
+        } else if self.peek_char() == Some('%') {
+            self.next_char();
+            Token::Percentage { value, has_sign }
+        } else {
+            Token::Number { value, is_integer, has_sign }
+        }
+    }
+
+    fn consume_ident_like(&mut self) -> Token {
+        let name = self.consume_name();
+        if name.eq_ignore_ascii_case("url") && self.peek_char() == Some('(') {
+            self.next_char();
+            // skip whitespace to check whether the url is quoted
+            let mut lookahead = 0;
+            while self.peek_char_at(lookahead).map(is_whitespace).unwrap_or(false) {
+                lookahead += 1;
+            }
+            match self.peek_char_at(lookahead) {
+                Some('"') | Some('\'') => Token::Function(name),
+                _ => self.consume_url(),
+            }
+        } else if self.peek_char() == Some('(') {
+            self.next_char();
+            Token::Function(name)
+        } else {
+            Token::Ident(name)
+        }
+    }
+
+    fn consume_string(&mut self, ending: char) -> Token {
+        let mut s = String::new();
+        loop {
+            match self.peek_char() {
+                None => return Token::QuotedString(s),
+                Some(c) if c == ending => {
+                    self.next_char();
+                    return Token::QuotedString(s);
+                }
+                Some('\n') => return Token::BadString,
+                Some('\\') => {
+                    self.next_char();
+                    match self.peek_char() {
+                        None => {}
+                        Some('\n') => {
+                            self.next_char();
+                        }
+                        Some(_) => s.push(self.consume_escape()),
+                    }
+                }
+                Some(c) => {
+                    self.next_char();
+                    s.push(c);
+                }
+            }
+        }
+    }
+
+    fn consume_url(&mut self) -> Token {
+        let mut url = String::new();
+        while self.peek_char().map(is_whitespace).unwrap_or(false) {
+            self.next_char();
+        }
+        loop {
+            match self.next_char() {
+                None | Some(')') => return Token::Url(url),
+                Some(c) if is_whitespace(c) => {
+                    while self.peek_char().map(is_whitespace).unwrap_or(false) {
+                        self.next_char();
+                    }
+                    return match self.next_char() {
+                        None | Some(')') => Token::Url(url),
+                        Some(_) => {
+                            self.consume_bad_url_remnants();
+                            Token::BadUrl
+                        }
+                    };
+                }
+                Some('"') | Some('\'') | Some('(') => {
+                    self.consume_bad_url_remnants();
+                    return Token::BadUrl;
+                }
+                Some('\\') => {
+                    if self.peek_char().map(|c| c != '\n').unwrap_or(false) {
+                        url.push(self.consume_escape());
+                    } else {
+                        self.consume_bad_url_remnants();
+                        return Token::BadUrl;
+                    }
+                }
+                Some(c) => url.push(c),
+            }
+        }
+    }
+
+    fn consume_bad_url_remnants(&mut self) {
+        loop {
+            match self.next_char() {
+                None | Some(')') => return,
+                Some('\\') => {
+                    self.next_char();
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = SpannedToken;
+
+    fn next(&mut self) -> Option<SpannedToken> {
+        self.consume_comments();
+        let start = self.position;
+        let token = self.consume_token()?;
+        Some(SpannedToken { token, start, end: self.position })
+    }
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0C')
+}
+
+fn is_name_start_char(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+fn is_name_char(c: char) -> bool {
+    is_name_start_char(c) || c.is_ascii_digit() || c == '-'
+}
+
+#[test]
+fn test_tokenize_declaration() {
+    let tokens = Tokenizer::tokenize("width: -5.5px /* comment */ !important;")
+        .into_iter()
+        .map(|t| t.token)
+        .collect::<Vec<_>>();
+    assert_eq!(tokens, vec![
+        Token::Ident("width".to_string()),
+        Token::Colon,
+        Token::Whitespace,
+        Token::Dimension { value: -5.5, is_integer: false, has_sign: true, unit: "px".to_string() },
+        Token::Whitespace,
+        Token::Whitespace,
+        Token::Delim('!'),
+        Token::Ident("important".to_string()),
+        Token::Semicolon,
+    ]);
+}
+
+#[test]
+fn test_tokenize_functions_and_urls() {
+    let tokens = Tokenizer::tokenize("url(a.png) url(\"b.png\") #fff rgba(0,0,0,50%)")
+        .into_iter()
+        .map(|t| t.token)
+        .filter(|t| *t != Token::Whitespace)
+        .collect::<Vec<_>>();
+    assert_eq!(tokens, vec![
+        Token::Url("a.png".to_string()),
+        Token::Function("url".to_string()),
+        Token::QuotedString("b.png".to_string()),
+        Token::CloseParen,
+        Token::Hash { value: "fff".to_string(), is_id: true },
+        Token::Function("rgba".to_string()),
+        Token::Number { value: 0.0, is_integer: true, has_sign: false },
+        Token::Comma,
+        Token::Number { value: 0.0, is_integer: true, has_sign: false },
+        Token::Comma,
+        Token::Number { value: 0.0, is_integer: true, has_sign: false },
+        Token::Comma,
+        Token::Percentage { value: 50.0, has_sign: false },
+        Token::CloseParen,
+    ]);
+}
@@ -0,0 +1,239 @@
+//! CSS transitions: animating changes of the computed style with the `transition-*` properties
+
+use std::collections::BTreeMap;
+
+use crate::animation::nth_item;
+use crate::cascade::ComputedStyle;
+use crate::property::*;
+
+/// A running transition of one property from `start_value` to `end_value`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    pub property_type: PType,
+    /// Value at the start of the transition (and during the delay)
+    pub start_value: Property,
+    /// Value at the end of the transition, the computed value of the new style
+    pub end_value: Property,
+    /// Time (in milliseconds) at which the style changed
+    pub start_time: f32,
+    /// Delay in milliseconds after `start_time` before the value starts to change, can be negative
+    pub delay: f32,
+    /// Duration in milliseconds, shorter than the `transition-duration` if this transition
+    /// reverses another one, see `reversing_shortening_factor`
+    pub duration: f32,
+    pub timing_function: TimingFunction,
+    /// The value the transition can be reversed to: the end value of the transition that this
+    /// one reversed, otherwise the start value
+    pub reversing_adjusted_start_value: Property,
+    /// How far the reversed transition got (0 to 1), 1 if this transition didn't reverse another one.
+    /// Going back only takes as long as it took to get to the current value.
+    pub reversing_shortening_factor: f32,
+}
+
+impl Transition {
+    /// Returns the progress (`0` is the start value, `1` the end value) at `time`,
+    /// after applying the timing function
+    pub fn progress(&self, time: f32) -> f32 {
+        let elapsed = time - self.start_time - self.delay;
+        let progress = if self.duration <= 0.0 {
+            if elapsed >= 0.0 { 1.0 } else { 0.0 }
+        } else {
+            (elapsed / self.duration).clamp(0.0, 1.0)
+        };
+        self.timing_function.apply(progress)
+    }
+
+    /// Returns the value of the property at `time`
+    pub fn value_at(&self, time: f32) -> Property {
+        self.start_value.interpolate(&self.end_value, self.progress(time)).unwrap_or_else(|| self.end_value.clone())
+    }
+
+    /// Returns whether the transition has reached its end value at `time`
+    pub fn is_finished(&self, time: f32) -> bool {
+        time >= self.start_time + self.delay + self.duration
+    }
+}
+
+/// Runs the transitions of one element: compares the computed styles before and after each
+/// style change and starts, retargets or reverses the transitions of the changed properties.
+///
+/// ```rust,ignore
+/// let mut transitions = TransitionEngine::new();
+/// // on every style change (i.e. on hover):
+/// let values = transitions.update(&old_style, &new_style, now);
+/// // on every frame until `!transitions.is_running()`:
+/// let values = transitions.sample(now);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TransitionEngine {
+    transitions: BTreeMap<PType, Transition>,
+}
+
+impl TransitionEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the running transitions
+    pub fn transitions(&self) -> impl Iterator<Item = &Transition> {
+        self.transitions.values()
+    }
+
+    /// Returns whether any transition is still running (as of the last `update` or `sample`)
+    pub fn is_running(&self) -> bool {
+        !self.transitions.is_empty()
+    }
+
+    /// Updates the transitions after the computed style of the element changed from `old_style`
+    /// to `new_style` at `time` (in milliseconds), then returns the current values, see `sample`.
+    /// Both styles are the styles without transitions, `old_style` is the `new_style` of the last
+    /// update. The `transition-*` properties of the new style apply, for each changed property:
+    ///
+    /// - if no transition is running, a transition from the old to the new value starts
+    /// - if a running transition already ends at the new value, it continues unchanged
+    /// - if the new value is the value a running transition started from (i.e. the mouse leaves
+    ///   an element before its hover transition finished), the transition reverses: it goes back
+    ///   from the current value, taking only as long as the running transition took so far
+    /// - otherwise the running transition is retargeted: a new transition starts from its current value
+    ///
+    /// Running transitions of properties that aren't transitioned by the new style (or whose
+    /// value can't be interpolated) are cancelled, the property jumps to its new value.
+    pub fn update(&mut self, old_style: &ComputedStyle, new_style: &ComputedStyle, time: f32) -> Vec<Property> {
+        self.transitions.retain(|_, t| !t.is_finished(time));
+        let settings = transition_settings(new_style);
+
+        for property_type in PType::ALL {
+            let after = new_style.get(property_type);
+            let running = self.transitions.get(&property_type);
+            let current = match running {
+                Some(transition) => transition.value_at(time),
+                None => old_style.get(property_type).clone(),
+            };
+            let setting = settings.get(&property_type).filter(|(duration, delay, _)| duration.max(0.0) + delay > 0.0);
+            let can_transition = current != *after && current.can_interpolate(after);
+
+            let (duration, delay, timing_function) = match (running, setting) {
+                // continues towards the same value
+                (Some(transition), _) if transition.end_value == *after => continue,
+                (_, Some(setting)) if can_transition => setting.clone(),
+                _ => {
+                    self.transitions.remove(&property_type);
+                    continue;
+                }
+            };
+
+            let transition = match running {
+                Some(running) if running.reversing_adjusted_start_value == *after => {
+                    let factor = running.progress(time) * running.reversing_shortening_factor + (1.0 - running.reversing_shortening_factor);
+                    let factor = factor.abs().clamp(0.0, 1.0);
+                    Transition {
+                        property_type,
+                        start_value: current,
+                        end_value: after.clone(),
+                        start_time: time,
+                        delay: if delay < 0.0 { delay * factor } else { delay },
+                        duration: duration * factor,
+                        timing_function,
+                        reversing_adjusted_start_value: running.end_value.clone(),
+                        reversing_shortening_factor: factor,
+                    }
+                }
+                _ => Transition {
+                    property_type,
+                    start_value: current.clone(),
+                    end_value: after.clone(),
+                    start_time: time,
+                    delay,
+                    duration,
+                    timing_function,
+                    reversing_adjusted_start_value: current,
+                    reversing_shortening_factor: 1.0,
+                },
+            };
+            self.transitions.insert(property_type, transition);
+        }
+
+        self.sample(time)
+    }
+
+    /// Returns the values of the properties with a running transition at `time` (in milliseconds),
+    /// in the order of `PType::ALL`. Finished transitions are removed: the property has its
+    /// value of the new style again.
+    pub fn sample(&mut self, time: f32) -> Vec<Property> {
+        self.transitions.retain(|_, t| !t.is_finished(time));
+        self.transitions.values().map(|t| t.value_at(time)).collect()
+    }
+}
+
+/// Returns the duration, delay (in milliseconds) and timing function of the transitioned
+/// properties of the style. A property listed more than once uses the last occurrence.
+fn transition_settings(style: &ComputedStyle) -> BTreeMap<PType, (f32, f32, TimingFunction)> {
+    let mut settings = BTreeMap::new();
+    let items = match style.get(PType::TransitionProperty) {
+        Property::TransitionProperty(Value::Exact(items)) => items,
+        _ => return settings,
+    };
+
+    for (i, item) in items.iter().enumerate() {
+        let duration = match style.get(PType::TransitionDuration) {
+            Property::TransitionDuration(v) => nth_item(v, i).get_ms(),
+            _ => 0.0,
+        };
+        let delay = match style.get(PType::TransitionDelay) {
+            Property::TransitionDelay(v) => nth_item(v, i).get_ms(),
+            _ => 0.0,
+        };
+        let timing_function = match style.get(PType::TransitionTimingFunction) {
+            Property::TransitionTimingFunction(v) => nth_item(v, i),
+            _ => TimingFunction::default(),
+        };
+        for property_type in PType::ALL.into_iter().filter(|p| item.contains(*p)) {
+            settings.insert(property_type, (duration, delay, timing_function.clone()));
+        }
+    }
+    settings
+}
+
+#[test]
+fn test_transitions() {
+    use crate::matching::{ElementState, TestElement, TestNode};
+    use crate::sheet::{NodeTypeTag, Stylesheet};
+
+    let stylesheet = Stylesheet::parse(
+        "div { width: 0px; height: 0px; color: #000000; display: block; \
+            transition-property: width, color, display; transition-duration: 1s, 500ms; transition-timing-function: linear; } \
+        div:hover { width: 100px; height: 10px; color: #ffffff; display: flex; } \
+        div:active { width: 50px; }",
+    )
+    .unwrap();
+    let nodes: &[TestNode] = &[(NodeTypeTag::Div, None, &[], &[], None)];
+    let style = |state: ElementState| {
+        let states = [(0, state)];
+        ComputedStyle::compute(std::slice::from_ref(&stylesheet), &TestElement { nodes, index: 0, states: &states }, None)
+    };
+    let (normal, hover, active) = (style(ElementState::default()), style(ElementState::HOVER), style(ElementState::HOVER | ElementState::ACTIVE));
+    let strings = |properties: Vec<Property>| properties.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+
+    let mut engine = TransitionEngine::new();
+    assert_eq!(engine.update(&normal, &normal, 0.0), Vec::new());
+    // height isn't transitioned, display can't be interpolated
+    assert_eq!(strings(engine.update(&normal, &hover, 1000.0)), vec!["color: rgba(0, 0, 0, 1)", "width: 0px"]);
+    assert_eq!(strings(engine.sample(1250.0)), vec!["color: rgba(128, 128, 128, 1)", "width: 25px"]);
+    assert_eq!(strings(engine.sample(1600.0)), vec!["width: 60px"]);
+
+    // reversed halfway: going back takes half as long
+    // (the color transition just finished, so it transitions back with the full duration)
+    assert_eq!(strings(engine.update(&hover, &normal, 1500.0)), vec!["color: rgba(255, 255, 255, 1)", "width: 50px"]);
+    assert_eq!(engine.transitions().find(|t| t.property_type == PType::Width).map(|t| t.duration), Some(500.0));
+    assert_eq!(strings(engine.sample(1750.0)), vec!["color: rgba(128, 128, 128, 1)", "width: 25px"]);
+    assert_eq!(strings(engine.sample(2000.0)), Vec::<String>::new());
+    assert!(!engine.is_running());
+
+    // retargeted: starts from the current value with the full duration
+    engine.update(&normal, &hover, 3000.0);
+    assert_eq!(strings(engine.update(&hover, &active, 3200.0)), vec!["color: rgba(102, 102, 102, 1)", "width: 20px"]);
+    assert_eq!(strings(engine.sample(3700.0)), vec!["width: 35px"]);
+    // changing back to the value it is transitioning to doesn't restart the transition
+    assert_eq!(strings(engine.update(&active, &active, 3700.0)), vec!["width: 35px"]);
+    assert_eq!(strings(engine.sample(4200.0)), Vec::<String>::new());
+}